@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::database::{self, DbPool};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseBackupResult {
+    pub success: bool,
+    pub path: Option<String>,
+    pub size_bytes: Option<u64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseRestoreResult {
+    pub success: bool,
+    pub restart_required: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseStatus {
+    pub available: bool,
+    pub path: String,
+    pub error: Option<String>,  // Why the database failed to open at startup
+}
+
+/// Result of opening the database at startup, managed so it can be reported later
+pub struct DatabaseInitStatus {
+    pub error: Option<String>,
+}
+
+/// First 16 bytes of every SQLite 3 database file
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Error returned by commands that need the database when it failed to open
+pub const DATABASE_UNAVAILABLE: &str = "Database unavailable; changes won't be saved";
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Report whether the database opened at startup, and why not if it didn't.
+/// Without it settings and instances can't be loaded or saved.
+#[tauri::command]
+pub fn get_database_status(app: AppHandle) -> DatabaseStatus {
+    let available = app.try_state::<DbPool>().is_some();
+    // Init runs asynchronously at startup, so no status yet means it's still opening
+    let error = app
+        .try_state::<DatabaseInitStatus>()
+        .and_then(|status| status.error.clone());
+
+    DatabaseStatus {
+        available,
+        path: database::get_db_path(&app).to_string_lossy().to_string(),
+        error,
+    }
+}
+
+/// Write a consistent copy of the app database.
+/// `destination` may be a file path or an existing directory, in which case a
+/// timestamped file name is used. Safe to call while the app is running.
+#[tauri::command]
+pub async fn backup_database(app: AppHandle, destination: String) -> DatabaseBackupResult {
+    let fail = |error: String| DatabaseBackupResult {
+        success: false,
+        path: None,
+        size_bytes: None,
+        error: Some(error),
+    };
+
+    let pool = match app.try_state::<DbPool>() {
+        Some(p) => p.inner().clone(),
+        None => return fail(DATABASE_UNAVAILABLE.to_string()),
+    };
+
+    let mut target = PathBuf::from(&destination);
+    if target.is_dir() {
+        let timestamp = chrono::Utc::now().format("%Y%m%d-%H%M%S");
+        target = target.join(format!("hypanel-backup-{}.db", timestamp));
+    }
+
+    if target.exists() {
+        return fail(format!("File already exists: {}", target.display()));
+    }
+
+    if let Some(parent) = target.parent() {
+        if !parent.as_os_str().is_empty() && !parent.exists() {
+            return fail(format!("Directory does not exist: {}", parent.display()));
+        }
+    }
+
+    println!("[backup_database] Backing up database to: {}", target.display());
+
+    if let Err(e) = database::backup_to(&pool, &target).await {
+        println!("[backup_database] Error: {}", e);
+        return fail(format!("Failed to back up database: {}", e));
+    }
+
+    let size_bytes = std::fs::metadata(&target).map(|m| m.len()).ok();
+
+    DatabaseBackupResult {
+        success: true,
+        path: Some(target.to_string_lossy().to_string()),
+        size_bytes,
+        error: None,
+    }
+}
+
+/// Stage a backup to replace the app database.
+/// The swap happens on the next launch, so the app must be restarted.
+#[tauri::command]
+pub async fn restore_database(app: AppHandle, source: String) -> DatabaseRestoreResult {
+    let fail = |error: String| DatabaseRestoreResult {
+        success: false,
+        restart_required: false,
+        error: Some(error),
+    };
+
+    let source_path = Path::new(&source);
+    if !source_path.is_file() {
+        return fail(format!("Backup file not found: {}", source));
+    }
+
+    if !is_sqlite_file(source_path) {
+        return fail("Selected file is not a SQLite database".to_string());
+    }
+
+    if let Err(e) = validate_backup(source_path).await {
+        return fail(e);
+    }
+
+    let db_path = database::get_db_path(&app);
+    let pending = database::pending_restore_path(&db_path);
+
+    println!("[restore_database] Staging restore from: {}", source);
+
+    if let Err(e) = std::fs::copy(source_path, &pending) {
+        return fail(format!("Failed to stage backup: {}", e));
+    }
+
+    DatabaseRestoreResult {
+        success: true,
+        restart_required: true,
+        error: None,
+    }
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+fn is_sqlite_file(path: &Path) -> bool {
+    use std::io::Read;
+
+    let mut header = [0u8; 16];
+    std::fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut header))
+        .map(|_| &header == SQLITE_HEADER)
+        .unwrap_or(false)
+}
+
+/// Open the backup read-only and make sure it is intact and looks like ours
+async fn validate_backup(path: &Path) -> Result<(), String> {
+    use sqlx::Connection;
+
+    let url = format!("sqlite:{}?mode=ro", path.display());
+    let mut conn = sqlx::SqliteConnection::connect(&url)
+        .await
+        .map_err(|e| format!("Failed to open backup: {}", e))?;
+
+    let integrity: (String,) = sqlx::query_as("PRAGMA integrity_check")
+        .fetch_one(&mut conn)
+        .await
+        .map_err(|e| format!("Failed to check backup integrity: {}", e))?;
+
+    if integrity.0 != "ok" {
+        return Err(format!("Backup is corrupted: {}", integrity.0));
+    }
+
+    let has_instances: Option<(String,)> =
+        sqlx::query_as("SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'instances'")
+            .fetch_optional(&mut conn)
+            .await
+            .map_err(|e| format!("Failed to read backup: {}", e))?;
+
+    let _ = conn.close().await;
+
+    if has_instances.is_none() {
+        return Err("Backup is not a HyPanel database".to_string());
+    }
+
+    Ok(())
+}
@@ -3,6 +3,9 @@ use serde_json::Value;
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
+use tauri::{AppHandle, Emitter};
+
+use super::errors::ErrorCode;
 
 // ============================================================================
 // Types - Generic JSON
@@ -20,6 +23,7 @@ pub struct JsonReadResult {
 pub struct JsonWriteResult {
     pub success: bool,
     pub error: Option<String>,
+    pub error_code: Option<ErrorCode>,
 }
 
 // ============================================================================
@@ -120,6 +124,15 @@ pub struct ServerConfig {
     pub extra: HashMap<String, Value>,
 }
 
+/// Event emitted when saving config.json changes the server port,
+/// so stale firewall rules for the old port can be replaced
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerPortChangedEvent {
+    pub instance_path: String,
+    pub old_port: u16,
+    pub new_port: u16,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerConfigResult {
     pub success: bool,
@@ -184,6 +197,7 @@ pub fn write_json_file(file_path: String, content: Value) -> JsonWriteResult {
                 return JsonWriteResult {
                     success: false,
                     error: Some(format!("Failed to create directory: {}", e)),
+                    error_code: Some(ErrorCode::from_io(&e)),
                 };
             }
         }
@@ -196,6 +210,7 @@ pub fn write_json_file(file_path: String, content: Value) -> JsonWriteResult {
             return JsonWriteResult {
                 success: false,
                 error: Some(format!("Failed to serialize JSON: {}", e)),
+                error_code: Some(ErrorCode::InvalidConfig),
             };
         }
     };
@@ -204,10 +219,12 @@ pub fn write_json_file(file_path: String, content: Value) -> JsonWriteResult {
         Ok(()) => JsonWriteResult {
             success: true,
             error: None,
+            error_code: None,
         },
         Err(e) => JsonWriteResult {
             success: false,
             error: Some(format!("Failed to write file: {}", e)),
+            error_code: Some(ErrorCode::from_io(&e)),
         },
     }
 }
@@ -220,6 +237,7 @@ pub fn write_json_file_raw(file_path: String, content: String) -> JsonWriteResul
         return JsonWriteResult {
             success: false,
             error: Some(format!("Invalid JSON: {}", e)),
+            error_code: Some(ErrorCode::InvalidConfig),
         };
     }
 
@@ -232,6 +250,7 @@ pub fn write_json_file_raw(file_path: String, content: String) -> JsonWriteResul
                 return JsonWriteResult {
                     success: false,
                     error: Some(format!("Failed to create directory: {}", e)),
+                    error_code: Some(ErrorCode::from_io(&e)),
                 };
             }
         }
@@ -241,10 +260,12 @@ pub fn write_json_file_raw(file_path: String, content: String) -> JsonWriteResul
         Ok(()) => JsonWriteResult {
             success: true,
             error: None,
+            error_code: None,
         },
         Err(e) => JsonWriteResult {
             success: false,
             error: Some(format!("Failed to write file: {}", e)),
+            error_code: Some(ErrorCode::from_io(&e)),
         },
     }
 }
@@ -304,6 +325,7 @@ pub fn save_whitelist(instance_path: String, whitelist: Whitelist) -> JsonWriteR
             return JsonWriteResult {
                 success: false,
                 error: Some(format!("Failed to serialize whitelist: {}", e)),
+                error_code: Some(ErrorCode::InvalidConfig),
             };
         }
     };
@@ -312,10 +334,12 @@ pub fn save_whitelist(instance_path: String, whitelist: Whitelist) -> JsonWriteR
         Ok(()) => JsonWriteResult {
             success: true,
             error: None,
+            error_code: None,
         },
         Err(e) => JsonWriteResult {
             success: false,
             error: Some(format!("Failed to write whitelist.json: {}", e)),
+            error_code: Some(ErrorCode::from_io(&e)),
         },
     }
 }
@@ -372,6 +396,7 @@ pub fn save_bans(instance_path: String, bans: Vec<Ban>) -> JsonWriteResult {
             return JsonWriteResult {
                 success: false,
                 error: Some(format!("Failed to serialize bans: {}", e)),
+                error_code: Some(ErrorCode::InvalidConfig),
             };
         }
     };
@@ -380,10 +405,12 @@ pub fn save_bans(instance_path: String, bans: Vec<Ban>) -> JsonWriteResult {
         Ok(()) => JsonWriteResult {
             success: true,
             error: None,
+            error_code: None,
         },
         Err(e) => JsonWriteResult {
             success: false,
             error: Some(format!("Failed to write bans.json: {}", e)),
+            error_code: Some(ErrorCode::from_io(&e)),
         },
     }
 }
@@ -447,6 +474,7 @@ pub fn save_permissions(instance_path: String, permissions: Permissions) -> Json
             return JsonWriteResult {
                 success: false,
                 error: Some(format!("Failed to serialize permissions: {}", e)),
+                error_code: Some(ErrorCode::InvalidConfig),
             };
         }
     };
@@ -455,10 +483,12 @@ pub fn save_permissions(instance_path: String, permissions: Permissions) -> Json
         Ok(()) => JsonWriteResult {
             success: true,
             error: None,
+            error_code: None,
         },
         Err(e) => JsonWriteResult {
             success: false,
             error: Some(format!("Failed to write permissions.json: {}", e)),
+            error_code: Some(ErrorCode::from_io(&e)),
         },
     }
 }
@@ -509,27 +539,231 @@ pub fn get_server_config(instance_path: String) -> ServerConfigResult {
 
 /// Save server config to instance
 #[tauri::command]
-pub fn save_server_config(instance_path: String, config: ServerConfig) -> JsonWriteResult {
+pub fn save_server_config(app: AppHandle, instance_path: String, config: ServerConfig) -> JsonWriteResult {
     let path = Path::new(&instance_path).join("Server").join("config.json");
 
+    // Read before overwriting so a port change can be reported
+    let old_port = read_config_port(&instance_path).unwrap_or(DEFAULT_SERVER_PORT);
+    let new_port = config_port(&config).unwrap_or(DEFAULT_SERVER_PORT);
+
     let formatted = match serde_json::to_string_pretty(&config) {
         Ok(s) => s,
         Err(e) => {
             return JsonWriteResult {
                 success: false,
                 error: Some(format!("Failed to serialize config: {}", e)),
+                error_code: Some(ErrorCode::InvalidConfig),
             };
         }
     };
 
     match fs::write(path, formatted) {
-        Ok(()) => JsonWriteResult {
-            success: true,
-            error: None,
+        Ok(()) => {
+            report_port_change(&app, instance_path, old_port, new_port);
+            JsonWriteResult {
+                success: true,
+                error: None,
+                error_code: None,
+            }
+        }
+        Err(e) => JsonWriteResult {
+            success: false,
+            error: Some(format!("Failed to write config.json: {}", e)),
+            error_code: Some(ErrorCode::from_io(&e)),
         },
+    }
+}
+
+/// Save server config.json exactly as given, keeping the user's formatting and key order.
+/// The content must still parse as a server config.
+#[tauri::command]
+pub fn save_server_config_raw(app: AppHandle, instance_path: String, raw: String) -> JsonWriteResult {
+    let path = Path::new(&instance_path).join("Server").join("config.json");
+
+    let config = match serde_json::from_str::<ServerConfig>(&raw) {
+        Ok(config) => config,
+        Err(e) => {
+            return JsonWriteResult {
+                success: false,
+                error: Some(format!("Invalid server config: {}", e)),
+                error_code: Some(ErrorCode::InvalidConfig),
+            };
+        }
+    };
+
+    let old_port = read_config_port(&instance_path).unwrap_or(DEFAULT_SERVER_PORT);
+    let new_port = config_port(&config).unwrap_or(DEFAULT_SERVER_PORT);
+
+    match write_file_atomic(&path, &raw) {
+        Ok(()) => {
+            report_port_change(&app, instance_path, old_port, new_port);
+            JsonWriteResult {
+                success: true,
+                error: None,
+                error_code: None,
+            }
+        }
         Err(e) => JsonWriteResult {
             success: false,
             error: Some(format!("Failed to write config.json: {}", e)),
+            error_code: Some(ErrorCode::from_io(&e)),
+        },
+    }
+}
+
+/// Emit `server-port-changed` if a config save moved the server to another port
+fn report_port_change(app: &AppHandle, instance_path: String, old_port: u16, new_port: u16) {
+    if old_port == new_port {
+        return;
+    }
+
+    println!(
+        "[save_server_config] Port changed from {} to {} for {}",
+        old_port, new_port, instance_path
+    );
+    let _ = app.emit(
+        "server-port-changed",
+        ServerPortChangedEvent {
+            instance_path,
+            old_port,
+            new_port,
+        },
+    );
+}
+
+/// Get the world the server loads by default (`Defaults.World` in config.json)
+#[tauri::command]
+pub fn get_default_world(instance_path: String) -> Option<String> {
+    read_default_world(Path::new(&instance_path))
+}
+
+/// Switch the world the server loads by default.
+/// Only `Defaults.World` is changed; the rest of config.json is left as-is.
+/// The world must exist under `Server/universe/worlds`.
+#[tauri::command]
+pub fn set_default_world(instance_path: String, world_name: String) -> ServerConfigResult {
+    let fail = |error: String| ServerConfigResult {
+        success: false,
+        config: None,
+        raw: None,
+        error: Some(error),
+    };
+
+    let server_dir = Path::new(&instance_path).join("Server");
+    let world_name = world_name.trim();
+
+    if world_name.is_empty() || world_name.contains(['/', '\\']) || world_name == ".." {
+        return fail(format!("Invalid world name: {}", world_name));
+    }
+
+    if !server_dir.join("universe").join("worlds").join(world_name).is_dir() {
+        return fail(format!("World '{}' does not exist", world_name));
+    }
+
+    let path = server_dir.join("config.json");
+    let content = match fs::read_to_string(&path) {
+        Ok(c) => c,
+        Err(e) => return fail(format!("Failed to read config.json: {}", e)),
+    };
+
+    // Edit the raw JSON so fields we don't model are preserved exactly
+    let mut json = match serde_json::from_str::<Value>(&content) {
+        Ok(json) if json.is_object() => json,
+        Ok(_) => return fail("config.json is not a JSON object".to_string()),
+        Err(e) => return fail(format!("Failed to parse config.json: {}", e)),
+    };
+
+    let defaults = json
+        .as_object_mut()
+        .map(|root| root.entry("Defaults").or_insert_with(|| Value::Object(Default::default())));
+    match defaults.and_then(|d| d.as_object_mut()) {
+        Some(defaults) => {
+            defaults.insert("World".to_string(), Value::String(world_name.to_string()));
+        }
+        None => return fail("config.json has an invalid Defaults section".to_string()),
+    }
+
+    let formatted = match serde_json::to_string_pretty(&json) {
+        Ok(s) => s,
+        Err(e) => return fail(format!("Failed to serialize config: {}", e)),
+    };
+
+    if let Err(e) = write_file_atomic(&path, &formatted) {
+        return fail(format!("Failed to write config.json: {}", e));
+    }
+
+    println!("[set_default_world] Default world set to '{}' for {}", world_name, instance_path);
+
+    match serde_json::from_value::<ServerConfig>(json) {
+        Ok(config) => ServerConfigResult {
+            success: true,
+            config: Some(config),
+            raw: Some(formatted),
+            error: None,
+        },
+        // Saved fine, but the rest of the file doesn't match the typed config
+        Err(e) => ServerConfigResult {
+            success: true,
+            config: None,
+            raw: Some(formatted),
+            error: Some(format!("Saved, but config.json could not be parsed: {}", e)),
         },
     }
 }
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Read `Defaults.World` from an instance's Server/config.json
+pub fn read_default_world(instance_path: &Path) -> Option<String> {
+    let content = fs::read_to_string(instance_path.join("Server").join("config.json")).ok()?;
+    let config = serde_json::from_str::<Value>(&content).ok()?;
+    config
+        .get("Defaults")?
+        .get("World")?
+        .as_str()
+        .map(String::from)
+}
+
+/// Write a file by writing a sibling temp file and renaming it over the target,
+/// so a crash mid-write never leaves a truncated file
+pub(crate) fn write_file_atomic(path: &Path, content: &str) -> std::io::Result<()> {
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+
+    fs::write(&temp_path, content)?;
+    fs::rename(&temp_path, path).inspect_err(|_| {
+        let _ = fs::remove_file(&temp_path);
+    })
+}
+
+/// Default UDP port used by the Hytale server
+pub const DEFAULT_SERVER_PORT: u16 = 5520;
+
+/// Read the server port from the instance's config.json, if one is configured.
+/// Looks for a "Port" key or a "Bind"/"Address" value like "0.0.0.0:5520".
+pub fn read_config_port(instance_path: &str) -> Option<u16> {
+    let path = Path::new(instance_path).join("Server").join("config.json");
+    let content = fs::read_to_string(path).ok()?;
+    let config = serde_json::from_str::<ServerConfig>(&content).ok()?;
+    config_port(&config)
+}
+
+/// The port a parsed config.json sets, if any
+fn config_port(config: &ServerConfig) -> Option<u16> {
+    if let Some(port) = config.extra.get("Port").and_then(|v| v.as_u64()) {
+        return u16::try_from(port).ok();
+    }
+
+    ["Bind", "Address"]
+        .iter()
+        .filter_map(|key| config.extra.get(*key).and_then(|v| v.as_str()))
+        .find_map(parse_bind_port)
+}
+
+/// Extract the port from a bind address like "0.0.0.0:5520" or "[::]:5520"
+pub fn parse_bind_port(bind: &str) -> Option<u16> {
+    bind.rsplit(':').next()?.trim().parse::<u16>().ok()
+}
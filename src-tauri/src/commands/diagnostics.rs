@@ -0,0 +1,344 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+use super::network::is_udp_port_available;
+use super::server::{resolve_bind_address, ServerState};
+use super::system::{available_disk_space, check_java_executable, REQUIRED_JAVA_MAJOR};
+use crate::database::{self, DbPool};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Ordered from best to worst, so the max of a set is its overall status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticCheck {
+    pub id: String,
+    pub label: String,
+    pub status: CheckStatus,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticsReport {
+    pub success: bool,
+    pub instance_id: String,
+    pub checks: Vec<DiagnosticCheck>,
+    /// Worst status across all checks (None when the instance couldn't be loaded)
+    pub overall: Option<CheckStatus>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathCheck {
+    pub path: String,
+    pub exists: bool,
+}
+
+/// Expected on-disk layout of an instance, resolved to absolute paths
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstancePaths {
+    pub root: PathCheck,
+    pub server_dir: PathCheck,
+    pub server_jar: PathCheck,
+    pub assets: PathCheck,
+    pub universe: PathCheck,
+    pub logs: PathCheck,
+    /// Display names of the expected paths that don't exist
+    pub missing: Vec<String>,
+}
+
+/// Free space below this is a warning (Assets.zip alone is several GB)
+const DISK_WARN_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Free space below this will almost certainly break world saves
+const DISK_FAIL_BYTES: u64 = 512 * 1024 * 1024;
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Run pre-flight checks for an instance and report pass/warn/fail for each.
+/// Covers server files, Java, config.json, the server port, free disk and auth.
+#[tauri::command]
+pub async fn diagnose_instance(
+    pool: State<'_, DbPool>,
+    state: State<'_, Arc<Mutex<ServerState>>>,
+    instance_id: String,
+) -> Result<DiagnosticsReport, ()> {
+    let fail = |error: String| DiagnosticsReport {
+        success: false,
+        instance_id: instance_id.clone(),
+        checks: Vec::new(),
+        overall: None,
+        error: Some(error),
+    };
+
+    let instance = match database::get_instance_by_id(&pool, &instance_id).await {
+        Ok(Some(instance)) => instance,
+        Ok(None) => return Ok(fail("Instance not found".to_string())),
+        Err(e) => return Ok(fail(format!("Failed to load instance: {}", e))),
+    };
+
+    let is_running = state.lock().unwrap().is_running(&instance_id);
+
+    let instance_path = instance.path.clone();
+    let java_path = instance.java_path.clone();
+    let server_args = instance.server_args.clone();
+
+    // Java and disk probes block, so run the whole battery off the async runtime
+    let mut checks = match tokio::task::spawn_blocking(move || {
+        let base = Path::new(&instance_path);
+        vec![
+            check_server_jar(base),
+            check_assets(base),
+            check_java(java_path.as_deref()),
+            check_config(base),
+            check_port(&instance_path, server_args.as_deref(), is_running),
+            check_disk_space(base),
+        ]
+    })
+    .await
+    {
+        Ok(checks) => checks,
+        Err(e) => return Ok(fail(format!("Diagnostics failed: {}", e))),
+    };
+
+    checks.push(check_auth(instance.auth_status.as_deref()));
+
+    let overall = checks.iter().map(|c| c.status).max();
+
+    println!(
+        "[diagnose_instance] {}: {:?}",
+        instance_id,
+        overall.unwrap_or(CheckStatus::Pass)
+    );
+
+    Ok(DiagnosticsReport {
+        success: true,
+        instance_id,
+        checks,
+        overall,
+        error: None,
+    })
+}
+
+// ============================================================================
+// Checks
+// ============================================================================
+
+/// Resolve and stat each expected path of an instance
+pub fn resolve_instance_paths(instance_path: &Path) -> InstancePaths {
+    let root = instance_path
+        .canonicalize()
+        .or_else(|_| std::path::absolute(instance_path))
+        .unwrap_or_else(|_| instance_path.to_path_buf());
+    let server_dir = root.join("Server");
+
+    let mut missing = Vec::new();
+    let mut probe = |name: &str, path: PathBuf| {
+        let exists = path.exists();
+        if !exists {
+            missing.push(name.to_string());
+        }
+        PathCheck {
+            path: path.to_string_lossy().to_string(),
+            exists,
+        }
+    };
+
+    let root_check = probe("Instance directory", root.clone());
+    let server_dir_check = probe("Server", server_dir.clone());
+    let server_jar = probe("HytaleServer.jar", server_dir.join("HytaleServer.jar"));
+    let assets = probe("Assets.zip", root.join("Assets.zip"));
+    let universe = probe("universe", server_dir.join("universe"));
+    let logs = probe("logs", server_dir.join("logs"));
+
+    InstancePaths {
+        root: root_check,
+        server_dir: server_dir_check,
+        server_jar,
+        assets,
+        universe,
+        logs,
+        missing,
+    }
+}
+
+fn check(id: &str, label: &str, status: CheckStatus, message: impl Into<String>) -> DiagnosticCheck {
+    DiagnosticCheck {
+        id: id.to_string(),
+        label: label.to_string(),
+        status,
+        message: message.into(),
+    }
+}
+
+fn check_server_jar(base: &Path) -> DiagnosticCheck {
+    let jar = base.join("Server").join("HytaleServer.jar");
+    if jar.is_file() {
+        check("server_jar", "Server JAR", CheckStatus::Pass, "HytaleServer.jar found")
+    } else {
+        check(
+            "server_jar",
+            "Server JAR",
+            CheckStatus::Fail,
+            format!("Not found: {}", jar.display()),
+        )
+    }
+}
+
+fn check_assets(base: &Path) -> DiagnosticCheck {
+    let assets = base.join("Assets.zip");
+    if assets.is_file() {
+        check("assets", "Assets", CheckStatus::Pass, "Assets.zip found")
+    } else {
+        check(
+            "assets",
+            "Assets",
+            CheckStatus::Fail,
+            format!("Not found: {}", assets.display()),
+        )
+    }
+}
+
+fn check_java(java_path: Option<&str>) -> DiagnosticCheck {
+    let java_exe = java_path.filter(|p| !p.trim().is_empty()).unwrap_or("java");
+
+    match check_java_executable(java_exe) {
+        Some(info) if info.is_valid => check(
+            "java",
+            "Java",
+            CheckStatus::Pass,
+            format!("Java {} ({})", info.version.unwrap_or_default(), java_exe),
+        ),
+        Some(info) => check(
+            "java",
+            "Java",
+            CheckStatus::Fail,
+            info.error
+                .unwrap_or_else(|| format!("Java {} or higher is required", REQUIRED_JAVA_MAJOR)),
+        ),
+        None => check(
+            "java",
+            "Java",
+            CheckStatus::Fail,
+            format!("Could not run Java at: {}", java_exe),
+        ),
+    }
+}
+
+fn check_config(base: &Path) -> DiagnosticCheck {
+    let config_path = base.join("Server").join("config.json");
+    if !config_path.exists() {
+        return check(
+            "config",
+            "config.json",
+            CheckStatus::Warn,
+            "No config.json yet; the server will create one on first start",
+        );
+    }
+
+    match std::fs::read_to_string(&config_path) {
+        Ok(content) => match serde_json::from_str::<serde_json::Value>(&content) {
+            Ok(_) => check("config", "config.json", CheckStatus::Pass, "config.json is valid JSON"),
+            Err(e) => check(
+                "config",
+                "config.json",
+                CheckStatus::Fail,
+                format!("Invalid JSON: {}", e),
+            ),
+        },
+        Err(e) => check(
+            "config",
+            "config.json",
+            CheckStatus::Fail,
+            format!("Failed to read config.json: {}", e),
+        ),
+    }
+}
+
+fn check_port(instance_path: &str, server_args: Option<&str>, is_running: bool) -> DiagnosticCheck {
+    let (host, port) = resolve_bind_address(instance_path, server_args);
+
+    if is_running {
+        return check(
+            "port",
+            "Port",
+            CheckStatus::Pass,
+            format!("Port {} is in use by this running server", port),
+        );
+    }
+
+    if is_udp_port_available(&host, port) {
+        check("port", "Port", CheckStatus::Pass, format!("Port {} is free", port))
+    } else {
+        check(
+            "port",
+            "Port",
+            CheckStatus::Fail,
+            format!("Port {} is already in use", port),
+        )
+    }
+}
+
+fn check_disk_space(base: &Path) -> DiagnosticCheck {
+    let available = match available_disk_space(base) {
+        Some(available) => available,
+        None => {
+            return check(
+                "disk",
+                "Disk space",
+                CheckStatus::Warn,
+                "Could not determine free disk space",
+            )
+        }
+    };
+
+    let available_gb = available as f64 / (1024.0 * 1024.0 * 1024.0);
+    let message = format!("{:.1} GB free", available_gb);
+
+    let status = if available < DISK_FAIL_BYTES {
+        CheckStatus::Fail
+    } else if available < DISK_WARN_BYTES {
+        CheckStatus::Warn
+    } else {
+        CheckStatus::Pass
+    };
+
+    check("disk", "Disk space", status, message)
+}
+
+fn check_auth(auth_status: Option<&str>) -> DiagnosticCheck {
+    match auth_status {
+        Some("authenticated") => check("auth", "Authentication", CheckStatus::Pass, "Server is authenticated"),
+        Some("offline") => check(
+            "auth",
+            "Authentication",
+            CheckStatus::Warn,
+            "Server runs in offline mode",
+        ),
+        Some("unauthenticated") => check(
+            "auth",
+            "Authentication",
+            CheckStatus::Warn,
+            "Server is not authenticated; players won't be able to join until it is",
+        ),
+        _ => check(
+            "auth",
+            "Authentication",
+            CheckStatus::Warn,
+            "Authentication status is unknown",
+        ),
+    }
+}
@@ -4,11 +4,23 @@ use std::fs::{self, File};
 use std::io::{BufRead, BufReader, Write};
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
+use std::sync::Mutex;
 use tauri::{AppHandle, Emitter, Manager};
 use zip::ZipArchive;
 
+use super::backup::DATABASE_UNAVAILABLE;
+use super::errors::ErrorCode;
+use crate::database::{self, DbPool};
+
 const DOWNLOADER_URL: &str = "https://downloader.hytale.com/hytale-downloader.zip";
 
+/// User-configured CLI location (the `downloader_path` setting), checked before
+/// the app data dir and PATH. Mirrored here so lookups don't need the database.
+static DOWNLOADER_PATH_OVERRIDE: Mutex<Option<String>> = Mutex::new(None);
+
+/// User-edited settings files kept across a re-download (relative to the Server folder)
+pub const PRESERVED_CONFIG_FILES: [&str; 4] = ["config.json", "whitelist.json", "bans.json", "permissions.json"];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DownloaderInfo {
     pub available: bool,
@@ -29,7 +41,10 @@ pub struct DownloadProgress {
 pub struct DownloadResult {
     pub success: bool,
     pub output_path: Option<String>,
+    /// Settings files kept from the previous install (relative to the Server folder)
+    pub preserved_files: Vec<String>,
     pub error: Option<String>,
+    pub error_code: Option<ErrorCode>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -63,10 +78,18 @@ fn get_cli_directory(app: &AppHandle) -> Option<PathBuf> {
     app.path().app_data_dir().ok()
 }
 
-/// Find hytale-downloader in app directory or PATH
-fn find_downloader_with_app(app: Option<&AppHandle>) -> Option<String> {
+/// Find hytale-downloader at the configured path, in the app directory or in PATH
+pub fn find_downloader_with_app(app: Option<&AppHandle>) -> Option<String> {
     let exe_name = get_downloader_executable();
 
+    // A configured path wins, as long as it still exists
+    if let Some(path) = DOWNLOADER_PATH_OVERRIDE.lock().unwrap().clone() {
+        if std::path::Path::new(&path).is_file() {
+            return Some(path);
+        }
+        println!("[downloader] WARNING: Configured downloader path not found: {}", path);
+    }
+
     // First check app data directory if app handle is provided
     if let Some(app) = app {
         if let Some(app_dir) = get_cli_directory(app) {
@@ -98,6 +121,140 @@ fn find_downloader_with_app(app: Option<&AppHandle>) -> Option<String> {
     None
 }
 
+// ============================================================================
+// CLI invocation
+// ============================================================================
+
+/// Extra attempts when the CLI fails to spawn (antivirus lock, file not visible yet after extraction)
+const DOWNLOADER_SPAWN_RETRIES: u32 = 2;
+
+/// Delay before the first retry; doubled for each following one
+const DOWNLOADER_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Output of a finished hytale-downloader run
+#[derive(Debug, Clone)]
+pub struct DownloaderOutput {
+    /// None when the process was killed by a signal
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl DownloaderOutput {
+    pub fn success(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+/// Run hytale-downloader with `args` and collect its output.
+/// Spawn failures are retried with backoff; a run that exceeds `timeout` is killed
+/// and not retried, since the CLI did start.
+pub async fn run_downloader(
+    path: &str,
+    args: &[&str],
+    timeout: std::time::Duration,
+) -> Result<DownloaderOutput, String> {
+    let mut delay = DOWNLOADER_RETRY_DELAY;
+    let mut attempt = 0;
+
+    loop {
+        let output = tokio::process::Command::new(path)
+            .args(args)
+            .kill_on_drop(true)
+            .output();
+
+        match tokio::time::timeout(timeout, output).await {
+            Ok(Ok(output)) => {
+                return Ok(DownloaderOutput {
+                    exit_code: output.status.code(),
+                    stdout: String::from_utf8_lossy(&output.stdout).trim().to_string(),
+                    stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+                });
+            }
+            Ok(Err(e)) if attempt < DOWNLOADER_SPAWN_RETRIES => {
+                attempt += 1;
+                println!(
+                    "[downloader] Failed to run downloader ({}), retrying in {}ms ({}/{})",
+                    e,
+                    delay.as_millis(),
+                    attempt,
+                    DOWNLOADER_SPAWN_RETRIES
+                );
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+            Ok(Err(e)) => return Err(format!("Failed to run downloader: {}", e)),
+            Err(_) => {
+                return Err(format!(
+                    "Downloader did not respond within {}s",
+                    timeout.as_secs()
+                ));
+            }
+        }
+    }
+}
+
+/// Load the configured downloader path from settings (called once after the database is ready)
+pub async fn load_downloader_path(pool: &DbPool) {
+    let path = database::get_setting(pool, "downloader_path")
+        .await
+        .ok()
+        .flatten()
+        .filter(|p| !p.trim().is_empty());
+
+    if let Some(ref path) = path {
+        println!("[downloader] Using configured downloader path: {}", path);
+    }
+    *DOWNLOADER_PATH_OVERRIDE.lock().unwrap() = path;
+}
+
+/// Get the configured downloader path, if any
+#[tauri::command]
+pub fn get_downloader_path() -> Option<String> {
+    DOWNLOADER_PATH_OVERRIDE.lock().unwrap().clone()
+}
+
+/// Set a custom location for the hytale-downloader CLI, or clear it with an empty path.
+/// The file must exist and be executable.
+#[tauri::command]
+pub async fn set_downloader_path(app: AppHandle, path: String) -> Result<(), String> {
+    let pool = app
+        .try_state::<DbPool>()
+        .ok_or(DATABASE_UNAVAILABLE)?
+        .inner()
+        .clone();
+
+    let path = path.trim().to_string();
+    if !path.is_empty() {
+        validate_downloader_executable(std::path::Path::new(&path))?;
+    }
+
+    database::set_setting(&pool, "downloader_path", &path)
+        .await
+        .map_err(|e| format!("Failed to save downloader path: {}", e))?;
+
+    println!("[downloader] Downloader path set to: {:?}", path);
+    *DOWNLOADER_PATH_OVERRIDE.lock().unwrap() = if path.is_empty() { None } else { Some(path) };
+    Ok(())
+}
+
+fn validate_downloader_executable(path: &std::path::Path) -> Result<(), String> {
+    let metadata = fs::metadata(path).map_err(|_| format!("File not found: {}", path.display()))?;
+    if !metadata.is_file() {
+        return Err(format!("Not a file: {}", path.display()));
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if metadata.permissions().mode() & 0o111 == 0 {
+            return Err(format!("File is not executable: {}", path.display()));
+        }
+    }
+
+    Ok(())
+}
+
 /// Get detailed information about the hytale-downloader CLI
 /// This only checks if the executable exists, doesn't run any commands
 #[tauri::command]
@@ -125,6 +282,9 @@ pub fn get_downloader_info(app: AppHandle) -> DownloaderInfo {
     }
 }
 
+/// How long to wait for the CLI to print a version
+const DOWNLOADER_VERSION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
 /// Get CLI and game version (call this separately, it runs commands)
 #[tauri::command]
 pub async fn get_downloader_version(app: AppHandle) -> DownloaderInfo {
@@ -141,42 +301,19 @@ pub async fn get_downloader_version(app: AppHandle) -> DownloaderInfo {
         }
     };
 
-    // Run version commands in async context
-    let downloader_path = path.clone();
-
-    let cli_version = tokio::task::spawn_blocking({
-        let dp = downloader_path.clone();
-        move || {
-            Command::new(&dp)
-                .arg("-version")
-                .output()
-                .ok()
-                .and_then(|output| {
-                    if output.status.success() {
-                        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
-                    } else {
-                        None
-                    }
-                })
-        }
-    }).await.ok().flatten();
-
-    let game_version = tokio::task::spawn_blocking({
-        let dp = downloader_path.clone();
-        move || {
-            Command::new(&dp)
-                .arg("-print-version")
-                .output()
+    let run_version = |flag: &'static str| {
+        let path = path.clone();
+        async move {
+            run_downloader(&path, &[flag], DOWNLOADER_VERSION_TIMEOUT)
+                .await
                 .ok()
-                .and_then(|output| {
-                    if output.status.success() {
-                        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
-                    } else {
-                        None
-                    }
-                })
+                .filter(|output| output.success())
+                .map(|output| output.stdout)
         }
-    }).await.ok().flatten();
+    };
+
+    let cli_version = run_version("-version").await;
+    let game_version = run_version("-print-version").await;
 
     DownloaderInfo {
         available: true,
@@ -187,6 +324,52 @@ pub async fn get_downloader_version(app: AppHandle) -> DownloaderInfo {
     }
 }
 
+/// Patchlines offered when the CLI can't list them
+const DEFAULT_PATCHLINES: [&str; 2] = ["release", "pre-release"];
+
+/// How long to wait for the CLI to list patchlines
+const LIST_PATCHLINES_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// List patchlines accepted by `download_server_files`.
+/// Asks the CLI via `-list-patchlines`; if the CLI is missing, doesn't support the
+/// flag, or prints nothing usable, returns DEFAULT_PATCHLINES ("release", "pre-release").
+#[tauri::command]
+pub async fn get_patchlines(app: AppHandle) -> Vec<String> {
+    let defaults = || DEFAULT_PATCHLINES.iter().map(|p| p.to_string()).collect();
+
+    let path = match find_downloader_with_app(Some(&app)) {
+        Some(p) => p,
+        None => return defaults(),
+    };
+
+    let output = match run_downloader(&path, &["-list-patchlines"], LIST_PATCHLINES_TIMEOUT).await {
+        Ok(output) if output.success() => output,
+        _ => {
+            println!("[get_patchlines] CLI could not list patchlines, using defaults");
+            return defaults();
+        }
+    };
+
+    let patchlines: Vec<String> = output
+        .stdout
+        .lines()
+        .map(|line| line.trim().trim_start_matches(['-', '*']).trim())
+        .filter(|line| {
+            !line.is_empty()
+                && line
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        })
+        .map(String::from)
+        .collect();
+
+    if patchlines.is_empty() {
+        defaults()
+    } else {
+        patchlines
+    }
+}
+
 /// Download and install the hytale-downloader CLI
 #[tauri::command]
 pub async fn install_downloader_cli(app: AppHandle) -> InstallCliResult {
@@ -345,95 +528,28 @@ pub async fn install_downloader_cli(app: AppHandle) -> InstallCliResult {
     println!("[CLI Install] Opening zip file for extraction...");
 
     // Extract the ZIP file
-    let zip_file = match File::open(&zip_path) {
-        Ok(f) => f,
+    let extracted = match safe_extract(std::path::Path::new(&zip_path), &app_dir) {
+        Ok(files) => files,
         Err(e) => {
-            println!("[CLI Install] ERROR: Failed to open zip: {}", e);
+            println!("[CLI Install] ERROR: {}", e);
             return InstallCliResult {
                 success: false,
                 path: None,
-                error: Some(format!("Failed to open zip: {}", e)),
+                error: Some(e),
             };
         }
     };
 
-    let mut archive = match ZipArchive::new(zip_file) {
-        Ok(a) => {
-            println!("[CLI Install] Zip archive has {} files", a.len());
-            a
-        }
-        Err(e) => {
-            println!("[CLI Install] ERROR: Failed to read zip: {}", e);
-            return InstallCliResult {
-                success: false,
-                path: None,
-                error: Some(format!("Failed to read zip: {}", e)),
-            };
-        }
-    };
-
-    // Extract all files
-    println!("[CLI Install] Extracting files...");
-    for i in 0..archive.len() {
-        let mut file = match archive.by_index(i) {
-            Ok(f) => f,
-            Err(e) => {
-                println!("[CLI Install] ERROR: Failed to read zip entry {}: {}", i, e);
-                return InstallCliResult {
-                    success: false,
-                    path: None,
-                    error: Some(format!("Failed to read zip entry: {}", e)),
-                };
-            }
-        };
-
-        let outpath = match file.enclosed_name() {
-            Some(path) => app_dir.join(path),
-            None => continue,
-        };
-
-        println!("[CLI Install] Extracting: {:?}", outpath);
-
-        if file.name().ends_with('/') {
-            fs::create_dir_all(&outpath).ok();
-        } else {
-            if let Some(parent) = outpath.parent() {
-                fs::create_dir_all(parent).ok();
-            }
-            let mut outfile = match File::create(&outpath) {
-                Ok(f) => f,
-                Err(e) => {
-                    println!("[CLI Install] ERROR: Failed to create file {:?}: {}", outpath, e);
-                    return InstallCliResult {
-                        success: false,
-                        path: None,
-                        error: Some(format!("Failed to create file: {}", e)),
-                    };
-                }
-            };
-            if let Err(e) = std::io::copy(&mut file, &mut outfile) {
-                println!("[CLI Install] ERROR: Failed to extract file: {}", e);
-                return InstallCliResult {
-                    success: false,
-                    path: None,
-                    error: Some(format!("Failed to extract file: {}", e)),
-                };
-            }
-
-            // Set executable permissions on Unix
-            #[cfg(unix)]
-            {
-                use std::os::unix::fs::PermissionsExt;
-                if let Ok(metadata) = outfile.metadata() {
-                    let mut perms = metadata.permissions();
-                    perms.set_mode(0o755);
-                    fs::set_permissions(&outpath, perms).ok();
-                }
-            }
+    // Set executable permissions on Unix
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        for path in &extracted {
+            fs::set_permissions(path, fs::Permissions::from_mode(0o755)).ok();
         }
     }
 
-    println!("[CLI Install] Extraction complete, cleaning up zip...");
+    println!("[CLI Install] Extracted {} files, cleaning up zip...", extracted.len());
 
     // Clean up zip file
     fs::remove_file(&zip_path).ok();
@@ -478,25 +594,121 @@ pub async fn install_downloader_cli(app: AppHandle) -> InstallCliResult {
     }
 }
 
-/// Check for hytale-downloader updates
+/// How long to wait for the CLI to check for its own updates
+const CHECK_UPDATE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Check for hytale-downloader updates.
+/// With `stream` set, each line of CLI output is also emitted as a
+/// `downloader-update-progress` event while the check runs.
 #[tauri::command]
-pub fn check_downloader_update(app: AppHandle) -> Result<String, String> {
+pub async fn check_downloader_update(app: AppHandle, stream: Option<bool>) -> Result<String, String> {
     let path = find_downloader_with_app(Some(&app)).ok_or("hytale-downloader not found")?;
 
-    let output = Command::new(&path)
-        .arg("-check-update")
-        .output()
+    if stream.unwrap_or(false) {
+        return stream_update_check(&app, &path).await;
+    }
+
+    let output = run_downloader(&path, &["-check-update"], CHECK_UPDATE_TIMEOUT)
+        .await
         .map_err(|e| format!("Failed to check for updates: {}", e))?;
 
-    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    Ok(output.stdout)
 }
 
-/// Download server files using hytale-downloader CLI
+/// Run `-check-update`, emitting its output line by line. The CLI is killed if it
+/// doesn't finish within CHECK_UPDATE_TIMEOUT.
+async fn stream_update_check(app: &AppHandle, path: &str) -> Result<String, String> {
+    use tokio::io::{AsyncBufReadExt, BufReader as AsyncBufReader};
+
+    let emit = |status: &str, message: String| {
+        let _ = app.emit(
+            "downloader-update-progress",
+            DownloadProgress {
+                status: status.to_string(),
+                percentage: None,
+                message,
+            },
+        );
+    };
+
+    emit("starting", "Checking for downloader updates...".to_string());
+
+    let mut child = tokio::process::Command::new(path)
+        .arg("-check-update")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| {
+            let error_msg = format!("Failed to check for updates: {}", e);
+            emit("error", error_msg.clone());
+            error_msg
+        })?;
+
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+
+    let read_stdout = async {
+        let mut collected = Vec::new();
+        if let Some(stdout) = stdout {
+            let mut lines = AsyncBufReader::new(stdout).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                println!("[check_downloader_update] STDOUT: {}", line);
+                emit("checking", line.clone());
+                collected.push(line);
+            }
+        }
+        collected
+    };
+
+    let read_stderr = async {
+        if let Some(stderr) = stderr {
+            let mut lines = AsyncBufReader::new(stderr).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                println!("[check_downloader_update] STDERR: {}", line);
+                emit("checking", line);
+            }
+        }
+    };
+
+    let run = async {
+        let (collected, _) = tokio::join!(read_stdout, read_stderr);
+        child.wait().await.map(|status| (status, collected))
+    };
+
+    // On timeout the child is dropped here and killed (kill_on_drop)
+    match tokio::time::timeout(CHECK_UPDATE_TIMEOUT, run).await {
+        Ok(Ok((status, collected))) => {
+            println!("[check_downloader_update] Process exited with: {:?}", status);
+            emit("complete", "Update check finished".to_string());
+            Ok(collected.join("\n").trim().to_string())
+        }
+        Ok(Err(e)) => {
+            let error_msg = format!("Failed to check for updates: {}", e);
+            emit("error", error_msg.clone());
+            Err(error_msg)
+        }
+        Err(_) => {
+            let error_msg = format!(
+                "Failed to check for updates: Downloader did not respond within {}s",
+                CHECK_UPDATE_TIMEOUT.as_secs()
+            );
+            emit("error", error_msg.clone());
+            Err(error_msg)
+        }
+    }
+}
+
+/// Download server files using hytale-downloader CLI.
+/// Existing settings files are kept unless `reset_config` is set, and the
+/// downloaded archive is deleted right after extraction unless `keep_zip` is set.
 #[tauri::command]
 pub async fn download_server_files(
     app: AppHandle,
     destination: String,
     patchline: Option<String>,
+    reset_config: Option<bool>,
+    keep_zip: Option<bool>,
 ) -> DownloadResult {
     println!("[download_server_files] Starting download to: {}", destination);
 
@@ -510,7 +722,9 @@ pub async fn download_server_files(
             return DownloadResult {
                 success: false,
                 output_path: None,
+                preserved_files: Vec::new(),
                 error: Some("hytale-downloader not installed. Please install it first.".to_string()),
+                error_code: Some(ErrorCode::CliMissing),
             };
         }
     };
@@ -523,7 +737,9 @@ pub async fn download_server_files(
             return DownloadResult {
                 success: false,
                 output_path: None,
+                preserved_files: Vec::new(),
                 error: Some(format!("Failed to create destination directory: {}", e)),
+                error_code: Some(ErrorCode::from_io(&e)),
             };
         }
         println!("[download_server_files] Created destination directory: {}", destination);
@@ -583,7 +799,9 @@ pub async fn download_server_files(
             return DownloadResult {
                 success: false,
                 output_path: None,
+                preserved_files: Vec::new(),
                 error: Some(format!("Failed to start downloader: {}", e)),
+                error_code: Some(ErrorCode::from_io(&e)),
             };
         }
     };
@@ -626,7 +844,9 @@ pub async fn download_server_files(
             return DownloadResult {
                 success: false,
                 output_path: None,
+                preserved_files: Vec::new(),
                 error: Some(error_msg),
+                error_code: Some(ErrorCode::from_io(&e)),
             };
         }
     };
@@ -636,6 +856,8 @@ pub async fn download_server_files(
     if !status.success() {
         let error_msg = format!("Download failed with exit code: {:?}", status.code());
         println!("[download_server_files] ERROR: {}", error_msg);
+        // Don't leave a partial archive taking up space
+        fs::remove_file(&zip_path).ok();
         let _ = app.emit(
             "download-progress",
             DownloadProgress {
@@ -647,7 +869,9 @@ pub async fn download_server_files(
         return DownloadResult {
             success: false,
             output_path: None,
+            preserved_files: Vec::new(),
             error: Some(error_msg),
+            error_code: Some(ErrorCode::DownloadFailed),
         };
     }
 
@@ -659,7 +883,9 @@ pub async fn download_server_files(
         return DownloadResult {
             success: false,
             output_path: None,
+            preserved_files: Vec::new(),
             error: Some(error_msg),
+            error_code: Some(ErrorCode::DownloadFailed),
         };
     }
 
@@ -683,79 +909,53 @@ pub async fn download_server_files(
         return DownloadResult {
             success: false,
             output_path: None,
+            preserved_files: Vec::new(),
             error: Some(error_msg),
+            error_code: Some(ErrorCode::from_io(&e)),
         };
     }
 
+    // Keep the user's settings files unless a clean config was requested
+    let server_dir = dest_path.join("Server");
+    let saved_configs = if reset_config.unwrap_or(false) {
+        Vec::new()
+    } else {
+        read_preserved_configs(&server_dir)
+    };
+
     // Extract the zip file
     println!("[download_server_files] Extracting zip to: {}", destination);
-    let zip_file = match File::open(&zip_path) {
-        Ok(f) => f,
-        Err(e) => {
-            let error_msg = format!("Failed to open zip file: {}", e);
-            println!("[download_server_files] ERROR: {}", error_msg);
-            return DownloadResult {
-                success: false,
-                output_path: None,
-                error: Some(error_msg),
-            };
-        }
-    };
+    let extract_result = safe_extract(zip_path_buf, dest_path);
 
-    let mut archive = match ZipArchive::new(zip_file) {
-        Ok(a) => {
-            println!("[download_server_files] Zip has {} files", a.len());
-            a
+    // Free the archive's space as soon as it's been read, before anything else touches the disk
+    if keep_zip.unwrap_or(false) {
+        println!("[download_server_files] Keeping zip file: {}", zip_path);
+    } else {
+        println!("[download_server_files] Cleaning up zip file...");
+        if let Err(e) = fs::remove_file(&zip_path) {
+            println!("[download_server_files] WARNING: Failed to delete zip: {}", e);
         }
+    }
+
+    // Put the settings back even if extraction stopped halfway
+    let preserved_files = restore_preserved_configs(&server_dir, saved_configs);
+
+    match extract_result {
+        Ok(files) => println!("[download_server_files] Extracted {} files", files.len()),
         Err(e) => {
-            let error_msg = format!("Failed to read zip file: {}", e);
-            println!("[download_server_files] ERROR: {}", error_msg);
+            println!("[download_server_files] ERROR: {}", e);
             return DownloadResult {
                 success: false,
                 output_path: None,
-                error: Some(error_msg),
-            };
-        }
-    };
-
-    // Extract all files
-    for i in 0..archive.len() {
-        let mut file = match archive.by_index(i) {
-            Ok(f) => f,
-            Err(e) => {
-                println!("[download_server_files] WARNING: Failed to read zip entry {}: {}", i, e);
-                continue;
-            }
-        };
-
-        let outpath = match file.enclosed_name() {
-            Some(path) => dest_path.join(path),
-            None => continue,
-        };
-
-        if file.name().ends_with('/') {
-            fs::create_dir_all(&outpath).ok();
-        } else {
-            if let Some(parent) = outpath.parent() {
-                fs::create_dir_all(parent).ok();
-            }
-            let mut outfile = match File::create(&outpath) {
-                Ok(f) => f,
-                Err(e) => {
-                    println!("[download_server_files] WARNING: Failed to create {:?}: {}", outpath, e);
-                    continue;
-                }
+                preserved_files,
+                error: Some(e),
+                error_code: Some(ErrorCode::ExtractFailed),
             };
-            if let Err(e) = std::io::copy(&mut file, &mut outfile) {
-                println!("[download_server_files] WARNING: Failed to extract {:?}: {}", outpath, e);
-            }
         }
     }
 
-    // Clean up zip file
-    println!("[download_server_files] Cleaning up zip file...");
-    if let Err(e) = fs::remove_file(&zip_path) {
-        println!("[download_server_files] WARNING: Failed to delete zip: {}", e);
+    if !preserved_files.is_empty() {
+        println!("[download_server_files] config-preserved: {}", preserved_files.join(", "));
     }
 
     // List files in destination
@@ -778,10 +978,117 @@ pub async fn download_server_files(
     DownloadResult {
         success: true,
         output_path: Some(destination),
+        preserved_files,
         error: None,
+        error_code: None,
     }
 }
 
+/// Extract a zip archive into `dest`, refusing to write anything outside of it.
+/// Entries whose resolved path escapes `dest` (via `..`, absolute paths or symlinked
+/// directories) are skipped and logged. Returns the paths of the extracted files.
+pub fn safe_extract(archive_path: &std::path::Path, dest: &std::path::Path) -> Result<Vec<PathBuf>, String> {
+    let zip_file = File::open(archive_path).map_err(|e| format!("Failed to open zip file: {}", e))?;
+    let mut archive = ZipArchive::new(zip_file).map_err(|e| format!("Failed to read zip file: {}", e))?;
+
+    fs::create_dir_all(dest).map_err(|e| format!("Failed to create destination directory: {}", e))?;
+    let dest_root = dest
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve destination directory: {}", e))?;
+
+    println!("[safe_extract] Extracting {} entries to {:?}", archive.len(), dest_root);
+
+    let mut extracted = Vec::new();
+    for i in 0..archive.len() {
+        let mut file = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read zip entry {}: {}", i, e))?;
+
+        let relative = match file.enclosed_name() {
+            Some(path) => path,
+            None => {
+                println!("[safe_extract] WARNING: Skipping unsafe entry: {}", file.name());
+                continue;
+            }
+        };
+        let outpath = dest_root.join(&relative);
+
+        let dir = if file.is_dir() {
+            outpath.clone()
+        } else {
+            match outpath.parent() {
+                Some(parent) => parent.to_path_buf(),
+                None => continue,
+            }
+        };
+
+        // Resolve symlinks on disk so a linked directory can't redirect the write
+        if !resolves_inside(&dir, &dest_root) {
+            println!("[safe_extract] WARNING: Skipping entry outside destination: {}", file.name());
+            continue;
+        }
+
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {:?}: {}", dir, e))?;
+
+        if file.is_dir() {
+            continue;
+        }
+
+        if fs::symlink_metadata(&outpath).map(|m| m.file_type().is_symlink()).unwrap_or(false) {
+            println!("[safe_extract] WARNING: Skipping entry over existing symlink: {}", file.name());
+            continue;
+        }
+
+        let mut outfile = File::create(&outpath).map_err(|e| format!("Failed to create {:?}: {}", outpath, e))?;
+        std::io::copy(&mut file, &mut outfile).map_err(|e| format!("Failed to extract {:?}: {}", outpath, e))?;
+        extracted.push(outpath);
+    }
+
+    Ok(extracted)
+}
+
+/// Check that the deepest existing ancestor of `path` canonicalizes to somewhere under `root`
+fn resolves_inside(path: &std::path::Path, root: &std::path::Path) -> bool {
+    path.ancestors()
+        .find(|ancestor| ancestor.exists())
+        .and_then(|ancestor| ancestor.canonicalize().ok())
+        .map(|resolved| resolved.starts_with(root))
+        .unwrap_or(false)
+}
+
+/// Read the preserved settings files that currently exist in the server directory
+fn read_preserved_configs(server_dir: &std::path::Path) -> Vec<(String, Vec<u8>)> {
+    PRESERVED_CONFIG_FILES
+        .iter()
+        .filter_map(|name| {
+            let content = fs::read(server_dir.join(name)).ok()?;
+            Some((name.to_string(), content))
+        })
+        .collect()
+}
+
+/// Write previously read settings files back over the extracted ones.
+/// Returns the names of the files that were restored.
+fn restore_preserved_configs(server_dir: &std::path::Path, saved: Vec<(String, Vec<u8>)>) -> Vec<String> {
+    let mut restored = Vec::new();
+    if saved.is_empty() {
+        return restored;
+    }
+
+    if let Err(e) = fs::create_dir_all(server_dir) {
+        println!("[download_server_files] WARNING: Failed to create Server directory: {}", e);
+        return restored;
+    }
+
+    for (name, content) in saved {
+        match fs::write(server_dir.join(&name), content) {
+            Ok(()) => restored.push(name),
+            Err(e) => println!("[download_server_files] WARNING: Failed to restore {}: {}", name, e),
+        }
+    }
+    restored
+}
+
 /// Parse download progress from CLI output
 fn parse_download_progress(line: &str) -> DownloadProgress {
     let line_lower = line.to_lowercase();
@@ -893,3 +1200,67 @@ pub fn check_server_files(path: String) -> ServerFilesStatus {
         server_path: if has_server_jar { Some(server_jar.to_string_lossy().to_string()) } else { None },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    /// Empty scratch directory under the system temp dir
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("hypanel-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Write a zip whose entries have the given raw names
+    fn write_zip(path: &Path, entries: &[&str]) {
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        for name in entries {
+            zip.start_file(*name, options).unwrap();
+            zip.write_all(b"payload").unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn safe_extract_skips_entries_outside_destination() {
+        let root = scratch_dir("safe-extract");
+        let dest = root.join("dest");
+        let absolute = root.join("absolute.txt");
+        let archive = root.join("archive.zip");
+        write_zip(
+            &archive,
+            &["ok/inside.txt", "../evil.txt", "ok/../../evil2.txt", &absolute.to_string_lossy()],
+        );
+
+        let extracted = safe_extract(&archive, &dest).unwrap();
+
+        assert_eq!(extracted, vec![dest.canonicalize().unwrap().join("ok").join("inside.txt")]);
+        assert!(!root.join("evil.txt").exists());
+        assert!(!root.join("evil2.txt").exists());
+        assert!(!absolute.exists());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn safe_extract_does_not_follow_symlinked_directories() {
+        let root = scratch_dir("safe-extract-symlink");
+        let dest = root.join("dest");
+        let outside = root.join("outside");
+        fs::create_dir_all(&dest).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        std::os::unix::fs::symlink(&outside, dest.join("link")).unwrap();
+        let archive = root.join("archive.zip");
+        write_zip(&archive, &["link/evil.txt"]);
+
+        let extracted = safe_extract(&archive, &dest).unwrap();
+
+        assert!(extracted.is_empty());
+        assert!(!outside.join("evil.txt").exists());
+        let _ = fs::remove_dir_all(&root);
+    }
+}
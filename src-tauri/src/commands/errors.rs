@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Machine-readable error category sent next to the human-readable message,
+/// so the frontend can show guidance for the specific failure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The server is already running
+    AlreadyRunning,
+    /// HytaleServer.jar or Assets.zip is missing
+    ServerFilesMissing,
+    /// Java is missing or older than the required version
+    JavaUnavailable,
+    /// The JVM arguments failed validation
+    InvalidJvmArgs,
+    /// The server port is taken by another process
+    PortInUse,
+    /// Starting the server would commit more heap than the machine has
+    InsufficientMemory,
+    /// The hytale-downloader CLI isn't installed
+    CliMissing,
+    /// The downloader ran but didn't produce the server files
+    DownloadFailed,
+    /// The downloaded archive couldn't be extracted
+    ExtractFailed,
+    /// The content couldn't be parsed or serialized as valid config
+    InvalidConfig,
+    /// A file or directory doesn't exist
+    NotFound,
+    /// The OS refused access to a file or directory
+    PermissionDenied,
+    /// The world is the server's default world and needs force to delete
+    DefaultWorld,
+    /// Any other I/O or process failure
+    Io,
+}
+
+impl ErrorCode {
+    /// Categorize an I/O error
+    pub fn from_io(error: &std::io::Error) -> Self {
+        match error.kind() {
+            std::io::ErrorKind::NotFound => ErrorCode::NotFound,
+            std::io::ErrorKind::PermissionDenied => ErrorCode::PermissionDenied,
+            _ => ErrorCode::Io,
+        }
+    }
+}
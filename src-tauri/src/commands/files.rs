@@ -1,6 +1,11 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::Path;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+/// Subdirectories created for a new instance
+pub const INSTANCE_SUBDIRS: [&str; 3] = ["universe", "mods", "logs"];
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CopyResult {
@@ -8,6 +13,16 @@ pub struct CopyResult {
     pub files_copied: u32,
     pub destination: String,
     pub error: Option<String>,
+    pub mismatches: Vec<CopyMismatch>,
+}
+
+/// A copied file that doesn't match its source
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CopyMismatch {
+    pub path: String,  // Relative to the destination folder
+    pub reason: String,
+    pub source_size: Option<u64>,
+    pub dest_size: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -17,9 +32,115 @@ pub struct DownloaderStatus {
     pub error: Option<String>,
 }
 
-/// Copies server files from Hytale launcher to destination folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CopyProgress {
+    pub status: String,
+    pub percentage: Option<f32>,
+    pub message: String,
+    pub files_copied: u32,
+    pub total_files: u32,
+}
+
+/// Buffer size for chunked file copies with progress reporting
+const COPY_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Tracks copy progress and emits `copy-progress` events.
+/// Percentage is byte-based so a single large Assets.zip still moves the bar.
+struct CopyTracker<'a> {
+    app: &'a AppHandle,
+    files_copied: u32,
+    total_files: u32,
+    bytes_copied: u64,
+    total_bytes: u64,
+    last_percent: Option<u32>,
+}
+
+impl CopyTracker<'_> {
+    fn add_bytes(&mut self, bytes: u64) {
+        self.bytes_copied += bytes;
+        self.emit_if_changed();
+    }
+
+    fn file_done(&mut self) {
+        self.files_copied += 1;
+        self.emit_if_changed();
+    }
+
+    fn percentage(&self) -> f32 {
+        if self.total_bytes > 0 {
+            (self.bytes_copied as f32 / self.total_bytes as f32 * 100.0).min(100.0)
+        } else if self.total_files > 0 {
+            self.files_copied as f32 / self.total_files as f32 * 100.0
+        } else {
+            100.0
+        }
+    }
+
+    /// Only emit when the whole-number percentage changes
+    fn emit_if_changed(&mut self) {
+        let percent = self.percentage() as u32;
+        if self.last_percent == Some(percent) {
+            return;
+        }
+        self.last_percent = Some(percent);
+
+        let _ = self.app.emit(
+            "copy-progress",
+            CopyProgress {
+                status: "copying".to_string(),
+                percentage: Some(self.percentage()),
+                message: format!(
+                    "Copying files... {} / {} ({:.1} MB / {:.1} MB)",
+                    self.files_copied,
+                    self.total_files,
+                    self.bytes_copied as f64 / 1_000_000.0,
+                    self.total_bytes as f64 / 1_000_000.0
+                ),
+                files_copied: self.files_copied,
+                total_files: self.total_files,
+            },
+        );
+    }
+}
+
+/// Copies server files from Hytale launcher to destination folder.
+/// Runs off the main thread and emits `copy-progress` events.
+/// With `verify`, file sizes are compared afterwards; `verify_contents` also
+/// compares the bytes of the server jar and Assets.zip.
 #[tauri::command]
-pub fn copy_server_files(source: String, destination: String) -> CopyResult {
+pub async fn copy_server_files(
+    app: AppHandle,
+    source: String,
+    destination: String,
+    verify: Option<bool>,
+    verify_contents: Option<bool>,
+) -> CopyResult {
+    let dest_for_error = destination.clone();
+    let verify = verify.unwrap_or(false) || verify_contents.unwrap_or(false);
+    let verify_contents = verify_contents.unwrap_or(false);
+    match tauri::async_runtime::spawn_blocking(move || {
+        copy_server_files_blocking(&app, source, destination, verify, verify_contents)
+    })
+    .await
+    {
+        Ok(result) => result,
+        Err(e) => CopyResult {
+            success: false,
+            files_copied: 0,
+            destination: dest_for_error,
+            error: Some(format!("Copy task failed: {}", e)),
+            mismatches: Vec::new(),
+        },
+    }
+}
+
+fn copy_server_files_blocking(
+    app: &AppHandle,
+    source: String,
+    destination: String,
+    verify: bool,
+    verify_contents: bool,
+) -> CopyResult {
     let source_path = Path::new(&source);
     let dest_path = Path::new(&destination);
 
@@ -30,6 +151,7 @@ pub fn copy_server_files(source: String, destination: String) -> CopyResult {
             files_copied: 0,
             destination,
             error: Some("Source path does not exist".to_string()),
+            mismatches: Vec::new(),
         };
     }
 
@@ -40,53 +162,270 @@ pub fn copy_server_files(source: String, destination: String) -> CopyResult {
             files_copied: 0,
             destination,
             error: Some(format!("Failed to create destination directory: {}", e)),
+            mismatches: Vec::new(),
         };
     }
 
-    let mut files_copied = 0;
-
-    // Copy Server directory
     let server_src = source_path.join("Server");
     let server_dest = dest_path.join("Server");
+    let assets_src = source_path.join("Assets.zip");
+    let assets_dest = dest_path.join("Assets.zip");
+
+    // Pre-count so progress has a meaningful total
+    let (mut total_files, mut total_bytes) = if server_src.exists() {
+        count_files(&server_src)
+    } else {
+        (0, 0)
+    };
+    if let Ok(meta) = fs::metadata(&assets_src) {
+        total_files += 1;
+        total_bytes += meta.len();
+    }
+
+    let mut tracker = CopyTracker {
+        app,
+        files_copied: 0,
+        total_files,
+        bytes_copied: 0,
+        total_bytes,
+        last_percent: None,
+    };
+    tracker.emit_if_changed();
+
+    // Copy Server directory
     if server_src.exists() {
-        match copy_dir_recursive(&server_src, &server_dest) {
-            Ok(count) => files_copied += count,
-            Err(e) => {
-                return CopyResult {
-                    success: false,
-                    files_copied,
-                    destination,
-                    error: Some(format!("Failed to copy Server directory: {}", e)),
-                };
-            }
+        if let Err(e) = copy_dir_with_progress(&server_src, &server_dest, &mut tracker) {
+            return CopyResult {
+                success: false,
+                files_copied: tracker.files_copied,
+                destination,
+                error: Some(format!("Failed to copy Server directory: {}", e)),
+                mismatches: Vec::new(),
+            };
         }
     }
 
     // Copy Assets.zip
-    let assets_src = source_path.join("Assets.zip");
-    let assets_dest = dest_path.join("Assets.zip");
     if assets_src.exists() {
-        if let Err(e) = fs::copy(&assets_src, &assets_dest) {
+        if let Err(e) = copy_file_with_progress(&assets_src, &assets_dest, &mut tracker) {
             return CopyResult {
                 success: false,
-                files_copied,
+                files_copied: tracker.files_copied,
                 destination,
                 error: Some(format!("Failed to copy Assets.zip: {}", e)),
+                mismatches: Vec::new(),
             };
         }
-        files_copied += 1;
     }
 
+    let mismatches = if verify {
+        let _ = app.emit(
+            "copy-progress",
+            CopyProgress {
+                status: "verifying".to_string(),
+                percentage: Some(100.0),
+                message: "Verifying copied files...".to_string(),
+                files_copied: tracker.files_copied,
+                total_files: tracker.total_files,
+            },
+        );
+        verify_copy(source_path, dest_path, verify_contents)
+    } else {
+        Vec::new()
+    };
+
+    let _ = app.emit(
+        "copy-progress",
+        CopyProgress {
+            status: "completed".to_string(),
+            percentage: Some(100.0),
+            message: "Copy complete!".to_string(),
+            files_copied: tracker.files_copied,
+            total_files: tracker.total_files,
+        },
+    );
+
+    let error = if mismatches.is_empty() {
+        None
+    } else {
+        Some(format!("{} file(s) failed verification", mismatches.len()))
+    };
+
     CopyResult {
-        success: true,
-        files_copied,
+        success: mismatches.is_empty(),
+        files_copied: tracker.files_copied,
         destination,
-        error: None,
+        error,
+        mismatches,
     }
 }
 
+/// Files whose contents are compared when `verify_contents` is set
+const CRITICAL_FILES: [&str; 2] = ["Server/HytaleServer.jar", "Assets.zip"];
+
+/// Compare copied files against the source by size, and optionally by content
+fn verify_copy(source: &Path, dest: &Path, verify_contents: bool) -> Vec<CopyMismatch> {
+    let mut relative_files = Vec::new();
+    let server_src = source.join("Server");
+    if server_src.exists() {
+        collect_relative_files(&server_src, Path::new("Server"), &mut relative_files);
+    }
+    if source.join("Assets.zip").exists() {
+        relative_files.push(PathBuf::from("Assets.zip"));
+    }
+
+    let mut mismatches = Vec::new();
+
+    for relative in relative_files {
+        let src_file = source.join(&relative);
+        let dest_file = dest.join(&relative);
+        let source_size = fs::metadata(&src_file).map(|m| m.len()).ok();
+        let dest_size = fs::metadata(&dest_file).map(|m| m.len()).ok();
+        let display = relative.to_string_lossy().replace('\\', "/");
+
+        let reason = if dest_size.is_none() {
+            Some("Missing from destination".to_string())
+        } else if source_size != dest_size {
+            Some("Size differs".to_string())
+        } else if verify_contents && CRITICAL_FILES.contains(&display.as_str()) {
+            match files_equal(&src_file, &dest_file) {
+                Ok(true) => None,
+                Ok(false) => Some("Contents differ".to_string()),
+                Err(e) => Some(format!("Failed to compare contents: {}", e)),
+            }
+        } else {
+            None
+        };
+
+        if let Some(reason) = reason {
+            mismatches.push(CopyMismatch {
+                path: display,
+                reason,
+                source_size,
+                dest_size,
+            });
+        }
+    }
+
+    mismatches
+}
+
+/// List files under `dir`, as paths prefixed with `prefix`
+fn collect_relative_files(dir: &Path, prefix: &Path, out: &mut Vec<PathBuf>) {
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let relative = prefix.join(entry.file_name());
+            match entry.file_type() {
+                Ok(ft) if ft.is_dir() => collect_relative_files(&entry.path(), &relative, out),
+                Ok(_) => out.push(relative),
+                Err(_) => {}
+            }
+        }
+    }
+}
+
+/// Stream both files and compare them chunk by chunk
+fn files_equal(a: &Path, b: &Path) -> Result<bool, std::io::Error> {
+    let mut file_a = fs::File::open(a)?;
+    let mut file_b = fs::File::open(b)?;
+    let mut buf_a = vec![0u8; COPY_CHUNK_SIZE];
+    let mut buf_b = vec![0u8; COPY_CHUNK_SIZE];
+
+    loop {
+        let n = read_full(&mut file_a, &mut buf_a)?;
+        let m = read_full(&mut file_b, &mut buf_b)?;
+        if n != m || buf_a[..n] != buf_b[..m] {
+            return Ok(false);
+        }
+        if n == 0 {
+            return Ok(true);
+        }
+    }
+}
+
+/// Read until the buffer is full or EOF, so chunk boundaries line up between files
+fn read_full(file: &mut fs::File, buf: &mut [u8]) -> Result<usize, std::io::Error> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        let n = file.read(&mut buf[filled..])?;
+        if n == 0 {
+            break;
+        }
+        filled += n;
+    }
+    Ok(filled)
+}
+
+/// Count files and total bytes under a directory
+fn count_files(path: &Path) -> (u32, u64) {
+    let mut files = 0;
+    let mut bytes = 0;
+
+    if let Ok(entries) = fs::read_dir(path) {
+        for entry in entries.flatten() {
+            match entry.file_type() {
+                Ok(ft) if ft.is_dir() => {
+                    let (f, b) = count_files(&entry.path());
+                    files += f;
+                    bytes += b;
+                }
+                Ok(_) => {
+                    files += 1;
+                    bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+                }
+                Err(_) => {}
+            }
+        }
+    }
+
+    (files, bytes)
+}
+
+fn copy_dir_with_progress(src: &Path, dest: &Path, tracker: &mut CopyTracker) -> Result<(), std::io::Error> {
+    fs::create_dir_all(dest)?;
+
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let src_path = entry.path();
+        let dest_path = dest.join(entry.file_name());
+
+        if entry.file_type()?.is_dir() {
+            copy_dir_with_progress(&src_path, &dest_path, tracker)?;
+        } else {
+            copy_file_with_progress(&src_path, &dest_path, tracker)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Copy a single file in chunks, reporting bytes as they are written
+fn copy_file_with_progress(src: &Path, dest: &Path, tracker: &mut CopyTracker) -> Result<(), std::io::Error> {
+    let mut reader = fs::File::open(src)?;
+    let mut writer = fs::File::create(dest)?;
+    let mut buf = vec![0u8; COPY_CHUNK_SIZE];
+
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        writer.write_all(&buf[..n])?;
+        tracker.add_bytes(n as u64);
+    }
+
+    writer.flush()?;
+    // Keep permissions (e.g. executable bits) like fs::copy does
+    if let Ok(meta) = reader.metadata() {
+        let _ = fs::set_permissions(dest, meta.permissions());
+    }
+
+    tracker.file_done();
+    Ok(())
+}
+
 /// Recursively copies a directory
-fn copy_dir_recursive(src: &Path, dest: &Path) -> Result<u32, std::io::Error> {
+pub fn copy_dir_recursive(src: &Path, dest: &Path) -> Result<u32, std::io::Error> {
     let mut count = 0;
     fs::create_dir_all(dest)?;
 
@@ -162,8 +501,7 @@ pub fn create_instance(path: String, name: String) -> Result<String, String> {
         .map_err(|e| format!("Failed to create instance directory: {}", e))?;
 
     // Create subdirectories
-    let dirs = ["universe", "mods", "logs"];
-    for dir in dirs {
+    for dir in INSTANCE_SUBDIRS {
         fs::create_dir_all(instance_path.join(dir))
             .map_err(|e| format!("Failed to create {} directory: {}", dir, e))?;
     }
@@ -180,3 +518,35 @@ pub fn validate_server_files(path: String) -> bool {
 
     server_exists && assets_exists
 }
+
+/// Creates the instance directory and its standard subdirectories.
+/// Returns the directories that did not exist before, so a failed create can undo them.
+/// On error, anything created so far has already been removed.
+pub fn scaffold_instance_dirs(instance_path: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut created = Vec::new();
+
+    let targets = std::iter::once(instance_path.to_path_buf())
+        .chain(INSTANCE_SUBDIRS.iter().map(|dir| instance_path.join(dir)));
+
+    for dir in targets {
+        if dir.exists() {
+            continue;
+        }
+        if let Err(e) = fs::create_dir_all(&dir) {
+            remove_created_dirs(&created);
+            return Err(format!("Failed to create {}: {}", dir.display(), e));
+        }
+        created.push(dir);
+    }
+
+    Ok(created)
+}
+
+/// Removes directories returned by `scaffold_instance_dirs`, newest first
+pub fn remove_created_dirs(created: &[PathBuf]) {
+    for dir in created.iter().rev() {
+        if let Err(e) = fs::remove_dir_all(dir) {
+            println!("[files] Warning: Failed to remove {}: {}", dir.display(), e);
+        }
+    }
+}
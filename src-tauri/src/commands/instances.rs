@@ -1,6 +1,15 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use tauri::{AppHandle, Manager, State};
 
+use super::config::write_file_atomic;
+use super::diagnostics::{resolve_instance_paths, InstancePaths};
+use super::downloader::{check_server_files, find_downloader_with_app, PRESERVED_CONFIG_FILES};
+use super::files::{copy_dir_recursive, remove_created_dirs, scaffold_instance_dirs};
+use super::server::{validate_launch_wrapper, ServerState};
+use super::system::check_java;
+use super::version::read_installed_version;
 use crate::database::{self, DbPool, Instance, CreateInstanceInput};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,29 +26,116 @@ pub struct InstancesListResult {
     pub error: Option<String>,
 }
 
+/// Portable copy of an instance's settings and server config files, without
+/// its path, auth state, Java path, environment variables or server password
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceBundle {
+    pub version: u32,
+    pub exported_at: String,
+    pub name: String,
+    pub jvm_args: Option<String>,
+    pub server_args: Option<String>,
+    pub autostart: bool,
+    pub stop_command: Option<String>,
+    pub stop_command_timeout_secs: Option<i64>,
+    pub stop_timeout_secs: i64,
+    pub launch_wrapper: Option<String>,
+    pub notes: Option<String>,
+    pub files: HashMap<String, serde_json::Value>,  // Server/ file name -> parsed JSON
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceBundleResult {
+    pub success: bool,
+    pub bundle: Option<InstanceBundle>,
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeleteResult {
     pub success: bool,
     pub error: Option<String>,
 }
 
-/// Create a new server instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClearAuthResult {
+    pub success: bool,
+    pub removed_files: Vec<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnboardingStep {
+    pub id: String,
+    pub done: bool,
+}
+
+/// Arguments applied to newly created instances (None = leave empty)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DefaultArgs {
+    pub jvm_args: Option<String>,
+    pub server_args: Option<String>,
+}
+
+/// Onboarding progress; steps are done if recorded or if the app state shows them done
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnboardingState {
+    pub completed: bool,
+    pub java_ready: bool,
+    pub cli_installed: bool,
+    pub has_instances: bool,
+    pub steps: Vec<OnboardingStep>,
+    pub next_step: Option<String>,  // First step that isn't done yet
+}
+
+/// Onboarding steps in the order the wizard shows them
+const ONBOARDING_STEPS: [&str; 3] = ["java", "server_files", "instance"];
+
+/// Credential files written by the server (encrypted persistence) and the downloader CLI.
+/// Only these exact names are ever deleted when logging an instance out.
+const KNOWN_CREDENTIAL_FILES: [&str; 2] = ["auth.enc", ".hytale-downloader-credentials.json"];
+
+/// Format version written to exported instance bundles
+const INSTANCE_BUNDLE_VERSION: u32 = 1;
+
+/// Longest instance notes accepted, in characters
+const MAX_INSTANCE_NOTES_CHARS: usize = 20_000;
+
+/// Create a new server instance.
+/// With `scaffold`, also creates the instance directory layout; the directories
+/// are removed again if the database insert fails.
 #[tauri::command]
 pub async fn create_server_instance(
     pool: State<'_, DbPool>,
     name: String,
     path: String,
     java_path: Option<String>,
+    env_vars: Option<HashMap<String, String>>,
+    scaffold: Option<bool>,
+    notes: Option<String>,
 ) -> Result<InstanceResult, ()> {
     println!("[create_server_instance] Creating instance: {} at {}", name, path);
 
+    if let Some(Err(e)) = notes.as_deref().map(validate_notes) {
+        return Ok(InstanceResult {
+            success: false,
+            instance: None,
+            error: Some(e),
+        });
+    }
+
     // Check if instance already exists at this path
     match database::get_instance_by_path(&pool, &path).await {
-        Ok(Some(_)) => {
+        Ok(Some(existing)) => {
+            let error = if existing.deleted_at.is_some() {
+                "An instance at this path is in the trash. Restore or purge it first"
+            } else {
+                "An instance already exists at this path"
+            };
             return Ok(InstanceResult {
                 success: false,
                 instance: None,
-                error: Some("An instance already exists at this path".to_string()),
+                error: Some(error.to_string()),
             });
         }
         Err(e) => {
@@ -52,15 +148,41 @@ pub async fn create_server_instance(
         _ => {}
     }
 
+    if let Err(e) = check_path_not_nested(&pool, &path).await {
+        return Ok(InstanceResult {
+            success: false,
+            instance: None,
+            error: Some(e),
+        });
+    }
+
+    let created_dirs = if scaffold.unwrap_or(false) {
+        match scaffold_instance_dirs(std::path::Path::new(&path)) {
+            Ok(dirs) => dirs,
+            Err(e) => {
+                return Ok(InstanceResult {
+                    success: false,
+                    instance: None,
+                    error: Some(e),
+                });
+            }
+        }
+    } else {
+        Vec::new()
+    };
+
     let input = CreateInstanceInput {
         name,
         path,
         java_path,
+        env_vars: env_vars.as_ref().map(serialize_env_vars),
+        notes,
     };
 
     match database::create_instance(&pool, input).await {
-        Ok(instance) => {
+        Ok(mut instance) => {
             println!("[create_server_instance] Instance created: {}", instance.id);
+            apply_default_args(&pool, &mut instance).await;
             Ok(InstanceResult {
                 success: true,
                 instance: Some(instance),
@@ -69,6 +191,7 @@ pub async fn create_server_instance(
         }
         Err(e) => {
             println!("[create_server_instance] Error: {}", e);
+            remove_created_dirs(&created_dirs);
             Ok(InstanceResult {
                 success: false,
                 instance: None,
@@ -103,6 +226,70 @@ pub async fn get_server_instances(pool: State<'_, DbPool>) -> Result<InstancesLi
     }
 }
 
+/// Find instances whose name contains `query` (case-insensitive).
+/// With `running_only` set, only instances with a running server are returned.
+#[tauri::command]
+pub async fn search_instances(
+    pool: State<'_, DbPool>,
+    state: State<'_, Arc<Mutex<ServerState>>>,
+    query: String,
+    running_only: Option<bool>,
+) -> Result<InstancesListResult, ()> {
+    match database::search_instances(&pool, query.trim()).await {
+        Ok(mut instances) => {
+            if running_only.unwrap_or(false) {
+                let state_guard = state.lock().unwrap();
+                instances.retain(|instance| state_guard.is_running(&instance.id));
+            }
+
+            Ok(InstancesListResult {
+                success: true,
+                instances,
+                error: None,
+            })
+        }
+        Err(e) => {
+            println!("[search_instances] Error: {}", e);
+            Ok(InstancesListResult {
+                success: false,
+                instances: vec![],
+                error: Some(format!("Failed to search instances: {}", e)),
+            })
+        }
+    }
+}
+
+/// Save a manual ordering of instances (first ID is shown first).
+/// Returns the instances in their new order.
+#[tauri::command]
+pub async fn reorder_instances(
+    pool: State<'_, DbPool>,
+    ordered_ids: Vec<String>,
+) -> Result<InstancesListResult, ()> {
+    println!("[reorder_instances] Reordering {} instances", ordered_ids.len());
+
+    let result = match database::reorder_instances(&pool, &ordered_ids).await {
+        Ok(()) => database::get_all_instances(&pool).await,
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(instances) => Ok(InstancesListResult {
+            success: true,
+            instances,
+            error: None,
+        }),
+        Err(e) => {
+            println!("[reorder_instances] Error: {}", e);
+            Ok(InstancesListResult {
+                success: false,
+                instances: vec![],
+                error: Some(format!("Failed to reorder instances: {}", e)),
+            })
+        }
+    }
+}
+
 /// Get a single instance by ID
 #[tauri::command]
 pub async fn get_server_instance(
@@ -128,7 +315,8 @@ pub async fn get_server_instance(
     }
 }
 
-/// Delete a server instance
+/// Move a server instance to the trash.
+/// The row is kept so it can be restored; files are only removed if `delete_files` is set.
 #[tauri::command]
 pub async fn delete_server_instance(
     pool: State<'_, DbPool>,
@@ -167,10 +355,10 @@ pub async fn delete_server_instance(
         }
     }
 
-    // Delete from database
-    match database::delete_instance(&pool, &id).await {
+    // Soft-delete in database
+    match database::soft_delete_instance(&pool, &id).await {
         Ok(true) => {
-            println!("[delete_server_instance] Instance deleted from database");
+            println!("[delete_server_instance] Instance moved to trash");
             Ok(DeleteResult {
                 success: true,
                 error: None,
@@ -178,7 +366,7 @@ pub async fn delete_server_instance(
         }
         Ok(false) => Ok(DeleteResult {
             success: false,
-            error: Some("Instance not found in database".to_string()),
+            error: Some("Instance not found or already in trash".to_string()),
         }),
         Err(e) => {
             println!("[delete_server_instance] Error: {}", e);
@@ -190,8 +378,126 @@ pub async fn delete_server_instance(
     }
 }
 
+/// List instances in the trash
+#[tauri::command]
+pub async fn list_deleted_instances(pool: State<'_, DbPool>) -> Result<InstancesListResult, ()> {
+    match database::get_deleted_instances(&pool).await {
+        Ok(instances) => Ok(InstancesListResult {
+            success: true,
+            instances,
+            error: None,
+        }),
+        Err(e) => {
+            println!("[list_deleted_instances] Error: {}", e);
+            Ok(InstancesListResult {
+                success: false,
+                instances: vec![],
+                error: Some(format!("Failed to fetch deleted instances: {}", e)),
+            })
+        }
+    }
+}
+
+/// Restore an instance from the trash
+#[tauri::command]
+pub async fn restore_instance(
+    pool: State<'_, DbPool>,
+    id: String,
+) -> Result<InstanceResult, ()> {
+    println!("[restore_instance] Restoring instance: {}", id);
+
+    match database::restore_instance(&pool, &id).await {
+        Ok(true) => match database::get_instance_by_id(&pool, &id).await {
+            Ok(instance) => Ok(InstanceResult {
+                success: true,
+                instance,
+                error: None,
+            }),
+            Err(e) => Ok(InstanceResult {
+                success: false,
+                instance: None,
+                error: Some(format!("Failed to fetch restored instance: {}", e)),
+            }),
+        },
+        Ok(false) => Ok(InstanceResult {
+            success: false,
+            instance: None,
+            error: Some("Instance not found in trash".to_string()),
+        }),
+        Err(e) => {
+            println!("[restore_instance] Error: {}", e);
+            Ok(InstanceResult {
+                success: false,
+                instance: None,
+                error: Some(format!("Failed to restore instance: {}", e)),
+            })
+        }
+    }
+}
+
+/// Permanently delete an instance that is in the trash
+#[tauri::command]
+pub async fn purge_instance(
+    pool: State<'_, DbPool>,
+    id: String,
+    delete_files: Option<bool>,
+) -> Result<DeleteResult, ()> {
+    println!("[purge_instance] Purging instance: {}", id);
+
+    let instance = match database::get_instance_by_id(&pool, &id).await {
+        Ok(Some(i)) => i,
+        Ok(None) => {
+            return Ok(DeleteResult {
+                success: false,
+                error: Some("Instance not found".to_string()),
+            });
+        }
+        Err(e) => {
+            return Ok(DeleteResult {
+                success: false,
+                error: Some(format!("Database error: {}", e)),
+            });
+        }
+    };
+
+    if instance.deleted_at.is_none() {
+        return Ok(DeleteResult {
+            success: false,
+            error: Some("Instance must be moved to the trash before purging".to_string()),
+        });
+    }
+
+    if delete_files.unwrap_or(false) {
+        let path = std::path::Path::new(&instance.path);
+        if path.exists() {
+            if let Err(e) = std::fs::remove_dir_all(path) {
+                println!("[purge_instance] Warning: Failed to delete files: {}", e);
+            }
+        }
+    }
+
+    match database::delete_instance(&pool, &id).await {
+        Ok(true) => Ok(DeleteResult {
+            success: true,
+            error: None,
+        }),
+        Ok(false) => Ok(DeleteResult {
+            success: false,
+            error: Some("Instance not found in database".to_string()),
+        }),
+        Err(e) => {
+            println!("[purge_instance] Error: {}", e);
+            Ok(DeleteResult {
+                success: false,
+                error: Some(format!("Failed to purge instance: {}", e)),
+            })
+        }
+    }
+}
+
 /// Update instance configuration
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn update_server_instance(
     pool: State<'_, DbPool>,
     id: String,
@@ -199,26 +505,57 @@ pub async fn update_server_instance(
     java_path: Option<String>,
     jvm_args: Option<String>,
     server_args: Option<String>,
+    autostart: Option<bool>,
+    env_vars: Option<HashMap<String, String>>,
+    stop_command: Option<String>,
+    stop_command_timeout_secs: Option<u32>,
+    stop_timeout_secs: Option<u32>,
+    launch_wrapper: Option<String>,
+    notes: Option<String>,
 ) -> Result<InstanceResult, ()> {
     println!("[update_server_instance] Updating instance: {}", id);
 
-    match database::update_instance(&pool, &id, name, java_path, jvm_args, server_args).await {
-        Ok(true) => {
-            // Fetch the updated instance
-            match database::get_instance_by_id(&pool, &id).await {
-                Ok(instance) => Ok(InstanceResult {
-                    success: true,
-                    instance,
-                    error: None,
-                }),
-                Err(e) => Ok(InstanceResult {
-                    success: false,
-                    instance: None,
-                    error: Some(format!("Failed to fetch updated instance: {}", e)),
-                }),
-            }
-        }
-        Ok(false) => Ok(InstanceResult {
+    if let Some(Err(e)) = notes.as_deref().map(validate_notes) {
+        return Ok(InstanceResult {
+            success: false,
+            instance: None,
+            error: Some(e),
+        });
+    }
+
+    if let Some(Err(e)) = launch_wrapper.as_deref().map(validate_launch_wrapper) {
+        return Ok(InstanceResult {
+            success: false,
+            instance: None,
+            error: Some(format!("Invalid launch wrapper: {}", e)),
+        });
+    }
+
+    let env_vars = env_vars.as_ref().map(serialize_env_vars);
+
+    match database::update_instance(
+        &pool,
+        &id,
+        name,
+        java_path,
+        jvm_args,
+        server_args,
+        autostart,
+        env_vars,
+        stop_command,
+        stop_command_timeout_secs,
+        stop_timeout_secs,
+        launch_wrapper,
+        notes,
+    )
+    .await
+    {
+        Ok(Some(instance)) => Ok(InstanceResult {
+            success: true,
+            instance: Some(instance),
+            error: None,
+        }),
+        Ok(None) => Ok(InstanceResult {
             success: false,
             instance: None,
             error: Some("Instance not found".to_string()),
@@ -234,6 +571,580 @@ pub async fn update_server_instance(
     }
 }
 
+/// Import an existing server directory as an instance.
+/// Returns the existing instance if one already points at this path.
+#[tauri::command]
+pub async fn import_instance(
+    pool: State<'_, DbPool>,
+    name: String,
+    path: String,
+) -> Result<InstanceResult, ()> {
+    println!("[import_instance] Importing {} from {}", name, path);
+
+    match database::get_instance_by_path(&pool, &path).await {
+        Ok(Some(existing)) if existing.deleted_at.is_some() => {
+            println!("[import_instance] Restoring trashed instance: {}", existing.id);
+            if let Err(e) = database::restore_instance(&pool, &existing.id).await {
+                return Ok(InstanceResult {
+                    success: false,
+                    instance: None,
+                    error: Some(format!("Failed to restore instance: {}", e)),
+                });
+            }
+            let instance = database::get_instance_by_id(&pool, &existing.id).await.ok().flatten();
+            return Ok(InstanceResult {
+                success: instance.is_some(),
+                instance,
+                error: None,
+            });
+        }
+        Ok(Some(existing)) => {
+            println!("[import_instance] Instance already exists: {}", existing.id);
+            return Ok(InstanceResult {
+                success: true,
+                instance: Some(existing),
+                error: None,
+            });
+        }
+        Err(e) => {
+            return Ok(InstanceResult {
+                success: false,
+                instance: None,
+                error: Some(format!("Database error: {}", e)),
+            });
+        }
+        _ => {}
+    }
+
+    if let Err(e) = check_path_not_nested(&pool, &path).await {
+        return Ok(InstanceResult {
+            success: false,
+            instance: None,
+            error: Some(e),
+        });
+    }
+
+    let status = check_server_files(path.clone());
+    if !status.has_server_jar || !status.has_assets {
+        let missing = match (status.has_server_jar, status.has_assets) {
+            (false, false) => "Server/HytaleServer.jar and Assets.zip",
+            (false, true) => "Server/HytaleServer.jar",
+            _ => "Assets.zip",
+        };
+        return Ok(InstanceResult {
+            success: false,
+            instance: None,
+            error: Some(format!("Not a valid server directory: missing {}", missing)),
+        });
+    }
+
+    let input = CreateInstanceInput {
+        name,
+        path: path.clone(),
+        java_path: None,
+        env_vars: None,
+        notes: None,
+    };
+
+    let mut instance = match database::create_instance(&pool, input).await {
+        Ok(i) => i,
+        Err(e) => {
+            println!("[import_instance] Error: {}", e);
+            return Ok(InstanceResult {
+                success: false,
+                instance: None,
+                error: Some(format!("Failed to create instance: {}", e)),
+            });
+        }
+    };
+
+    // Best-effort version detection from the server jar
+    let version = tokio::task::spawn_blocking(move || read_installed_version(&path))
+        .await
+        .ok()
+        .flatten();
+    if let Some(version) = version {
+        if database::update_instance_version(&pool, &instance.id, &version).await.is_ok() {
+            instance.installed_version = Some(version);
+        }
+    }
+
+    println!("[import_instance] Instance imported: {}", instance.id);
+    Ok(InstanceResult {
+        success: true,
+        instance: Some(instance),
+        error: None,
+    })
+}
+
+/// Duplicate an instance, optionally copying its server files to a new path.
+/// Auth state is not carried over, so the clone has to authenticate again.
+#[tauri::command]
+pub async fn duplicate_instance(
+    pool: State<'_, DbPool>,
+    id: String,
+    new_name: String,
+    new_path: String,
+    copy_files: bool,
+) -> Result<InstanceResult, ()> {
+    println!("[duplicate_instance] Duplicating {} to {} at {}", id, new_name, new_path);
+
+    let fail = |error: String| -> Result<InstanceResult, ()> {
+        println!("[duplicate_instance] Error: {}", error);
+        Ok(InstanceResult {
+            success: false,
+            instance: None,
+            error: Some(error),
+        })
+    };
+
+    let source = match database::get_instance_by_id(&pool, &id).await {
+        Ok(Some(i)) => i,
+        Ok(None) => return fail("Instance not found".to_string()),
+        Err(e) => return fail(format!("Database error: {}", e)),
+    };
+
+    // Enforce the unique path constraint up front for a clear error
+    match database::get_instance_by_path(&pool, &new_path).await {
+        Ok(Some(_)) => return fail("An instance already exists at this path".to_string()),
+        Err(e) => return fail(format!("Database error: {}", e)),
+        _ => {}
+    }
+    if let Err(e) = check_path_not_nested(&pool, &new_path).await {
+        return fail(e);
+    }
+
+    if copy_files {
+        let src = std::path::Path::new(&source.path);
+        let dest = std::path::Path::new(&new_path);
+
+        if !src.exists() {
+            return fail("Source instance directory not found".to_string());
+        }
+        if dest.exists() && dest.read_dir().map(|mut d| d.next().is_some()).unwrap_or(true) {
+            return fail("Destination directory is not empty".to_string());
+        }
+
+        let (src, dest) = (src.to_path_buf(), dest.to_path_buf());
+        let copy_result = tokio::task::spawn_blocking(move || {
+            let count = copy_dir_recursive(&src, &dest)?;
+            remove_auth_credentials(&dest.join("Server"));
+            Ok::<u32, std::io::Error>(count)
+        })
+        .await;
+
+        match copy_result {
+            Ok(Ok(count)) => println!("[duplicate_instance] Copied {} files", count),
+            Ok(Err(e)) => {
+                let _ = std::fs::remove_dir_all(&new_path);
+                return fail(format!("Failed to copy server files: {}", e));
+            }
+            Err(e) => {
+                let _ = std::fs::remove_dir_all(&new_path);
+                return fail(format!("Failed to copy server files: {}", e));
+            }
+        }
+    }
+
+    // Don't leave copied files behind when the instance can't be registered
+    let copied_dir = copy_files.then(|| new_path.clone());
+    let remove_copy = || {
+        if let Some(dir) = &copied_dir {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    };
+
+    let input = CreateInstanceInput {
+        name: new_name,
+        path: new_path,
+        java_path: source.java_path.clone(),
+        env_vars: source.env_vars.clone(),
+        notes: source.notes.clone(),
+    };
+
+    let instance = match database::create_instance(&pool, input).await {
+        Ok(i) => i,
+        Err(e) => {
+            remove_copy();
+            return fail(format!("Failed to create instance: {}", e));
+        }
+    };
+
+    if let Err(e) = database::update_instance(
+        &pool,
+        &instance.id,
+        None,
+        None,
+        source.jvm_args.clone(),
+        source.server_args.clone(),
+        None,
+        None,
+        source.stop_command.clone(),
+        source.stop_command_timeout_secs.map(|t| t as u32),
+        Some(source.stop_timeout_secs.max(0) as u32),
+        source.launch_wrapper.clone(),
+        None,
+    )
+    .await
+    {
+        let _ = database::delete_instance(&pool, &instance.id).await;
+        remove_copy();
+        return fail(format!("Failed to copy instance settings: {}", e));
+    }
+
+    if copy_files {
+        if let Some(ref version) = source.installed_version {
+            let _ = database::update_instance_version(&pool, &instance.id, version).await;
+        }
+    }
+
+    match database::get_instance_by_id(&pool, &instance.id).await {
+        Ok(instance) => {
+            println!("[duplicate_instance] Instance duplicated");
+            Ok(InstanceResult {
+                success: true,
+                instance,
+                error: None,
+            })
+        }
+        Err(e) => fail(format!("Failed to fetch duplicated instance: {}", e)),
+    }
+}
+
+/// Export an instance's settings and its config.json, whitelist, bans and permissions
+/// as a portable bundle. Machine-specific and secret values are left out.
+#[tauri::command]
+pub async fn export_instance_config(
+    pool: State<'_, DbPool>,
+    instance_id: String,
+) -> Result<InstanceBundleResult, ()> {
+    let fail = |error: String| -> Result<InstanceBundleResult, ()> {
+        println!("[export_instance_config] Error: {}", error);
+        Ok(InstanceBundleResult {
+            success: false,
+            bundle: None,
+            error: Some(error),
+        })
+    };
+
+    let instance = match database::get_instance_by_id(&pool, &instance_id).await {
+        Ok(Some(i)) => i,
+        Ok(None) => return fail("Instance not found".to_string()),
+        Err(e) => return fail(format!("Database error: {}", e)),
+    };
+
+    let server_dir = std::path::Path::new(&instance.path).join("Server");
+    let mut files = HashMap::new();
+    for name in PRESERVED_CONFIG_FILES {
+        let path = server_dir.join(name);
+        if !path.is_file() {
+            continue;
+        }
+        let content = match std::fs::read_to_string(&path) {
+            Ok(c) => c,
+            Err(e) => return fail(format!("Failed to read {}: {}", name, e)),
+        };
+        let mut value: serde_json::Value = match serde_json::from_str(&content) {
+            Ok(v) => v,
+            Err(e) => return fail(format!("Failed to parse {}: {}", name, e)),
+        };
+        if name == "config.json" {
+            if let Some(config) = value.as_object_mut() {
+                config.remove("Password");
+            }
+        }
+        files.insert(name.to_string(), value);
+    }
+
+    println!("[export_instance_config] Exported {} with {} files", instance_id, files.len());
+    Ok(InstanceBundleResult {
+        success: true,
+        bundle: Some(InstanceBundle {
+            version: INSTANCE_BUNDLE_VERSION,
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            name: instance.name,
+            jvm_args: instance.jvm_args,
+            server_args: instance.server_args,
+            autostart: instance.autostart,
+            stop_command: instance.stop_command,
+            stop_command_timeout_secs: instance.stop_command_timeout_secs,
+            stop_timeout_secs: instance.stop_timeout_secs,
+            launch_wrapper: instance.launch_wrapper,
+            notes: instance.notes,
+            files,
+        }),
+        error: None,
+    })
+}
+
+/// Create an instance at `new_path` from an exported bundle, writing its config files
+/// into `new_path`/Server. If any step fails, the instance and written files are rolled back.
+#[tauri::command]
+pub async fn import_instance_config(
+    pool: State<'_, DbPool>,
+    bundle: InstanceBundle,
+    new_path: String,
+) -> Result<InstanceResult, ()> {
+    println!("[import_instance_config] Importing {} to {}", bundle.name, new_path);
+
+    let fail = |error: String| -> Result<InstanceResult, ()> {
+        println!("[import_instance_config] Error: {}", error);
+        Ok(InstanceResult {
+            success: false,
+            instance: None,
+            error: Some(error),
+        })
+    };
+
+    if bundle.version != INSTANCE_BUNDLE_VERSION {
+        return fail(format!(
+            "Unsupported bundle version {} (expected {})",
+            bundle.version, INSTANCE_BUNDLE_VERSION
+        ));
+    }
+    if let Some(name) = bundle.files.keys().find(|name| !PRESERVED_CONFIG_FILES.contains(&name.as_str())) {
+        return fail(format!("Unexpected file in bundle: {}", name));
+    }
+    if let Some(Err(e)) = bundle.notes.as_deref().map(validate_notes) {
+        return fail(e);
+    }
+    if let Some(Err(e)) = bundle.launch_wrapper.as_deref().map(validate_launch_wrapper) {
+        return fail(format!("Invalid launch wrapper: {}", e));
+    }
+
+    // Serialize everything before touching the disk or database
+    let mut contents = Vec::new();
+    for (name, value) in &bundle.files {
+        match serde_json::to_string_pretty(value) {
+            Ok(c) => contents.push((name.clone(), c)),
+            Err(e) => return fail(format!("Failed to serialize {}: {}", name, e)),
+        }
+    }
+
+    match database::get_instance_by_path(&pool, &new_path).await {
+        Ok(Some(_)) => return fail("An instance already exists at this path".to_string()),
+        Err(e) => return fail(format!("Database error: {}", e)),
+        _ => {}
+    }
+    if let Err(e) = check_path_not_nested(&pool, &new_path).await {
+        return fail(e);
+    }
+
+    let input = CreateInstanceInput {
+        name: bundle.name.clone(),
+        path: new_path.clone(),
+        java_path: None,
+        env_vars: None,
+        notes: bundle.notes.clone(),
+    };
+    let instance = match database::create_instance(&pool, input).await {
+        Ok(i) => i,
+        Err(e) => return fail(format!("Failed to create instance: {}", e)),
+    };
+
+    let rollback = |error: String| {
+        let pool = pool.inner().clone();
+        let id = instance.id.clone();
+        async move {
+            let _ = database::delete_instance(&pool, &id).await;
+            fail(error)
+        }
+    };
+
+    if let Err(e) = database::update_instance(
+        &pool,
+        &instance.id,
+        None,
+        None,
+        bundle.jvm_args.clone(),
+        bundle.server_args.clone(),
+        Some(bundle.autostart),
+        None,
+        bundle.stop_command.clone(),
+        bundle.stop_command_timeout_secs.map(|t| t.max(0) as u32),
+        Some(bundle.stop_timeout_secs.max(0) as u32),
+        bundle.launch_wrapper.clone(),
+        None,
+    )
+    .await
+    {
+        return rollback(format!("Failed to apply instance settings: {}", e)).await;
+    }
+
+    let server_dir = std::path::Path::new(&new_path).join("Server");
+    if let Err(e) = write_bundle_files(&server_dir, &contents) {
+        return rollback(e).await;
+    }
+
+    match database::get_instance_by_id(&pool, &instance.id).await {
+        Ok(instance) => {
+            println!("[import_instance_config] Imported {} file(s)", contents.len());
+            Ok(InstanceResult {
+                success: true,
+                instance,
+                error: None,
+            })
+        }
+        Err(e) => fail(format!("Failed to fetch imported instance: {}", e)),
+    }
+}
+
+/// Write bundle files into a Server directory. On failure, files that existed are
+/// restored and new ones removed, so the directory ends up as it was.
+fn write_bundle_files(server_dir: &std::path::Path, contents: &[(String, String)]) -> Result<(), String> {
+    std::fs::create_dir_all(server_dir).map_err(|e| format!("Failed to create Server directory: {}", e))?;
+
+    let mut written: Vec<(std::path::PathBuf, Option<Vec<u8>>)> = Vec::new();
+    for (name, content) in contents {
+        let path = server_dir.join(name);
+        let previous = std::fs::read(&path).ok();
+        if let Err(e) = write_file_atomic(&path, content) {
+            for (path, previous) in written.into_iter().rev() {
+                let _ = match previous {
+                    Some(bytes) => std::fs::write(&path, bytes),
+                    None => std::fs::remove_file(&path),
+                };
+            }
+            return Err(format!("Failed to write {}: {}", name, e));
+        }
+        written.push((path, previous));
+    }
+
+    Ok(())
+}
+
+/// Refuse a path that is the same as, inside, or a parent of another instance's
+/// directory (including trashed ones), since copies and deletes would then
+/// reach into the other instance
+async fn check_path_not_nested(pool: &DbPool, path: &str) -> Result<(), String> {
+    let mut instances = database::get_all_instances(pool)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+    instances.extend(
+        database::get_deleted_instances(pool)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?,
+    );
+
+    let new_path = normalize_path(std::path::Path::new(path));
+    for instance in instances {
+        let existing = normalize_path(std::path::Path::new(&instance.path));
+        let relation = if new_path == existing {
+            "is the directory of"
+        } else if new_path.starts_with(&existing) {
+            "is inside"
+        } else if existing.starts_with(&new_path) {
+            "contains"
+        } else {
+            continue;
+        };
+        return Err(format!(
+            "This path {} the instance \"{}\" ({})",
+            relation, instance.name, instance.path
+        ));
+    }
+
+    Ok(())
+}
+
+/// Resolve a path for comparison: canonicalize its deepest existing ancestor and
+/// re-append the rest, so paths that don't exist yet compare correctly too
+fn normalize_path(path: &std::path::Path) -> std::path::PathBuf {
+    let mut existing = path;
+    let mut rest = Vec::new();
+    loop {
+        if let Ok(resolved) = existing.canonicalize() {
+            return rest.iter().rev().fold(resolved, |acc, part| acc.join(part));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                rest.push(name);
+                existing = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+/// Configured default arguments; empty settings count as unset
+async fn read_default_args(pool: &DbPool) -> DefaultArgs {
+    DefaultArgs {
+        jvm_args: read_non_empty_setting(pool, "default_jvm_args").await,
+        server_args: read_non_empty_setting(pool, "default_server_args").await,
+    }
+}
+
+async fn read_non_empty_setting(pool: &DbPool, key: &str) -> Option<String> {
+    database::get_setting(pool, key)
+        .await
+        .ok()
+        .flatten()
+        .filter(|v| !v.trim().is_empty())
+}
+
+/// Give a newly created instance the configured default arguments (best effort)
+async fn apply_default_args(pool: &DbPool, instance: &mut Instance) {
+    let defaults = read_default_args(pool).await;
+    if defaults.jvm_args.is_none() && defaults.server_args.is_none() {
+        return;
+    }
+
+    match database::update_instance(
+        pool,
+        &instance.id,
+        None,
+        None,
+        defaults.jvm_args.clone(),
+        defaults.server_args.clone(),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await
+    {
+        Ok(Some(updated)) => *instance = updated,
+        Ok(None) => {}
+        Err(e) => println!("[create_server_instance] Failed to apply default args: {}", e),
+    }
+}
+
+/// Serialize an environment variable map for storage
+fn serialize_env_vars(env_vars: &HashMap<String, String>) -> String {
+    serde_json::to_string(env_vars).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// Reject notes longer than MAX_INSTANCE_NOTES_CHARS
+fn validate_notes(notes: &str) -> Result<(), String> {
+    if notes.chars().count() > MAX_INSTANCE_NOTES_CHARS {
+        return Err(format!("Notes are too long (max {} characters)", MAX_INSTANCE_NOTES_CHARS));
+    }
+    Ok(())
+}
+
+/// Parse stored environment variables (invalid JSON yields an empty map)
+pub fn parse_env_vars(env_vars: Option<&str>) -> HashMap<String, String> {
+    env_vars
+        .and_then(|json| serde_json::from_str(json).ok())
+        .unwrap_or_default()
+}
+
+/// Remove stored server credentials from a copied Server directory
+fn remove_auth_credentials(server_dir: &std::path::Path) {
+    if let Ok(entries) = std::fs::read_dir(server_dir) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_lowercase();
+            let is_credentials = name.contains("credential") || name.starts_with("auth.");
+            if is_credentials && entry.path().is_file() {
+                let _ = std::fs::remove_file(entry.path());
+            }
+        }
+    }
+}
+
 /// Check if onboarding is completed
 #[tauri::command]
 pub async fn is_onboarding_complete(pool: State<'_, DbPool>) -> Result<bool, ()> {
@@ -252,24 +1163,178 @@ pub async fn complete_onboarding(pool: State<'_, DbPool>) -> Result<bool, ()> {
     }
 }
 
+/// Get per-step onboarding progress so a partial setup can resume where it left off
+#[tauri::command]
+pub async fn get_onboarding_state(app: AppHandle, pool: State<'_, DbPool>) -> Result<OnboardingState, ()> {
+    let completed = database::is_onboarding_completed(&pool).await.unwrap_or(false);
+
+    // Derive what we can from the actual state of the machine
+    let java_ready = check_java(app.clone(), None).await.is_valid;
+    let cli_installed = find_downloader_with_app(Some(&app)).is_some();
+    let has_instances = database::get_all_instances(&pool)
+        .await
+        .map(|instances| !instances.is_empty())
+        .unwrap_or(false);
+
+    let mut steps = Vec::new();
+    for id in ONBOARDING_STEPS {
+        let recorded = database::get_setting(&pool, &onboarding_step_key(id))
+            .await
+            .ok()
+            .flatten()
+            .map(|v| v == "true")
+            .unwrap_or(false);
+        let derived = match id {
+            "java" => java_ready,
+            "server_files" => cli_installed || has_instances,
+            "instance" => has_instances,
+            _ => false,
+        };
+        steps.push(OnboardingStep {
+            id: id.to_string(),
+            done: completed || recorded || derived,
+        });
+    }
+
+    let next_step = steps.iter().find(|s| !s.done).map(|s| s.id.clone());
+
+    Ok(OnboardingState {
+        completed,
+        java_ready,
+        cli_installed,
+        has_instances,
+        steps,
+        next_step,
+    })
+}
+
+/// Record progress on an onboarding step ("java", "server_files" or "instance")
+#[tauri::command]
+pub async fn set_onboarding_step(pool: State<'_, DbPool>, step: String, done: bool) -> Result<bool, ()> {
+    if !ONBOARDING_STEPS.contains(&step.as_str()) {
+        println!("[set_onboarding_step] Unknown step: {}", step);
+        return Ok(false);
+    }
+
+    match database::set_setting(&pool, &onboarding_step_key(&step), if done { "true" } else { "false" }).await {
+        Ok(()) => Ok(true),
+        Err(_) => Ok(false),
+    }
+}
+
+fn onboarding_step_key(step: &str) -> String {
+    format!("onboarding_step_{}", step)
+}
+
+/// Check if autostart is globally disabled (useful when troubleshooting)
+#[tauri::command]
+pub async fn is_autostart_disabled(pool: State<'_, DbPool>) -> Result<bool, ()> {
+    match database::get_setting(&pool, "autostart_disabled").await {
+        Ok(value) => Ok(value.map(|v| v == "true").unwrap_or(false)),
+        Err(_) => Ok(false),
+    }
+}
+
+/// Globally enable or disable autostart of instances on app launch
+#[tauri::command]
+pub async fn set_autostart_disabled(pool: State<'_, DbPool>, disabled: bool) -> Result<bool, ()> {
+    match database::set_setting(&pool, "autostart_disabled", if disabled { "true" } else { "false" }).await {
+        Ok(()) => Ok(true),
+        Err(_) => Ok(false),
+    }
+}
+
+/// Get the JVM/server arguments given to newly created instances
+#[tauri::command]
+pub async fn get_default_args(pool: State<'_, DbPool>) -> Result<DefaultArgs, ()> {
+    Ok(read_default_args(&pool).await)
+}
+
+/// Set the JVM/server arguments given to newly created instances (empty clears them)
+#[tauri::command]
+pub async fn set_default_args(pool: State<'_, DbPool>, args: DefaultArgs) -> Result<bool, ()> {
+    let jvm_args = args.jvm_args.unwrap_or_default();
+    let server_args = args.server_args.unwrap_or_default();
+
+    let saved = database::set_setting(&pool, "default_jvm_args", jvm_args.trim()).await.is_ok()
+        && database::set_setting(&pool, "default_server_args", server_args.trim()).await.is_ok();
+    Ok(saved)
+}
+
+/// Get a per-instance setting (None if it was never set)
+#[tauri::command]
+pub async fn get_instance_setting(
+    pool: State<'_, DbPool>,
+    instance_id: String,
+    key: String,
+) -> Result<Option<String>, ()> {
+    match database::get_instance_setting(&pool, &instance_id, &key).await {
+        Ok(value) => Ok(value),
+        Err(e) => {
+            println!("[get_instance_setting] Error reading {} for {}: {}", key, instance_id, e);
+            Ok(None)
+        }
+    }
+}
+
+/// Store a per-instance setting for an existing instance
+#[tauri::command]
+pub async fn set_instance_setting(
+    pool: State<'_, DbPool>,
+    instance_id: String,
+    key: String,
+    value: String,
+) -> Result<bool, ()> {
+    if key.trim().is_empty() {
+        return Ok(false);
+    }
+
+    match database::get_instance_by_id(&pool, &instance_id).await {
+        Ok(Some(_)) => {}
+        _ => {
+            println!("[set_instance_setting] Instance not found: {}", instance_id);
+            return Ok(false);
+        }
+    }
+
+    match database::set_instance_setting(&pool, &instance_id, &key, &value).await {
+        Ok(()) => Ok(true),
+        Err(e) => {
+            println!("[set_instance_setting] Error saving {} for {}: {}", key, instance_id, e);
+            Ok(false)
+        }
+    }
+}
+
+/// Result of check_instance_paths
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstancePathsCheck {
+    /// Ids of the instances whose directory doesn't exist
+    pub missing: Vec<String>,
+    /// Per-path existence and absolute locations, keyed by instance id
+    pub paths: HashMap<String, InstancePaths>,
+}
+
 /// Check which instance paths exist on disk
 #[tauri::command]
-pub async fn check_instance_paths(paths: Vec<(String, String)>) -> Result<Vec<String>, ()> {
+pub async fn check_instance_paths(paths: Vec<(String, String)>) -> Result<InstancePathsCheck, ()> {
     // paths is a Vec of (instance_id, path) tuples
-    // Returns Vec of instance_ids whose paths do NOT exist
-    let missing: Vec<String> = paths
-        .into_iter()
-        .filter_map(|(id, path)| {
-            let p = std::path::Path::new(&path);
-            if !p.exists() {
-                Some(id)
-            } else {
-                None
-            }
-        })
-        .collect();
+    let mut check = InstancePathsCheck {
+        missing: Vec::new(),
+        paths: HashMap::new(),
+    };
+
+    for (id, path) in paths {
+        let resolved = resolve_instance_paths(std::path::Path::new(&path));
+        if !resolved.root.exists {
+            check.missing.push(id.clone());
+        } else if !resolved.missing.is_empty() {
+            println!("[check_instance_paths] {} missing: {}", id, resolved.missing.join(", "));
+        }
+        check.paths.insert(id, resolved);
+    }
 
-    Ok(missing)
+    Ok(check)
 }
 
 /// Update instance auth status
@@ -304,3 +1369,69 @@ pub async fn update_instance_auth_status(
         }
     }
 }
+
+/// Log an instance out: delete its stored credential files and reset its auth status.
+/// The server must be stopped, since a running server keeps its tokens in memory.
+#[tauri::command]
+pub async fn clear_instance_auth(
+    pool: State<'_, DbPool>,
+    state: State<'_, Arc<Mutex<ServerState>>>,
+    instance_id: String,
+) -> Result<ClearAuthResult, ()> {
+    let fail = |error: String| ClearAuthResult {
+        success: false,
+        removed_files: Vec::new(),
+        error: Some(error),
+    };
+
+    if state.lock().unwrap().is_running(&instance_id) {
+        return Ok(fail("Stop the server before clearing authentication".to_string()));
+    }
+
+    let instance = match database::get_instance_by_id(&pool, &instance_id).await {
+        Ok(Some(instance)) => instance,
+        Ok(None) => return Ok(fail("Instance not found".to_string())),
+        Err(e) => return Ok(fail(format!("Failed to load instance: {}", e))),
+    };
+
+    let base = std::path::Path::new(&instance.path);
+    let mut removed_files = Vec::new();
+
+    // The server runs from Server/, the downloader may have been run from the instance root
+    for dir in [base.join("Server"), base.to_path_buf()] {
+        for name in KNOWN_CREDENTIAL_FILES {
+            let file = dir.join(name);
+            if !file.is_file() {
+                continue;
+            }
+            if let Err(e) = std::fs::remove_file(&file) {
+                return Ok(ClearAuthResult {
+                    success: false,
+                    removed_files,
+                    error: Some(format!("Failed to remove {}: {}", file.display(), e)),
+                });
+            }
+            removed_files.push(file.to_string_lossy().to_string());
+        }
+    }
+
+    println!(
+        "[clear_instance_auth] Removed {} credential file(s) for {}",
+        removed_files.len(),
+        instance_id
+    );
+
+    if let Err(e) = database::reset_instance_auth(&pool, &instance_id).await {
+        return Ok(ClearAuthResult {
+            success: false,
+            removed_files,
+            error: Some(format!("Failed to reset auth status: {}", e)),
+        });
+    }
+
+    Ok(ClearAuthResult {
+        success: true,
+        removed_files,
+        error: None,
+    })
+}
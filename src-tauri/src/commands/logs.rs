@@ -1,7 +1,17 @@
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
 use std::fs::{self, File};
-use std::io::{BufRead, BufReader, Seek, SeekFrom};
-use std::path::Path;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+/// How often a followed log file is checked for new data
+const LOG_FOLLOW_INTERVAL_MS: u64 = 1000;
+
+/// Lines kept per follow between polls; older ones are dropped first
+const LOG_FOLLOW_MAX_BUFFERED: usize = 10_000;
 
 // ============================================================================
 // Types
@@ -39,6 +49,65 @@ pub struct LogFilesResult {
     pub error: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogRetentionResult {
+    pub success: bool,
+    pub deleted_files: Vec<String>,
+    pub bytes_freed: u64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompressLogsResult {
+    pub success: bool,
+    pub compressed_files: Vec<String>,
+    pub bytes_saved: u64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogFollowResult {
+    pub success: bool,
+    pub lines: Vec<LogLine>,
+    pub dropped_lines: usize,  // Lines discarded because the buffer filled up since the last poll
+    pub rotated: bool,         // The file shrank (rotated or truncated) since the last poll
+    pub file_size: u64,
+    pub error: Option<String>,
+}
+
+/// Lines collected by a follow task, drained by poll_log_follow
+#[derive(Default)]
+struct LogFollowBuffer {
+    lines: VecDeque<LogLine>,
+    dropped_lines: usize,
+    rotated: bool,
+    file_size: u64,
+}
+
+struct LogFollow {
+    buffer: Arc<Mutex<LogFollowBuffer>>,
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+/// Active log follows keyed by handle
+pub struct LogFollowState {
+    follows: HashMap<String, LogFollow>,
+}
+
+impl LogFollowState {
+    pub fn new() -> Self {
+        Self {
+            follows: HashMap::new(),
+        }
+    }
+}
+
+impl Default for LogFollowState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 // ============================================================================
 // Commands
 // ============================================================================
@@ -64,7 +133,7 @@ pub fn list_log_files(instance_path: String) -> LogFilesResult {
                 let path = entry.path();
                 if path.is_file() {
                     if let Some(ext) = path.extension() {
-                        if ext == "log" || ext == "txt" {
+                        if ext == "log" || ext == "txt" || ext == "gz" {
                             let metadata = fs::metadata(&path).ok();
                             let modified = metadata.as_ref().and_then(|m| {
                                 m.modified().ok().map(|t| {
@@ -140,8 +209,13 @@ pub fn read_log_file(
         }
     };
 
-    let reader = BufReader::new(file);
-    let all_lines: Vec<String> = reader.lines().map_while(Result::ok).collect();
+    // Logs compressed by compress_old_logs are decompressed on the fly
+    let reader: Box<dyn Read> = if path.extension().map(|e| e == "gz").unwrap_or(false) {
+        Box::new(flate2::read::GzDecoder::new(file))
+    } else {
+        Box::new(file)
+    };
+    let all_lines: Vec<String> = lossy_lines(BufReader::new(reader)).map_while(Result::ok).collect();
     let total_lines = all_lines.len();
 
     // Apply offset and limit
@@ -237,9 +311,7 @@ pub fn tail_log_file(
         };
     }
 
-    let reader = BufReader::new(file);
-    let new_lines: Vec<LogLine> = reader
-        .lines()
+    let new_lines: Vec<LogLine> = lossy_lines(BufReader::new(file))
         .map_while(Result::ok)
         .enumerate()
         .map(|(i, line)| parse_log_line(i + 1, &line))
@@ -256,10 +328,375 @@ pub fn tail_log_file(
     }
 }
 
+/// Gzip `.log` files older than `older_than_days`, replacing `foo.log` with `foo.log.gz`.
+/// The most recently modified log is skipped since the server may still be writing to it.
+#[tauri::command]
+pub async fn compress_old_logs(instance_path: String, older_than_days: u64) -> CompressLogsResult {
+    let logs_dir = Path::new(&instance_path).join("Server").join("logs");
+
+    let result = tokio::task::spawn_blocking(move || compress_logs_in(&logs_dir, older_than_days)).await;
+
+    match result {
+        Ok(Ok((compressed_files, bytes_saved))) => {
+            println!(
+                "[compress_old_logs] Compressed {} files, saved {} bytes",
+                compressed_files.len(),
+                bytes_saved
+            );
+            CompressLogsResult {
+                success: true,
+                compressed_files,
+                bytes_saved,
+                error: None,
+            }
+        }
+        Ok(Err(e)) => CompressLogsResult {
+            success: false,
+            compressed_files: vec![],
+            bytes_saved: 0,
+            error: Some(e),
+        },
+        Err(e) => CompressLogsResult {
+            success: false,
+            compressed_files: vec![],
+            bytes_saved: 0,
+            error: Some(format!("Compression task failed: {}", e)),
+        },
+    }
+}
+
+/// Delete logs beyond `max_files` (newest kept) or older than `max_age_days`.
+/// The active log (the newest `.log`) is always kept and counts toward `max_files`.
+#[tauri::command]
+pub async fn apply_log_retention(
+    instance_path: String,
+    max_files: Option<usize>,
+    max_age_days: Option<u64>,
+) -> LogRetentionResult {
+    let result = tokio::task::spawn_blocking(move || {
+        let listing = list_log_files(instance_path);
+        if !listing.success {
+            return Err(listing.error.unwrap_or_default());
+        }
+        Ok(apply_retention(listing.files, max_files, max_age_days))
+    })
+    .await;
+
+    match result {
+        Ok(Ok((deleted_files, bytes_freed))) => {
+            println!(
+                "[apply_log_retention] Deleted {} files, freed {} bytes",
+                deleted_files.len(),
+                bytes_freed
+            );
+            LogRetentionResult {
+                success: true,
+                deleted_files,
+                bytes_freed,
+                error: None,
+            }
+        }
+        Ok(Err(e)) => LogRetentionResult {
+            success: false,
+            deleted_files: vec![],
+            bytes_freed: 0,
+            error: Some(e),
+        },
+        Err(e) => LogRetentionResult {
+            success: false,
+            deleted_files: vec![],
+            bytes_freed: 0,
+            error: Some(format!("Retention task failed: {}", e)),
+        },
+    }
+}
+
+/// Follow a log file in the background, buffering appended lines until polled.
+/// Starts at the end of the file unless `from_byte` is given. Returns a handle
+/// for poll_log_follow/stop_log_follow.
+#[tauri::command]
+pub fn start_log_follow(
+    state: State<'_, Arc<Mutex<LogFollowState>>>,
+    file_path: String,
+    from_byte: Option<u64>,
+) -> Result<String, String> {
+    let path = PathBuf::from(&file_path);
+    let file_size = fs::metadata(&path)
+        .map_err(|e| format!("Failed to open log file: {}", e))?
+        .len();
+
+    let position = from_byte.unwrap_or(file_size).min(file_size);
+    let buffer = Arc::new(Mutex::new(LogFollowBuffer {
+        file_size,
+        ..Default::default()
+    }));
+
+    let handle = uuid::Uuid::new_v4().to_string();
+    let task = tauri::async_runtime::spawn(follow_log(path, position, buffer.clone()));
+
+    println!("[log_follow] Following {} from byte {} ({})", file_path, position, handle);
+    state
+        .lock()
+        .unwrap()
+        .follows
+        .insert(handle.clone(), LogFollow { buffer, task });
+
+    Ok(handle)
+}
+
+/// Drain the lines buffered by a log follow since the last poll
+#[tauri::command]
+pub fn poll_log_follow(state: State<'_, Arc<Mutex<LogFollowState>>>, handle: String) -> LogFollowResult {
+    let buffer = match state.lock().unwrap().follows.get(&handle) {
+        Some(follow) => follow.buffer.clone(),
+        None => {
+            return LogFollowResult {
+                success: false,
+                lines: vec![],
+                dropped_lines: 0,
+                rotated: false,
+                file_size: 0,
+                error: Some("Log follow not found".to_string()),
+            };
+        }
+    };
+
+    let mut buffer = buffer.lock().unwrap();
+    LogFollowResult {
+        success: true,
+        lines: buffer.lines.drain(..).collect(),
+        dropped_lines: std::mem::take(&mut buffer.dropped_lines),
+        rotated: std::mem::take(&mut buffer.rotated),
+        file_size: buffer.file_size,
+        error: None,
+    }
+}
+
+/// Stop a log follow and discard its buffer
+#[tauri::command]
+pub fn stop_log_follow(state: State<'_, Arc<Mutex<LogFollowState>>>, handle: String) -> bool {
+    match state.lock().unwrap().follows.remove(&handle) {
+        Some(follow) => {
+            follow.task.abort();
+            println!("[log_follow] Stopped {}", handle);
+            true
+        }
+        None => false,
+    }
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
 
+/// Delete log files (sorted newest first) that fall outside the retention limits.
+/// Returns the deleted file names and bytes freed.
+fn apply_retention(files: Vec<LogFile>, max_files: Option<usize>, max_age_days: Option<u64>) -> (Vec<String>, u64) {
+    let cutoff = max_age_days.map(|days| chrono::Utc::now() - chrono::Duration::days(days.min(36_500) as i64));
+    let active = files.iter().position(|f| f.name.ends_with(".log"));
+
+    let mut kept = 0;
+    let mut deleted = Vec::new();
+    let mut bytes_freed = 0;
+    for (i, file) in files.into_iter().enumerate() {
+        let too_many = max_files.is_some_and(|max| kept >= max);
+        let too_old = match (cutoff, file.modified.as_deref()) {
+            (Some(cutoff), Some(modified)) => chrono::DateTime::parse_from_rfc3339(modified)
+                .map(|m| m < cutoff)
+                .unwrap_or(false),
+            _ => false,
+        };
+
+        if Some(i) == active || !(too_many || too_old) {
+            kept += 1;
+            continue;
+        }
+
+        match fs::remove_file(&file.path) {
+            Ok(()) => {
+                bytes_freed += file.size;
+                deleted.push(file.name);
+            }
+            Err(e) => println!("[apply_log_retention] Failed to delete {}: {}", file.name, e),
+        }
+    }
+
+    (deleted, bytes_freed)
+}
+
+/// Compress old logs in a directory. Returns the new file names and bytes saved.
+fn compress_logs_in(logs_dir: &Path, older_than_days: u64) -> Result<(Vec<String>, u64), String> {
+    if !logs_dir.exists() {
+        return Ok((vec![], 0));
+    }
+
+    let entries = fs::read_dir(logs_dir).map_err(|e| format!("Failed to read logs directory: {}", e))?;
+    let mut logs: Vec<(PathBuf, std::time::SystemTime)> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().map(|e| e == "log").unwrap_or(false))
+        .filter_map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((path, modified))
+        })
+        .collect();
+
+    // The newest log is the one the server is writing to
+    logs.sort_by_key(|(_, modified)| std::cmp::Reverse(*modified));
+    if !logs.is_empty() {
+        logs.remove(0);
+    }
+
+    let cutoff = std::time::SystemTime::now()
+        .checked_sub(std::time::Duration::from_secs(older_than_days.saturating_mul(24 * 60 * 60)))
+        .unwrap_or(std::time::UNIX_EPOCH);
+
+    let mut compressed_files = Vec::new();
+    let mut bytes_saved = 0;
+    for (path, modified) in logs {
+        if modified > cutoff {
+            continue;
+        }
+        match gzip_file(&path, modified) {
+            Ok((gz_path, saved)) => {
+                compressed_files.push(
+                    gz_path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                );
+                bytes_saved += saved;
+            }
+            Err(e) => println!("[compress_old_logs] WARNING: Failed to compress {:?}: {}", path, e),
+        }
+    }
+
+    Ok((compressed_files, bytes_saved))
+}
+
+/// Replace a file with a gzipped copy next to it, keeping its modification time.
+/// Writes to a temporary file first so a failure never loses the original, and
+/// refuses to run if the `.gz` already exists rather than overwrite it.
+fn gzip_file(path: &Path, modified: std::time::SystemTime) -> std::io::Result<(PathBuf, u64)> {
+    let mut gz_name = path.as_os_str().to_owned();
+    gz_name.push(".gz");
+    let gz_path = PathBuf::from(gz_name);
+    if gz_path.exists() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            format!("{} already exists", gz_path.display()),
+        ));
+    }
+    let mut tmp_name = gz_path.as_os_str().to_owned();
+    tmp_name.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_name);
+
+    let original_size = fs::metadata(path)?.len();
+
+    let write = || -> std::io::Result<u64> {
+        let mut input = File::open(path)?;
+        let mut encoder = flate2::write::GzEncoder::new(File::create(&tmp_path)?, flate2::Compression::default());
+        std::io::copy(&mut input, &mut encoder)?;
+        let output = encoder.finish()?;
+        output.sync_all()?;
+        output.set_modified(modified)?;
+        Ok(output.metadata()?.len())
+    };
+
+    let compressed_size = match write() {
+        Ok(size) => size,
+        Err(e) => {
+            fs::remove_file(&tmp_path).ok();
+            return Err(e);
+        }
+    };
+
+    fs::rename(&tmp_path, &gz_path)?;
+    fs::remove_file(path)?;
+
+    Ok((gz_path, original_size.saturating_sub(compressed_size)))
+}
+
+/// Poll a log file for appended data and push complete lines into the buffer.
+/// A file that shrinks is treated as rotated and read again from the start.
+async fn follow_log(path: PathBuf, mut position: u64, buffer: Arc<Mutex<LogFollowBuffer>>) {
+    let mut partial: Vec<u8> = Vec::new();
+    let mut line_number = 0;
+
+    loop {
+        tokio::time::sleep(std::time::Duration::from_millis(LOG_FOLLOW_INTERVAL_MS)).await;
+
+        // The file can briefly disappear while being rotated
+        let file_size = match tokio::fs::metadata(&path).await {
+            Ok(m) => m.len(),
+            Err(_) => continue,
+        };
+
+        let rotated = file_size < position;
+        if rotated {
+            println!("[log_follow] {:?} shrank, reopening from the start", path);
+            position = 0;
+            partial.clear();
+        }
+
+        let mut chunk = Vec::new();
+        if file_size > position {
+            let read = async {
+                let mut file = tokio::fs::File::open(&path).await?;
+                file.seek(SeekFrom::Start(position)).await?;
+                file.take(file_size - position).read_to_end(&mut chunk).await
+            };
+            match read.await {
+                Ok(n) => position += n as u64,
+                Err(e) => {
+                    println!("[log_follow] Failed to read {:?}: {}", path, e);
+                    continue;
+                }
+            }
+        }
+
+        partial.extend_from_slice(&chunk);
+        let complete = match partial.iter().rposition(|&b| b == b'\n') {
+            Some(end) => partial.drain(..=end).collect::<Vec<u8>>(),
+            None => Vec::new(),
+        };
+
+        let mut buffer = buffer.lock().unwrap();
+        buffer.file_size = file_size;
+        buffer.rotated |= rotated;
+        for line in String::from_utf8_lossy(&complete).lines() {
+            line_number += 1;
+            buffer.lines.push_back(parse_log_line(line_number, line));
+            if buffer.lines.len() > LOG_FOLLOW_MAX_BUFFERED {
+                buffer.lines.pop_front();
+                buffer.dropped_lines += 1;
+            }
+        }
+    }
+}
+
+/// Lines of `reader` with invalid UTF-8 replaced by U+FFFD, so a line with odd bytes
+/// still shows up instead of ending the read like `BufRead::lines` does
+pub fn lossy_lines<R: BufRead>(mut reader: R) -> impl Iterator<Item = std::io::Result<String>> {
+    let mut buf = Vec::new();
+    std::iter::from_fn(move || {
+        buf.clear();
+        match reader.read_until(b'\n', &mut buf) {
+            Ok(0) => None,
+            Ok(_) => {
+                if buf.ends_with(b"\n") {
+                    buf.pop();
+                    if buf.ends_with(b"\r") {
+                        buf.pop();
+                    }
+                }
+                Some(Ok(String::from_utf8_lossy(&buf).into_owned()))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    })
+}
+
 /// Parse a log line and extract level and timestamp if possible
 fn parse_log_line(line_number: usize, content: &str) -> LogLine {
     let level = extract_log_level(content);
@@ -274,7 +711,7 @@ fn parse_log_line(line_number: usize, content: &str) -> LogLine {
 }
 
 /// Extract log level from line (INFO, WARN, ERROR, DEBUG)
-fn extract_log_level(line: &str) -> Option<String> {
+pub fn extract_log_level(line: &str) -> Option<String> {
     let line_upper = line.to_uppercase();
 
     if line_upper.contains("[ERROR]") || line_upper.contains(" ERROR ") || line_upper.contains("ERROR:") {
@@ -312,3 +749,198 @@ fn extract_timestamp(line: &str) -> Option<String> {
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::time::{Duration, SystemTime};
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    /// Empty scratch directory under the system temp dir
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("hypanel-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Write a file and backdate its modification time by `age`
+    fn write_aged(path: &Path, content: &[u8], age: Duration) {
+        let mut file = File::create(path).unwrap();
+        file.write_all(content).unwrap();
+        file.set_modified(SystemTime::now() - age).unwrap();
+    }
+
+    fn gunzip(path: &Path) -> Vec<u8> {
+        let mut content = Vec::new();
+        flate2::read::GzDecoder::new(File::open(path).unwrap())
+            .read_to_end(&mut content)
+            .unwrap();
+        content
+    }
+
+    #[test]
+    fn gzip_file_replaces_log_and_keeps_mtime() {
+        let dir = scratch_dir("gzip-file");
+        let log = dir.join("server.log");
+        let content = "[INFO] Server started\n".repeat(500);
+        write_aged(&log, content.as_bytes(), 3 * DAY);
+        let modified = fs::metadata(&log).unwrap().modified().unwrap();
+
+        let (gz_path, saved) = gzip_file(&log, modified).unwrap();
+
+        assert_eq!(gz_path, dir.join("server.log.gz"));
+        assert!(!log.exists());
+        assert!(!dir.join("server.log.gz.tmp").exists());
+        assert_eq!(gunzip(&gz_path), content.as_bytes());
+        assert_eq!(saved, content.len() as u64 - fs::metadata(&gz_path).unwrap().len());
+        assert_eq!(fs::metadata(&gz_path).unwrap().modified().unwrap(), modified);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn gzip_file_never_overwrites_existing_archive() {
+        let dir = scratch_dir("gzip-existing");
+        let log = dir.join("server.log");
+        let existing = dir.join("server.log.gz");
+        write_aged(&log, b"new", 3 * DAY);
+        fs::write(&existing, b"old archive").unwrap();
+
+        let err = gzip_file(&log, SystemTime::now()).unwrap_err();
+
+        assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read(&log).unwrap(), b"new");
+        assert_eq!(fs::read(&existing).unwrap(), b"old archive");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn compress_skips_newest_recent_and_non_log_files() {
+        let dir = scratch_dir("compress-select");
+        // The newest log counts as active even when it's past the cutoff
+        write_aged(&dir.join("active.log"), b"active", 8 * DAY);
+        write_aged(&dir.join("recent.log"), b"recent", 10 * DAY);
+        write_aged(&dir.join("old.log"), b"old", 20 * DAY);
+        write_aged(&dir.join("notes.txt"), b"notes", 30 * DAY);
+        write_aged(&dir.join("archived.log.gz"), b"archive", 30 * DAY);
+
+        let (compressed, _) = compress_logs_in(&dir, 15).unwrap();
+
+        assert_eq!(compressed, vec!["old.log.gz".to_string()]);
+        assert!(dir.join("active.log").exists());
+        assert!(dir.join("recent.log").exists());
+        assert!(dir.join("notes.txt").exists());
+        assert!(!dir.join("old.log").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn compress_leaves_log_when_archive_name_is_taken() {
+        let dir = scratch_dir("compress-taken");
+        write_aged(&dir.join("active.log"), b"active", Duration::ZERO);
+        write_aged(&dir.join("old.log"), b"old", 20 * DAY);
+        fs::write(dir.join("old.log.gz"), b"old archive").unwrap();
+
+        let (compressed, saved) = compress_logs_in(&dir, 7).unwrap();
+
+        assert!(compressed.is_empty());
+        assert_eq!(saved, 0);
+        assert_eq!(fs::read(dir.join("old.log")).unwrap(), b"old");
+        assert_eq!(fs::read(dir.join("old.log.gz")).unwrap(), b"old archive");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    /// Create 10-byte log files, listed newest first like list_log_files.
+    /// Each entry is (name, age in days); `None` leaves the modified time unknown.
+    fn log_files(dir: &Path, entries: &[(&str, Option<i64>)]) -> Vec<LogFile> {
+        entries
+            .iter()
+            .map(|(name, age_days)| {
+                let path = dir.join(name);
+                fs::write(&path, b"0123456789").unwrap();
+                LogFile {
+                    name: name.to_string(),
+                    path: path.to_string_lossy().to_string(),
+                    size: 10,
+                    modified: age_days.map(|days| (chrono::Utc::now() - chrono::Duration::days(days)).to_rfc3339()),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn retention_keeps_newest_max_files() {
+        let dir = scratch_dir("retention-count");
+        let files = log_files(
+            &dir,
+            &[("server.log", Some(0)), ("a.log.gz", Some(1)), ("b.log.gz", Some(2)), ("c.log.gz", Some(3))],
+        );
+
+        let (deleted, freed) = apply_retention(files, Some(2), None);
+
+        assert_eq!(deleted, vec!["b.log.gz".to_string(), "c.log.gz".to_string()]);
+        assert_eq!(freed, 20);
+        assert!(dir.join("server.log").exists());
+        assert!(dir.join("a.log.gz").exists());
+        assert!(!dir.join("b.log.gz").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn retention_deletes_files_older_than_max_age() {
+        let dir = scratch_dir("retention-age");
+        let files = log_files(
+            &dir,
+            &[("server.log", Some(0)), ("a.log.gz", Some(3)), ("b.log.gz", Some(6)), ("c.log", Some(10)), ("d.log", None)],
+        );
+
+        let (deleted, _) = apply_retention(files, None, Some(5));
+
+        // Files without a known modified time are never too old
+        assert_eq!(deleted, vec!["b.log.gz".to_string(), "c.log".to_string()]);
+        assert!(dir.join("a.log.gz").exists());
+        assert!(dir.join("d.log").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn retention_always_keeps_active_log() {
+        let dir = scratch_dir("retention-active");
+        // The newest .log is active even when an archive is newer and it's past every limit
+        let files = log_files(&dir, &[("a.log.gz", Some(1)), ("server.log", Some(20)), ("old.log", Some(30))]);
+
+        let (deleted, _) = apply_retention(files, Some(0), Some(1));
+
+        assert_eq!(deleted, vec!["a.log.gz".to_string(), "old.log".to_string()]);
+        assert!(dir.join("server.log").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn retention_without_limits_keeps_everything() {
+        let dir = scratch_dir("retention-none");
+        let files = log_files(&dir, &[("server.log", Some(0)), ("a.log.gz", Some(400))]);
+
+        assert_eq!(apply_retention(files, None, None), (vec![], 0));
+        assert!(dir.join("a.log.gz").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn retention_skips_files_it_cannot_delete() {
+        let dir = scratch_dir("retention-missing");
+        let files = log_files(&dir, &[("server.log", Some(0)), ("a.log.gz", Some(10))]);
+        fs::remove_file(&files[1].path).unwrap();
+
+        assert_eq!(apply_retention(files, None, Some(5)), (vec![], 0));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn compress_missing_directory_is_empty() {
+        let dir = std::env::temp_dir().join(format!("hypanel-no-logs-{}", std::process::id()));
+        assert_eq!(compress_logs_in(&dir, 7).unwrap(), (vec![], 0));
+    }
+}
@@ -1,9 +1,14 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use sysinfo::{Pid, System};
-use tauri::State;
+use std::time::{Duration, Instant, SystemTime};
+use sysinfo::{Pid, ProcessRefreshKind, System, UpdateKind};
+use tauri::{AppHandle, Emitter, Manager, State};
 
+use super::config::get_server_config;
 use super::server::ServerState;
+use crate::database::{self, DbPool};
 
 // ============================================================================
 // Types
@@ -13,10 +18,15 @@ use super::server::ServerState;
 pub struct ServerMetrics {
     pub instance_id: String,
     pub pid: Option<u32>,
-    pub cpu_usage: Option<f32>,
+    pub cpu_usage: Option<f32>,             // Raw sysinfo value, summed across cores (can exceed 100%)
+    pub cpu_usage_normalized: Option<f32>,  // cpu_usage divided by core count, 0-100% of the whole machine
     pub memory_mb: Option<f64>,
     pub memory_percent: Option<f32>,
+    pub disk_read_bytes_per_sec: Option<f64>,   // None until a second sample exists or if the OS doesn't report it
+    pub disk_write_bytes_per_sec: Option<f64>,
     pub uptime_seconds: Option<u64>,
+    pub tps: Option<f32>,
+    pub ready: bool,  // Server has logged that it finished booting
     pub status: String,
 }
 
@@ -29,20 +39,94 @@ pub struct SystemMetrics {
     pub cpu_usage: f32,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskUsage {
+    pub instance_path: String,
+    pub total_bytes: u64,
+    pub folders: HashMap<String, u64>,  // top-level entry name -> bytes
+    pub worlds_bytes: u64,
+    pub logs_bytes: u64,
+    pub mods_bytes: u64,
+    pub calculated_at: String,
+    pub cached: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JvmRecommendation {
+    pub jvm_args: String,
+    pub xms_mb: u64,
+    pub xmx_mb: u64,
+    pub total_memory_mb: u64,
+    pub reason: String,
+    pub error: Option<String>,  // Set when the minimum heap doesn't fit next to the OS
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JvmValidationResult {
+    pub valid: bool,  // false if there are hard errors
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    pub xms_mb: Option<u64>,
+    pub xmx_mb: Option<u64>,
+}
+
 // ============================================================================
 // Cached System State
 // ============================================================================
 
+/// How long a cached disk usage result stays valid even if mtimes are unchanged
+const DISK_USAGE_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Heap sizing inputs for JVM recommendations
+const MIN_HEAP_MB: u64 = 1024;
+const BASE_HEAP_MB: u64 = 2048;
+const HEAP_PER_PLAYER_MB: u64 = 64;
+const HEAP_PER_VIEW_RADIUS_MB: u64 = 32;
+const OS_HEADROOM_MB: u64 = 2048;
+const DEFAULT_MAX_PLAYERS: u64 = 100;
+const DEFAULT_VIEW_RADIUS: u64 = 32;
+
+/// Default interval for the metrics broadcast task
+const DEFAULT_BROADCAST_INTERVAL_MS: u64 = 2000;
+
+/// Lower bound to keep the broadcast from hammering sysinfo
+const MIN_BROADCAST_INTERVAL_MS: u64 = 250;
+
+/// Cached disk usage result, invalidated when directory mtimes change
+pub struct DiskUsageCacheEntry {
+    pub mtimes: Vec<Option<SystemTime>>,
+    pub computed: Instant,
+    pub usage: DiskUsage,
+}
+
+/// Cumulative disk I/O of a server process at a point in time, for computing rates
+pub struct DiskIoSample {
+    pub pid: u32,
+    pub taken: Instant,
+    pub total_read_bytes: u64,
+    pub total_written_bytes: u64,
+}
+
 /// Cached sysinfo::System instance to avoid expensive re-initialization
 pub struct MetricsState {
     pub system: System,
+    pub disk_usage_cache: HashMap<String, DiskUsageCacheEntry>,
+    pub disk_io_samples: HashMap<String, DiskIoSample>,  // instance_id -> last sample
+    pub broadcast_task: Option<tauri::async_runtime::JoinHandle<()>>,
+    pub exporter_task: Option<tauri::async_runtime::JoinHandle<()>>,
 }
 
 impl MetricsState {
     pub fn new() -> Self {
         let mut system = System::new_all();
         system.refresh_all();
-        Self { system }
+        Self {
+            system,
+            disk_usage_cache: HashMap::new(),
+            disk_io_samples: HashMap::new(),
+            broadcast_task: None,
+            exporter_task: None,
+        }
     }
 }
 
@@ -57,59 +141,30 @@ pub fn get_server_metrics(
     metrics_state: State<'_, Arc<Mutex<MetricsState>>>,
     instance_id: String,
 ) -> ServerMetrics {
-    let state_guard = server_state.lock().unwrap();
-
-    match state_guard.processes.get(&instance_id) {
-        Some(process_arc) => {
-            let process = process_arc.lock().unwrap();
-            let pid = process.child.id();
-
-            // Calculate uptime
-            let uptime_seconds = {
-                let now = chrono::Utc::now();
-                let started = process.started_at;
-                (now - started).num_seconds().max(0) as u64
-            };
+    let running = {
+        let state_guard = server_state.lock().unwrap();
+        running_process_info(&state_guard, &instance_id)
+    };
 
+    match running {
+        Some((pid, started_at, tps, ready)) => {
             // Get process metrics using cached sysinfo
             let mut metrics = metrics_state.lock().unwrap();
             metrics.system.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
-
-            let (cpu_usage, memory_mb, memory_percent) = if let Some(proc) = metrics.system.process(Pid::from_u32(pid)) {
-                let cpu = proc.cpu_usage();
-                let mem_bytes = proc.memory();
-                let mem_mb = mem_bytes as f64 / 1024.0 / 1024.0;
-
-                // Calculate memory percentage
-                let total_mem = metrics.system.total_memory();
-                let mem_pct = if total_mem > 0 {
-                    (mem_bytes as f32 / total_mem as f32) * 100.0
-                } else {
-                    0.0
-                };
-
-                (Some(cpu), Some(mem_mb), Some(mem_pct))
-            } else {
-                (None, None, None)
-            };
-
-            ServerMetrics {
-                instance_id,
-                pid: Some(pid),
-                cpu_usage,
-                memory_mb,
-                memory_percent,
-                uptime_seconds: Some(uptime_seconds),
-                status: "running".to_string(),
-            }
+            process_metrics(&mut metrics, &instance_id, pid, started_at, tps, ready)
         }
         None => ServerMetrics {
             instance_id,
             pid: None,
             cpu_usage: None,
+            cpu_usage_normalized: None,
             memory_mb: None,
             memory_percent: None,
+            disk_read_bytes_per_sec: None,
+            disk_write_bytes_per_sec: None,
             uptime_seconds: None,
+            tps: None,
+            ready: false,
             status: "stopped".to_string(),
         },
     }
@@ -121,53 +176,79 @@ pub fn get_all_server_metrics(
     server_state: State<'_, Arc<Mutex<ServerState>>>,
     metrics_state: State<'_, Arc<Mutex<MetricsState>>>,
 ) -> Vec<ServerMetrics> {
-    let state_guard = server_state.lock().unwrap();
+    collect_all_server_metrics(&server_state, &metrics_state)
+}
 
-    if state_guard.processes.is_empty() {
-        return vec![];
+/// Start broadcasting `all-server-metrics` events at a fixed interval.
+/// If `interval_ms` is provided it is persisted as the new default.
+#[tauri::command]
+pub async fn start_metrics_broadcast(
+    app: AppHandle,
+    metrics_state: State<'_, Arc<Mutex<MetricsState>>>,
+    interval_ms: Option<u64>,
+) -> Result<u64, String> {
+    let pool = app.try_state::<DbPool>().map(|p| p.inner().clone());
+
+    let interval_ms = match interval_ms {
+        Some(ms) => {
+            if let Some(ref pool) = pool {
+                if let Err(e) = database::set_setting(pool, "metrics_broadcast_interval_ms", &ms.to_string()).await {
+                    println!("[metrics] WARNING: Failed to save broadcast interval: {}", e);
+                }
+            }
+            ms
+        }
+        None => match pool {
+            Some(ref pool) => database::get_setting(pool, "metrics_broadcast_interval_ms")
+                .await
+                .ok()
+                .flatten()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(DEFAULT_BROADCAST_INTERVAL_MS),
+            None => DEFAULT_BROADCAST_INTERVAL_MS,
+        },
     }
+    .max(MIN_BROADCAST_INTERVAL_MS);
 
-    let mut metrics = metrics_state.lock().unwrap();
-    metrics.system.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+    let server_state = app.state::<Arc<Mutex<ServerState>>>().inner().clone();
+    let metrics_arc = metrics_state.inner().clone();
+    let app_handle = app.clone();
 
-    state_guard
-        .processes
-        .iter()
-        .map(|(id, process_arc)| {
-            let process = process_arc.lock().unwrap();
-            let pid = process.child.id();
-
-            let uptime_seconds = {
-                let now = chrono::Utc::now();
-                (now - process.started_at).num_seconds().max(0) as u64
-            };
-
-            let (cpu_usage, memory_mb, memory_percent) = if let Some(proc) = metrics.system.process(Pid::from_u32(pid)) {
-                let cpu = proc.cpu_usage();
-                let mem_bytes = proc.memory();
-                let mem_mb = mem_bytes as f64 / 1024.0 / 1024.0;
-                let total_mem = metrics.system.total_memory();
-                let mem_pct = if total_mem > 0 {
-                    (mem_bytes as f32 / total_mem as f32) * 100.0
-                } else {
-                    0.0
-                };
-                (Some(cpu), Some(mem_mb), Some(mem_pct))
-            } else {
-                (None, None, None)
-            };
-
-            ServerMetrics {
-                instance_id: id.clone(),
-                pid: Some(pid),
-                cpu_usage,
-                memory_mb,
-                memory_percent,
-                uptime_seconds: Some(uptime_seconds),
-                status: "running".to_string(),
+    let task = tauri::async_runtime::spawn(async move {
+        println!("[metrics] Broadcasting metrics every {}ms", interval_ms);
+        loop {
+            // The sysinfo refresh is synchronous, so keep it off the async workers
+            let (server_state, metrics_arc) = (server_state.clone(), metrics_arc.clone());
+            let collected =
+                tokio::task::spawn_blocking(move || collect_all_server_metrics(&server_state, &metrics_arc)).await;
+            if let Ok(all_metrics) = collected {
+                let _ = app_handle.emit("all-server-metrics", &all_metrics);
             }
-        })
-        .collect()
+            tokio::time::sleep(Duration::from_millis(interval_ms)).await;
+        }
+    });
+
+    // Replace any previous broadcast task
+    let mut metrics = metrics_state.lock().unwrap();
+    if let Some(previous) = metrics.broadcast_task.replace(task) {
+        previous.abort();
+    }
+
+    Ok(interval_ms)
+}
+
+/// Stop the periodic metrics broadcast
+#[tauri::command]
+pub fn stop_metrics_broadcast(metrics_state: State<'_, Arc<Mutex<MetricsState>>>) -> bool {
+    let mut metrics = metrics_state.lock().unwrap();
+    match metrics.broadcast_task.take() {
+        Some(task) => {
+            task.abort();
+            println!("[metrics] Broadcast stopped");
+            true
+        }
+        None => false,
+    }
 }
 
 /// Get system-wide metrics
@@ -175,6 +256,318 @@ pub fn get_all_server_metrics(
 pub fn get_system_metrics(
     metrics_state: State<'_, Arc<Mutex<MetricsState>>>,
 ) -> SystemMetrics {
+    collect_system_metrics(&metrics_state)
+}
+
+/// Get disk usage for an instance directory, broken down by top-level folder
+#[tauri::command]
+pub async fn get_instance_disk_usage(
+    metrics_state: State<'_, Arc<Mutex<MetricsState>>>,
+    instance_path: String,
+) -> Result<DiskUsage, String> {
+    let root = Path::new(&instance_path);
+    if !root.exists() {
+        return Err(format!("Instance directory not found: {}", instance_path));
+    }
+
+    // Serve from cache if nothing changed at the watched directories
+    let mtimes = disk_usage_mtimes(root);
+    {
+        let metrics = metrics_state.lock().unwrap();
+        if let Some(entry) = metrics.disk_usage_cache.get(&instance_path) {
+            if entry.mtimes == mtimes && entry.computed.elapsed() < DISK_USAGE_CACHE_TTL {
+                return Ok(DiskUsage {
+                    cached: true,
+                    ..entry.usage.clone()
+                });
+            }
+        }
+    }
+
+    let path_clone = instance_path.clone();
+    let usage = tokio::task::spawn_blocking(move || calculate_disk_usage(&path_clone))
+        .await
+        .map_err(|e| format!("Failed to calculate disk usage: {}", e))?;
+
+    {
+        let mut metrics = metrics_state.lock().unwrap();
+        metrics.disk_usage_cache.insert(
+            instance_path,
+            DiskUsageCacheEntry {
+                mtimes,
+                computed: Instant::now(),
+                usage: usage.clone(),
+            },
+        );
+    }
+
+    Ok(usage)
+}
+
+/// Recommend JVM heap and GC flags based on system RAM and the server config
+#[tauri::command]
+pub fn recommend_jvm_args(
+    metrics_state: State<'_, Arc<Mutex<MetricsState>>>,
+    instance_path: String,
+) -> JvmRecommendation {
+    let total_memory_mb = {
+        let mut metrics = metrics_state.lock().unwrap();
+        metrics.system.refresh_memory();
+        metrics.system.total_memory() / 1024 / 1024
+    };
+
+    // Estimate what the server wants from player count and view distance
+    let (max_players, view_radius) = get_server_config(instance_path)
+        .config
+        .map(|c| (c.max_players.max(0) as u64, c.max_view_radius.max(0) as u64))
+        .unwrap_or((DEFAULT_MAX_PLAYERS, DEFAULT_VIEW_RADIUS));
+    let wanted_mb = BASE_HEAP_MB + max_players * HEAP_PER_PLAYER_MB + view_radius * HEAP_PER_VIEW_RADIUS_MB;
+
+    // Never take more than half the RAM, and always leave headroom for the OS
+    let cap_mb = (total_memory_mb / 2).min(total_memory_mb.saturating_sub(OS_HEADROOM_MB));
+    if cap_mb < MIN_HEAP_MB {
+        return JvmRecommendation {
+            jvm_args: String::new(),
+            xms_mb: 0,
+            xmx_mb: 0,
+            total_memory_mb,
+            reason: format!(
+                "{} MB system RAM leaves less than the {} MB minimum heap after {} MB for the OS",
+                total_memory_mb, MIN_HEAP_MB, OS_HEADROOM_MB
+            ),
+            error: Some("Not enough RAM to run a server".to_string()),
+        };
+    }
+
+    let xmx_mb = round_down_to_512(wanted_mb.min(cap_mb)).max(MIN_HEAP_MB);
+    let xms_mb = round_down_to_512(xmx_mb / 2).max(MIN_HEAP_MB).min(xmx_mb);
+
+    let reason = if cap_mb < wanted_mb {
+        format!(
+            "Limited to {} MB to leave room for the OS; the server config suggests about {} MB",
+            xmx_mb, wanted_mb
+        )
+    } else {
+        format!(
+            "Enough for {} players at view radius {} ({} MB system RAM)",
+            max_players, view_radius, total_memory_mb
+        )
+    };
+
+    JvmRecommendation {
+        jvm_args: format!(
+            "-Xms{} -Xmx{} -XX:+UseG1GC -XX:MaxGCPauseMillis=200",
+            format_heap_size(xms_mb),
+            format_heap_size(xmx_mb)
+        ),
+        xms_mb,
+        xmx_mb,
+        total_memory_mb,
+        reason,
+        error: None,
+    }
+}
+
+/// Validate JVM arguments before launch (heap sizes, malformed flags)
+#[tauri::command]
+pub fn validate_jvm_args(
+    metrics_state: State<'_, Arc<Mutex<MetricsState>>>,
+    args: String,
+    instance_path: String,
+) -> JvmValidationResult {
+    let (total_mb, available_mb) = memory_snapshot_mb(&metrics_state);
+    let mut result = check_jvm_args(&args, total_mb, available_mb);
+
+    // start_server adds the AOT cache flag itself when the cache exists
+    let aot_cache = Path::new(&instance_path).join("Server").join("HytaleServer.aot");
+    if aot_cache.exists() && args.contains("-XX:AOTCache") {
+        result
+            .warnings
+            .push("-XX:AOTCache is added automatically for this instance; remove it to avoid duplicates".to_string());
+    }
+
+    result
+}
+
+/// Get (total, available) system memory in MB
+pub fn memory_snapshot_mb(metrics_state: &Mutex<MetricsState>) -> (u64, u64) {
+    let mut metrics = metrics_state.lock().unwrap();
+    metrics.system.refresh_memory();
+    (
+        metrics.system.total_memory() / 1024 / 1024,
+        metrics.system.available_memory() / 1024 / 1024,
+    )
+}
+
+/// PIDs of running processes whose command line runs `jar` (e.g. a server started
+/// outside the app, or left running by a previous session). Relative jar arguments
+/// are resolved against the process's working directory.
+pub fn find_processes_running_jar(metrics_state: &Mutex<MetricsState>, jar: &Path) -> Vec<u32> {
+    let Ok(jar) = jar.canonicalize() else {
+        return vec![];
+    };
+
+    let mut metrics = metrics_state.lock().unwrap();
+    metrics.system.refresh_processes_specifics(
+        sysinfo::ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::new()
+            .with_cmd(UpdateKind::OnlyIfNotSet)
+            .with_cwd(UpdateKind::OnlyIfNotSet),
+    );
+
+    metrics
+        .system
+        .processes()
+        .iter()
+        .filter(|(_, process)| {
+            process.cmd().iter().any(|arg| {
+                let arg = Path::new(arg);
+                if arg.file_name() != jar.file_name() {
+                    return false;
+                }
+                let full = match process.cwd() {
+                    _ if arg.is_absolute() => arg.to_path_buf(),
+                    Some(cwd) => cwd.join(arg),
+                    None => return false,
+                };
+                full.canonicalize().map(|full| full == jar).unwrap_or(false)
+            })
+        })
+        .map(|(pid, _)| pid.as_u32())
+        .collect()
+}
+
+/// Check whether a process is still running
+pub fn is_process_alive(metrics_state: &Mutex<MetricsState>, pid: u32) -> bool {
+    let pid = Pid::from_u32(pid);
+    let mut metrics = metrics_state.lock().unwrap();
+    metrics.system.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[pid]), true);
+    metrics.system.process(pid).is_some()
+}
+
+/// Check JVM arguments against the given memory limits
+pub fn check_jvm_args(args: &str, total_mb: u64, available_mb: u64) -> JvmValidationResult {
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    let mut xms_mb = None;
+    let mut xmx_mb = None;
+
+    for arg in args.split_whitespace() {
+        if !arg.starts_with('-') {
+            warnings.push(format!("'{}' doesn't look like a JVM flag", arg));
+            continue;
+        }
+
+        for (prefix, slot) in [("-Xms", &mut xms_mb), ("-Xmx", &mut xmx_mb)] {
+            if let Some(size) = arg.strip_prefix(prefix) {
+                match parse_heap_size_mb(size) {
+                    Ok(mb) => {
+                        if slot.is_some() {
+                            warnings.push(format!("{} is set more than once; the last value wins", prefix));
+                        }
+                        *slot = Some(mb);
+                    }
+                    Err(e) => errors.push(format!("{}: {}", arg, e)),
+                }
+            }
+        }
+
+        if arg == "-XX:" || arg == "-XX:+" || arg == "-XX:-" || (arg.starts_with("-XX:") && arg.ends_with('=')) {
+            errors.push(format!("'{}' is an incomplete -XX option", arg));
+        }
+    }
+
+    if let (Some(xms), Some(xmx)) = (xms_mb, xmx_mb) {
+        if xms > xmx {
+            errors.push(format!("-Xms ({} MB) is larger than -Xmx ({} MB)", xms, xmx));
+        }
+    }
+
+    if let Some(xmx) = xmx_mb {
+        if total_mb > 0 && xmx > total_mb {
+            errors.push(format!("-Xmx ({} MB) exceeds physical memory ({} MB)", xmx, total_mb));
+        } else if available_mb > 0 && xmx > available_mb {
+            warnings.push(format!(
+                "-Xmx ({} MB) is more than currently available memory ({} MB)",
+                xmx, available_mb
+            ));
+        }
+    }
+
+    JvmValidationResult {
+        valid: errors.is_empty(),
+        errors,
+        warnings,
+        xms_mb,
+        xmx_mb,
+    }
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Max heap (-Xmx) in MB set by JVM arguments, if any (the last valid value wins)
+pub fn max_heap_mb(args: &str) -> Option<u64> {
+    args.split_whitespace()
+        .filter_map(|arg| arg.strip_prefix("-Xmx"))
+        .filter_map(|size| parse_heap_size_mb(size).ok())
+        .last()
+}
+
+/// Parse a JVM heap size like "8G", "512m", "1048576k" or a bare byte count into MB.
+/// A bare number under 1 MB is almost always a forgotten unit ("-Xmx8"), so it's an error.
+fn parse_heap_size_mb(size: &str) -> Result<u64, String> {
+    if size.is_empty() {
+        return Err("missing size".to_string());
+    }
+
+    let unit = size.chars().last().unwrap_or_default();
+    let (number, multiplier): (&str, u64) = match unit.to_ascii_lowercase() {
+        'k' => (&size[..size.len() - 1], 1024),
+        'm' => (&size[..size.len() - 1], 1024 * 1024),
+        'g' => (&size[..size.len() - 1], 1024 * 1024 * 1024),
+        't' => (&size[..size.len() - 1], 1024 * 1024 * 1024 * 1024),
+        c if c.is_ascii_digit() => (size, 1),
+        c => return Err(format!("unknown unit '{}'", c)),
+    };
+
+    let value = number
+        .parse::<u64>()
+        .map_err(|_| format!("invalid number '{}'", number))?;
+
+    let mb = value
+        .checked_mul(multiplier)
+        .ok_or_else(|| "size is too large".to_string())?
+        / (1024 * 1024);
+    if mb == 0 {
+        return Err(if multiplier == 1 {
+            "missing unit (use K, M or G, e.g. 8G)".to_string()
+        } else {
+            "heap size is too small".to_string()
+        });
+    }
+
+    Ok(mb)
+}
+
+/// Round a size in MB down to the nearest 512 MB
+fn round_down_to_512(mb: u64) -> u64 {
+    mb / 512 * 512
+}
+
+/// Format a heap size in MB as a JVM size string ("8G" or "1536M")
+fn format_heap_size(mb: u64) -> String {
+    if mb.is_multiple_of(1024) {
+        format!("{}G", mb / 1024)
+    } else {
+        format!("{}M", mb)
+    }
+}
+
+/// Sample system-wide memory and CPU usage
+pub fn collect_system_metrics(metrics_state: &Mutex<MetricsState>) -> SystemMetrics {
     let mut metrics = metrics_state.lock().unwrap();
 
     // Only refresh what we need - much faster than refresh_all()
@@ -194,3 +587,303 @@ pub fn get_system_metrics(
         cpu_usage,
     }
 }
+
+/// Collect metrics for all running servers, refreshing sysinfo once
+pub fn collect_all_server_metrics(
+    server_state: &Mutex<ServerState>,
+    metrics_state: &Mutex<MetricsState>,
+) -> Vec<ServerMetrics> {
+    let running: Vec<(String, RunningProcessInfo)> = {
+        let state_guard = server_state.lock().unwrap();
+        state_guard
+            .processes
+            .keys()
+            .chain(state_guard.adopted.keys())
+            .filter_map(|id| running_process_info(&state_guard, id).map(|info| (id.clone(), info)))
+            .collect()
+    };
+
+    if running.is_empty() {
+        return vec![];
+    }
+
+    let mut metrics = metrics_state.lock().unwrap();
+    metrics.system.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+
+    running
+        .into_iter()
+        .map(|(id, (pid, started_at, tps, ready))| process_metrics(&mut metrics, &id, pid, started_at, tps, ready))
+        .collect()
+}
+
+/// PID, start time, last TPS and readiness of a running server
+type RunningProcessInfo = (u32, chrono::DateTime<chrono::Utc>, Option<f32>, bool);
+
+/// Look up a running server, including one adopted from a previous app session
+/// (which has no output to read TPS or readiness from, so it counts as ready)
+fn running_process_info(state: &ServerState, instance_id: &str) -> Option<RunningProcessInfo> {
+    match state.processes.get(instance_id) {
+        Some(process_arc) => {
+            let process = process_arc.lock().unwrap();
+            Some((process.child.id(), process.started_at, process.tps, process.ready))
+        }
+        None => state
+            .adopted
+            .get(instance_id)
+            .map(|adopted| (adopted.pid, adopted.started_at, None, true)),
+    }
+}
+
+/// Build metrics for a server process from already refreshed sysinfo data
+fn process_metrics(
+    metrics: &mut MetricsState,
+    instance_id: &str,
+    pid: u32,
+    started_at: chrono::DateTime<chrono::Utc>,
+    tps: Option<f32>,
+    ready: bool,
+) -> ServerMetrics {
+    let uptime_seconds = (chrono::Utc::now() - started_at).num_seconds().max(0) as u64;
+
+    let (cpu_usage, memory_mb, memory_percent) = if let Some(proc) = metrics.system.process(Pid::from_u32(pid)) {
+        let cpu = proc.cpu_usage();
+        let mem_bytes = proc.memory();
+        let mem_mb = mem_bytes as f64 / 1024.0 / 1024.0;
+
+        // Calculate memory percentage
+        let total_mem = metrics.system.total_memory();
+        let mem_pct = if total_mem > 0 {
+            (mem_bytes as f32 / total_mem as f32) * 100.0
+        } else {
+            0.0
+        };
+
+        (Some(cpu), Some(mem_mb), Some(mem_pct))
+    } else {
+        (None, None, None)
+    };
+
+    let (disk_read_bytes_per_sec, disk_write_bytes_per_sec) = sample_disk_io(metrics, instance_id, pid);
+
+    ServerMetrics {
+        instance_id: instance_id.to_string(),
+        pid: Some(pid),
+        cpu_usage,
+        cpu_usage_normalized: cpu_usage.map(|cpu| normalize_cpu_usage(cpu, metrics.system.cpus().len())),
+        memory_mb,
+        memory_percent,
+        disk_read_bytes_per_sec,
+        disk_write_bytes_per_sec,
+        uptime_seconds: Some(uptime_seconds),
+        tps,
+        ready,
+        status: "running".to_string(),
+    }
+}
+
+/// Disk read/write rates of a server process since the previous sample for the instance.
+/// Returns None for both on the first sample, after a restart, or if the process is gone.
+fn sample_disk_io(metrics: &mut MetricsState, instance_id: &str, pid: u32) -> (Option<f64>, Option<f64>) {
+    let usage = match metrics.system.process(Pid::from_u32(pid)) {
+        Some(proc) => proc.disk_usage(),
+        None => {
+            metrics.disk_io_samples.remove(instance_id);
+            return (None, None);
+        }
+    };
+
+    let sample = DiskIoSample {
+        pid,
+        taken: Instant::now(),
+        total_read_bytes: usage.total_read_bytes,
+        total_written_bytes: usage.total_written_bytes,
+    };
+
+    let rates = match metrics.disk_io_samples.get(instance_id) {
+        Some(previous) if previous.pid == pid => {
+            let elapsed = sample.taken.duration_since(previous.taken).as_secs_f64();
+            if elapsed > 0.0 {
+                let rate = |now: u64, before: u64| now.saturating_sub(before) as f64 / elapsed;
+                (
+                    Some(rate(sample.total_read_bytes, previous.total_read_bytes)),
+                    Some(rate(sample.total_written_bytes, previous.total_written_bytes)),
+                )
+            } else {
+                (None, None)
+            }
+        }
+        _ => (None, None),
+    };
+
+    metrics.disk_io_samples.insert(instance_id.to_string(), sample);
+    rates
+}
+
+/// Scale a per-core CPU percentage (100% = one full core) to a share of the whole machine
+fn normalize_cpu_usage(cpu_usage: f32, cpu_count: usize) -> f32 {
+    (cpu_usage / cpu_count.max(1) as f32).clamp(0.0, 100.0)
+}
+
+/// Directories whose mtimes are used to invalidate the disk usage cache
+fn disk_usage_mtimes(root: &Path) -> Vec<Option<SystemTime>> {
+    let server = root.join("Server");
+    [
+        root.to_path_buf(),
+        server.clone(),
+        server.join("universe").join("worlds"),
+        server.join("logs"),
+        server.join("mods"),
+    ]
+    .iter()
+    .map(|p| std::fs::metadata(p).and_then(|m| m.modified()).ok())
+    .collect()
+}
+
+/// Walk an instance directory once, summing sizes per top-level entry and
+/// picking up the worlds/logs/mods totals on the way
+fn calculate_disk_usage(instance_path: &str) -> DiskUsage {
+    let root = Path::new(instance_path);
+    let server = root.join("Server");
+    let mut folders = HashMap::new();
+    let mut total_bytes = 0;
+
+    // (directory, size) for each sub-folder reported separately
+    let mut tracked = [
+        (server.join("universe").join("worlds"), 0),
+        (server.join("logs"), 0),
+        (server.join("mods"), 0),
+    ];
+
+    if let Ok(entries) = std::fs::read_dir(root) {
+        for entry in entries.flatten() {
+            let size = dir_size(&entry.path(), &mut tracked);
+            total_bytes += size;
+            folders.insert(entry.file_name().to_string_lossy().to_string(), size);
+        }
+    }
+
+    let [(_, worlds_bytes), (_, logs_bytes), (_, mods_bytes)] = tracked;
+
+    DiskUsage {
+        instance_path: instance_path.to_string(),
+        total_bytes,
+        folders,
+        worlds_bytes,
+        logs_bytes,
+        mods_bytes,
+        calculated_at: chrono::Utc::now().to_rfc3339(),
+        cached: false,
+    }
+}
+
+/// Recursively sum file sizes (symlinks are not followed).
+/// Also records the size of any directory in `tracked` when the walk reaches it.
+fn dir_size(path: &Path, tracked: &mut [(PathBuf, u64)]) -> u64 {
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(m) => m,
+        Err(_) => return 0,
+    };
+
+    if metadata.is_file() {
+        return metadata.len();
+    }
+
+    if !metadata.is_dir() {
+        return 0;
+    }
+
+    let size = std::fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|entry| dir_size(&entry.path(), tracked)).sum())
+        .unwrap_or(0);
+
+    if let Some((_, tracked_size)) = tracked.iter_mut().find(|(dir, _)| dir == path) {
+        *tracked_size = size;
+    }
+    size
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heap_sizes_parse_with_units() {
+        assert_eq!(parse_heap_size_mb("1048576k"), Ok(1024));
+        assert_eq!(parse_heap_size_mb("512m"), Ok(512));
+        assert_eq!(parse_heap_size_mb("512M"), Ok(512));
+        assert_eq!(parse_heap_size_mb("8G"), Ok(8192));
+        assert_eq!(parse_heap_size_mb("8g"), Ok(8192));
+        assert_eq!(parse_heap_size_mb("1t"), Ok(1024 * 1024));
+        // Rounds down to whole MB
+        assert_eq!(parse_heap_size_mb("1536k"), Ok(1));
+    }
+
+    #[test]
+    fn bare_heap_sizes_are_bytes() {
+        assert_eq!(parse_heap_size_mb("1073741824"), Ok(1024));
+        assert_eq!(parse_heap_size_mb("1048576"), Ok(1));
+        // Under 1 MB means the unit was forgotten
+        assert!(parse_heap_size_mb("8").unwrap_err().contains("missing unit"));
+        assert!(parse_heap_size_mb("1048575").unwrap_err().contains("missing unit"));
+    }
+
+    #[test]
+    fn invalid_heap_sizes_are_rejected() {
+        assert_eq!(parse_heap_size_mb(""), Err("missing size".to_string()));
+        assert_eq!(parse_heap_size_mb("8x"), Err("unknown unit 'x'".to_string()));
+        assert_eq!(parse_heap_size_mb("G"), Err("invalid number ''".to_string()));
+        assert_eq!(parse_heap_size_mb("-8G"), Err("invalid number '-8'".to_string()));
+        assert_eq!(parse_heap_size_mb("1.5G"), Err("invalid number '1.5'".to_string()));
+        assert_eq!(parse_heap_size_mb("100k"), Err("heap size is too small".to_string()));
+        assert_eq!(parse_heap_size_mb("99999999999t"), Err("size is too large".to_string()));
+        assert!(parse_heap_size_mb("8é").is_err());
+    }
+
+    #[test]
+    fn jvm_args_report_heap_sizes() {
+        let result = check_jvm_args("-Xms2G -Xmx8G -XX:+UseG1GC", 32_768, 16_384);
+        assert!(result.valid);
+        assert!(result.errors.is_empty());
+        assert!(result.warnings.is_empty());
+        assert_eq!(result.xms_mb, Some(2048));
+        assert_eq!(result.xmx_mb, Some(8192));
+    }
+
+    #[test]
+    fn jvm_args_flag_malformed_values() {
+        let result = check_jvm_args("-Xmx8 -XX:MaxGCPauseMillis= server", 32_768, 16_384);
+        assert!(!result.valid);
+        assert_eq!(result.errors.len(), 2);
+        assert!(result.errors[0].starts_with("-Xmx8:"));
+        assert!(result.errors[1].contains("incomplete -XX option"));
+        assert_eq!(result.warnings, vec!["'server' doesn't look like a JVM flag".to_string()]);
+        assert_eq!(result.xmx_mb, None);
+    }
+
+    #[test]
+    fn jvm_args_check_heap_against_memory() {
+        let too_big = check_jvm_args("-Xmx64G", 32_768, 16_384);
+        assert!(!too_big.valid);
+        assert!(too_big.errors[0].contains("exceeds physical memory"));
+
+        let tight = check_jvm_args("-Xmx20G", 32_768, 16_384);
+        assert!(tight.valid);
+        assert!(tight.warnings[0].contains("more than currently available"));
+
+        let inverted = check_jvm_args("-Xms8G -Xmx4G", 32_768, 16_384);
+        assert!(!inverted.valid);
+        assert!(inverted.errors[0].contains("larger than -Xmx"));
+
+        // Unknown memory limits skip the comparison
+        assert!(check_jvm_args("-Xmx64G", 0, 0).valid);
+    }
+
+    #[test]
+    fn jvm_args_warn_on_repeated_heap_flag() {
+        let result = check_jvm_args("-Xmx4G -Xmx8G", 32_768, 16_384);
+        assert!(result.valid);
+        assert_eq!(result.xmx_mb, Some(8192));
+        assert_eq!(result.warnings, vec!["-Xmx is set more than once; the last value wins".to_string()]);
+        assert_eq!(max_heap_mb("-Xmx4G -Xmx8G -Xmxoops"), Some(8192));
+    }
+}
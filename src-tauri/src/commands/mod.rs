@@ -1,22 +1,32 @@
+pub mod backup;
 pub mod config;
+pub mod diagnostics;
 pub mod downloader;
+pub mod errors;
 pub mod files;
 pub mod instances;
 pub mod logs;
 pub mod metrics;
 pub mod network;
+pub mod notifier;
+pub mod prometheus;
 pub mod server;
 pub mod system;
 pub mod version;
 pub mod worlds;
 
+pub use backup::*;
 pub use config::*;
+pub use diagnostics::*;
 pub use downloader::*;
+pub use errors::*;
 pub use files::*;
 pub use instances::*;
 pub use logs::*;
 pub use metrics::*;
 pub use network::*;
+pub use notifier::*;
+pub use prometheus::*;
 pub use server::*;
 pub use system::*;
 pub use version::*;
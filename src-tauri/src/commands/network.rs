@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
-#[cfg(any(target_os = "windows", target_os = "linux"))]
+use std::collections::HashMap;
+
+use super::config::{read_config_port, DEFAULT_SERVER_PORT};
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
 use std::process::Command;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -7,9 +10,12 @@ pub struct FirewallInfo {
     pub os: String,
     pub firewall_type: Option<String>,
     pub firewall_enabled: bool,
+    /// Enabled state per firewall profile (Domain/Private/Public); Windows only
+    pub profiles: Option<HashMap<String, bool>>,
     pub rule_exists: bool,
     pub rule_name: String,
     pub port: u16,
+    pub protocol: String,  // "udp", "tcp" or "both"
     pub command_to_add: String,
     pub command_to_remove: String,
     pub error: Option<String>,
@@ -20,6 +26,86 @@ pub struct FirewallResult {
     pub success: bool,
     pub message: String,
     pub error: Option<String>,
+    pub action: Option<String>,  // add_firewall_rule: "created", "updated" or "unchanged"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublicIpResult {
+    pub success: bool,
+    pub ipv4: Option<String>,
+    pub ipv6: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReachabilityResult {
+    pub public_ipv4: Option<String>,
+    pub public_ipv6: Option<String>,
+    pub port: u16,
+    pub address: Option<String>,  // e.g. "203.0.113.4:5520", ready to share
+    pub local_bind_ok: bool,      // true if the UDP port is free (server not running)
+    pub error: Option<String>,
+}
+
+/// Firewall info for an instance, with the port derived from its config.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceFirewallInfo {
+    #[serde(flatten)]
+    pub info: FirewallInfo,
+    pub port_source: String,  // "config" or "default"
+    pub note: Option<String>,
+}
+
+const PUBLIC_IPV4_URL: &str = "https://api.ipify.org";
+const PUBLIC_IPV6_URL: &str = "https://api6.ipify.org";
+
+/// Transport protocol(s) a firewall rule opens
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FirewallProtocol {
+    Udp,
+    Tcp,
+    Both,
+}
+
+impl FirewallProtocol {
+    /// Parse "udp", "tcp" or "both" (case-insensitive); defaults to UDP, which Hytale uses
+    fn parse(value: Option<&str>) -> Result<Self, String> {
+        match value.map(|v| v.trim().to_ascii_lowercase()).as_deref() {
+            None | Some("") | Some("udp") => Ok(Self::Udp),
+            Some("tcp") => Ok(Self::Tcp),
+            Some("both") => Ok(Self::Both),
+            Some(other) => Err(format!(
+                "Unknown protocol '{}', expected \"udp\", \"tcp\" or \"both\"",
+                other
+            )),
+        }
+    }
+
+    /// The individual protocols a rule needs, in lowercase
+    fn protocols(self) -> &'static [&'static str] {
+        match self {
+            Self::Udp => &["udp"],
+            Self::Tcp => &["tcp"],
+            Self::Both => &["udp", "tcp"],
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Udp => "udp",
+            Self::Tcp => "tcp",
+            Self::Both => "both",
+        }
+    }
+
+    /// Name for messages, e.g. "UDP" or "UDP/TCP"
+    fn label(self) -> &'static str {
+        match self {
+            Self::Udp => "UDP",
+            Self::Tcp => "TCP",
+            Self::Both => "UDP/TCP",
+        }
+    }
 }
 
 /// Get current OS
@@ -34,24 +120,36 @@ fn get_os() -> &'static str {
     return "unknown";
 }
 
-/// Check if Windows firewall is enabled (any profile)
+/// Enabled state of each Windows firewall profile (Domain, Private, Public).
+/// None if the profiles couldn't be read.
 #[cfg(target_os = "windows")]
-fn check_windows_firewall_enabled() -> bool {
+fn check_windows_firewall_profiles() -> Option<HashMap<String, bool>> {
     let output = Command::new("powershell")
         .args([
             "-NoProfile",
             "-Command",
-            "Get-NetFirewallProfile | Select-Object -ExpandProperty Enabled",
+            "Get-NetFirewallProfile | ForEach-Object { \"$($_.Name)=$($_.Enabled)\" }",
         ])
-        .output();
+        .output()
+        .ok()?;
 
-    match output {
-        Ok(out) => {
-            let stdout = String::from_utf8_lossy(&out.stdout);
-            // If any profile returns True, firewall is enabled
-            stdout.contains("True")
-        }
-        Err(_) => true, // Assume enabled if we can't check
+    parse_firewall_profiles(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parse `Name=True`/`Name=False` lines into a profile map
+#[cfg(target_os = "windows")]
+fn parse_firewall_profiles(output: &str) -> Option<HashMap<String, bool>> {
+    let profiles: HashMap<String, bool> = output
+        .lines()
+        .filter_map(|line| line.trim().split_once('='))
+        .filter(|(name, _)| !name.is_empty())
+        .map(|(name, enabled)| (name.to_string(), enabled.eq_ignore_ascii_case("True")))
+        .collect();
+
+    if profiles.is_empty() {
+        None
+    } else {
+        Some(profiles)
     }
 }
 
@@ -75,46 +173,72 @@ fn check_windows_rule_exists(rule_name: &str) -> bool {
     }
 }
 
-/// Check if Linux iptables rule exists
-#[cfg(target_os = "linux")]
-fn check_iptables_rule_exists(port: u16) -> bool {
-    let output = Command::new("iptables")
-        .args(["-C", "INPUT", "-p", "udp", "--dport", &port.to_string(), "-j", "ACCEPT"])
-        .output();
-
-    match output {
-        Ok(out) => out.status.success(),
-        Err(_) => false,
-    }
+/// PowerShell to create the rule; "both" creates one rule per protocol under the same name
+#[cfg(target_os = "windows")]
+fn windows_add_rule_script(rule_name: &str, port: u16, protocol: FirewallProtocol) -> String {
+    protocol
+        .protocols()
+        .iter()
+        .map(|proto| {
+            format!(
+                "New-NetFirewallRule -DisplayName '{}' -Direction Inbound -Protocol {} -LocalPort {} -Action Allow",
+                rule_name.replace('\'', "''"),
+                proto.to_ascii_uppercase(),
+                port
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
 }
 
-/// Check if Linux ufw rule exists
-#[cfg(target_os = "linux")]
-fn check_ufw_rule_exists(port: u16) -> bool {
-    let output = Command::new("ufw")
-        .args(["status", "numbered"])
+/// (port, protocol) of each Windows firewall rule with this display name
+#[cfg(target_os = "windows")]
+fn windows_rule_entries(rule_name: &str) -> Vec<(u16, String)> {
+    let output = Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-Command",
+            &format!(
+                "Get-NetFirewallRule -DisplayName '{}' -ErrorAction SilentlyContinue | Get-NetFirewallPortFilter | ForEach-Object {{ \"$($_.LocalPort) $($_.Protocol)\" }}",
+                rule_name.replace('\'', "''")
+            ),
+        ])
         .output();
 
     match output {
-        Ok(out) => {
-            let stdout = String::from_utf8_lossy(&out.stdout);
-            stdout.contains(&format!("{}/udp", port))
-        }
-        Err(_) => false,
+        Ok(out) => String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .filter_map(|l| {
+                let (port, proto) = l.trim().split_once(' ')?;
+                Some((port.parse().ok()?, proto.trim().to_ascii_lowercase()))
+            })
+            .collect(),
+        Err(_) => Vec::new(),
     }
 }
 
+/// Whether existing named rules open exactly `port` for exactly the protocols in `protocol`
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+fn rules_match(rules: &[(u16, String)], port: u16, protocol: FirewallProtocol) -> bool {
+    let mut existing: Vec<(u16, &str)> = rules.iter().map(|(p, proto)| (*p, proto.as_str())).collect();
+    existing.sort();
+    existing.dedup();
+    let mut wanted: Vec<(u16, &str)> = protocol.protocols().iter().map(|proto| (port, *proto)).collect();
+    wanted.sort();
+    existing == wanted
+}
+
 /// Detect available firewall on Linux
 #[cfg(target_os = "linux")]
 fn detect_linux_firewall() -> Option<&'static str> {
-    // Check for ufw first (more user-friendly)
+    // Check for ufw first (more user-friendly). `ufw status` needs root, so read
+    // whether it's enabled from its world-readable config instead
     if Command::new("which").arg("ufw").output().map(|o| o.status.success()).unwrap_or(false) {
-        // Check if ufw is active
-        if let Ok(output) = Command::new("ufw").arg("status").output() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            if stdout.contains("Status: active") {
-                return Some("ufw");
-            }
+        let enabled = std::fs::read_to_string("/etc/ufw/ufw.conf")
+            .map(|conf| conf.lines().any(|l| l.trim() == "ENABLED=yes"))
+            .unwrap_or(false);
+        if enabled {
+            return Some("ufw");
         }
     }
 
@@ -126,28 +250,384 @@ fn detect_linux_firewall() -> Option<&'static str> {
     None
 }
 
-/// Get firewall information for a specific port
+/// Check if pkexec is available for privilege escalation
+#[cfg(target_os = "linux")]
+fn has_pkexec() -> bool {
+    Command::new("which")
+        .arg("pkexec")
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Run a shell script as root through pkexec (shows a graphical password prompt)
+/// and return its stdout. Async so the wait on the prompt doesn't hold a runtime worker.
+#[cfg(target_os = "linux")]
+async fn run_with_pkexec(script: &str) -> Result<String, String> {
+    if !has_pkexec() {
+        return Err(
+            "pkexec is not installed. Please run the firewall command manually in a terminal with sudo"
+                .to_string(),
+        );
+    }
+
+    let output = tokio::process::Command::new("pkexec")
+        .args(["sh", "-c", script])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute pkexec: {}", e))?;
+
+    match output.status.code() {
+        Some(0) => Ok(String::from_utf8_lossy(&output.stdout).to_string()),
+        // 126: the user dismissed the authentication dialog, 127: not authorized
+        Some(126) | Some(127) => Err("Administrator permission was declined".to_string()),
+        _ => Err(format!(
+            "Firewall command failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+    }
+}
+
+/// Quote an argument for `sh -c`
+#[cfg(target_os = "linux")]
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+/// Printed between the rule listings of a privileged firewall script
+#[cfg(target_os = "linux")]
+const RULE_LISTING_SEPARATOR: &str = "---hypanel-rules---";
+
+/// Command that lists the INPUT rules of a Linux firewall with their comments (needs root)
+#[cfg(target_os = "linux")]
+fn linux_list_rules_command(firewall: &str) -> &'static str {
+    match firewall {
+        "ufw" => "ufw status",
+        _ => "iptables -S INPUT",
+    }
+}
+
+/// Read the rule listing without privileges. Both tools need root, so this only works
+/// when the app itself runs as root; otherwise the privileged script reads it.
+#[cfg(target_os = "linux")]
+fn read_linux_rules_unprivileged(firewall: &str) -> Option<String> {
+    let output = Command::new("sh")
+        .args(["-c", linux_list_rules_command(firewall)])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// (port, protocol) of each rule in a listing whose comment is exactly `rule_name`
+#[cfg(target_os = "linux")]
+fn parse_linux_named_rules(firewall: &str, listing: &str, rule_name: &str) -> Vec<(u16, String)> {
+    let mut rules: Vec<(u16, String)> = match firewall {
+        // e.g. "5520/udp   ALLOW   Anywhere   # HyPanel - Survival"
+        "ufw" => {
+            let comment = format!("# {}", rule_name);
+            listing
+                .lines()
+                .filter(|l| l.trim_end().ends_with(&comment))
+                .filter_map(|l| l.split_whitespace().next())
+                .filter_map(|target| target.split_once('/'))
+                .filter_map(|(port, proto)| Some((port.parse().ok()?, proto.to_string())))
+                .collect()
+        }
+        // e.g. "-A INPUT -p udp -m udp --dport 5520 -m comment --comment "HyPanel - Survival" -j ACCEPT"
+        _ => {
+            let comment = format!("--comment \"{}\"", rule_name);
+            listing
+                .lines()
+                .filter(|l| l.contains(&comment))
+                .filter_map(|l| {
+                    let tokens: Vec<&str> = l.split_whitespace().collect();
+                    let value_after = |flag: &str| {
+                        tokens.iter().position(|t| *t == flag).and_then(|i| tokens.get(i + 1))
+                    };
+                    Some((value_after("--dport")?.parse().ok()?, value_after("-p")?.to_string()))
+                })
+                .collect()
+        }
+    };
+
+    // ufw lists IPv4 and IPv6 rules separately
+    rules.sort();
+    rules.dedup();
+    rules
+}
+
+/// Whether `rules` open `port` for every protocol in `protocol`
+#[cfg(target_os = "linux")]
+fn has_linux_rules(rules: &[(u16, String)], port: u16, protocol: FirewallProtocol) -> bool {
+    protocol
+        .protocols()
+        .iter()
+        .all(|proto| rules.iter().any(|(p, pr)| *p == port && pr == proto))
+}
+
+/// Prints "<port> <proto>" for each `ufw status` line ending in "# $NAME"
+#[cfg(target_os = "linux")]
+const UFW_NAMED_RULES_AWK: &str = r#"{ sub(/[ \t]+$/, "") } length($0) >= length(c) && substr($0, length($0) - length(c) + 1) == c { split($1, t, "/"); print t[1], t[2] }"#;
+
+/// Prints "<port> <proto>" for each `iptables -S` line carrying `--comment "$NAME"`
+#[cfg(target_os = "linux")]
+const IPTABLES_NAMED_RULES_AWK: &str = r#"index($0, c) { p = ""; d = ""; for (i = 1; i < NF; i++) { if ($i == "-p") p = $(i + 1); if ($i == "--dport") d = $(i + 1) } if (p != "" && d != "") print d, p }"#;
+
+/// Shell script, run as root, that deletes the rules commented `rule_name` for which the shell
+/// test `delete_if` (over `$port` and `$proto`) holds, then adds any of `add` that are missing.
+/// Rules are matched by their comment, so other rules for the same port are left alone.
+/// Prints the rule listing before and after, separated by RULE_LISTING_SEPARATOR.
+#[cfg(target_os = "linux")]
+fn linux_rules_script(firewall: &str, rule_name: &str, delete_if: Option<&str>, add: &[(u16, &str)]) -> String {
+    let list = linux_list_rules_command(firewall);
+    let mut script = format!(
+        "NAME={}\n{}\necho '{}'\n",
+        shell_quote(rule_name),
+        list,
+        RULE_LISTING_SEPARATOR
+    );
+
+    if let Some(condition) = delete_if {
+        let (awk_comment, awk_program, delete) = match firewall {
+            "ufw" => ("# $NAME", UFW_NAMED_RULES_AWK, r#"ufw delete allow "$port/$proto""#),
+            _ => (
+                r#"--comment \"$NAME\""#,
+                IPTABLES_NAMED_RULES_AWK,
+                r#"iptables -D INPUT -p "$proto" --dport "$port" -j ACCEPT -m comment --comment "$NAME""#,
+            ),
+        };
+        script.push_str(&format!(
+            "{} | awk -v c=\"{}\" {} | sort -u | while read -r port proto; do\n  if {}; then {}; fi\ndone\n",
+            list,
+            awk_comment,
+            shell_quote(awk_program),
+            condition,
+            delete
+        ));
+    }
+
+    for (port, proto) in add {
+        match firewall {
+            // ufw skips rules that already exist
+            "ufw" => script.push_str(&format!("ufw allow {}/{} comment \"$NAME\"\n", port, proto)),
+            _ => {
+                let spec = format!("INPUT -p {} --dport {} -j ACCEPT -m comment --comment \"$NAME\"", proto, port);
+                script.push_str(&format!("iptables -C {} 2>/dev/null || iptables -A {}\n", spec, spec));
+            }
+        }
+    }
+
+    script.push_str(&format!("echo '{}'\n{}\n", RULE_LISTING_SEPARATOR, list));
+    script
+}
+
+/// Named rules from the listings printed before and after a `linux_rules_script` run
+#[cfg(target_os = "linux")]
+fn split_rule_listings(firewall: &str, output: &str, rule_name: &str) -> (Vec<(u16, String)>, Vec<(u16, String)>) {
+    let parts: Vec<&str> = output.split(RULE_LISTING_SEPARATOR).collect();
+    let before = parts.first().copied().unwrap_or_default();
+    let after = parts.last().copied().unwrap_or_default();
+    (
+        parse_linux_named_rules(firewall, before, rule_name),
+        parse_linux_named_rules(firewall, after, rule_name),
+    )
+}
+
+/// Shell test matching the rules for `port` with one of the given protocols
+#[cfg(target_os = "linux")]
+fn linux_rule_condition(port: u16, protocol: FirewallProtocol) -> String {
+    let protos = protocol
+        .protocols()
+        .iter()
+        .map(|proto| format!("[ \"$proto\" = {} ]", proto))
+        .collect::<Vec<_>>()
+        .join(" || ");
+    format!("[ \"$port\" = {} ] && {{ {}; }}", port, protos)
+}
+
+/// pf anchor file holding HyPanel rules on macOS
+#[cfg(target_os = "macos")]
+const PF_ANCHOR_PATH: &str = "/etc/pf.anchors/hypanel";
+
+/// Build the pf rule line for a port, labelled with the rule name
+#[cfg(target_os = "macos")]
+fn pf_rule_line(port: u16, protocol: FirewallProtocol, rule_name: &str) -> String {
+    let proto = match protocol.protocols() {
+        [single] => single.to_string(),
+        all => format!("{{ {} }}", all.join(" ")),
+    };
+    format!(
+        "pass in proto {} from any to any port {} label \"{}\"",
+        proto,
+        port,
+        rule_name.replace('"', "")
+    )
+}
+
+/// Read the current HyPanel pf anchor rules (the anchor file is world-readable)
+#[cfg(target_os = "macos")]
+fn read_pf_anchor_rules() -> Vec<String> {
+    std::fs::read_to_string(PF_ANCHOR_PATH)
+        .map(|content| {
+            content
+                .lines()
+                .map(|l| l.trim().to_string())
+                .filter(|l| !l.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Check if our pf anchor contains a rule with this name
+#[cfg(target_os = "macos")]
+fn check_pf_rule_exists(rule_name: &str) -> bool {
+    let label = format!("label \"{}\"", rule_name.replace('"', ""));
+    read_pf_anchor_rules().iter().any(|l| l.ends_with(&label))
+}
+
+/// (port, protocol) pairs opened by the pf anchor rules with this name.
+/// Handles both "proto udp" and "proto { udp tcp }".
+#[cfg(target_os = "macos")]
+fn pf_rule_entries(rule_name: &str) -> Vec<(u16, String)> {
+    let label = format!("label \"{}\"", rule_name.replace('"', ""));
+    read_pf_anchor_rules()
+        .iter()
+        .filter(|l| l.ends_with(&label))
+        .flat_map(|l| {
+            let tokens: Vec<&str> = l.split_whitespace().collect();
+            let port = tokens
+                .iter()
+                .position(|t| *t == "port")
+                .and_then(|i| tokens.get(i + 1))
+                .and_then(|p| p.parse::<u16>().ok());
+            let protos: Vec<String> = match tokens.iter().position(|t| *t == "proto") {
+                Some(i) if tokens.get(i + 1) == Some(&"{") => tokens[i + 2..]
+                    .iter()
+                    .take_while(|t| **t != "}")
+                    .map(|t| t.to_string())
+                    .collect(),
+                Some(i) => tokens.get(i + 1).map(|t| vec![t.to_string()]).unwrap_or_default(),
+                None => Vec::new(),
+            };
+            protos.into_iter().filter_map(move |proto| Some((port?, proto)))
+        })
+        .collect()
+}
+
+/// Write new anchor rules, make sure pf.conf references the anchor and reload pf.
+/// Prompts for administrator rights via osascript; async so the wait on the prompt
+/// doesn't hold a runtime worker.
+#[cfg(target_os = "macos")]
+async fn apply_pf_anchor_rules(rules: &[String]) -> Result<(), String> {
+    let temp_dir = std::env::temp_dir();
+    let rules_path = temp_dir.join("hypanel_pf_anchor");
+    let script_path = temp_dir.join("hypanel_pf.sh");
+
+    let mut anchor_content = rules.join("\n");
+    anchor_content.push('\n');
+    std::fs::write(&rules_path, anchor_content)
+        .map_err(|e| format!("Failed to create temporary anchor file: {}", e))?;
+
+    let script = format!(
+        r#"set -e
+cp '{rules}' '{anchor}'
+chmod 644 '{anchor}'
+grep -q 'anchor "hypanel"' /etc/pf.conf || printf '\nanchor "hypanel"\nload anchor "hypanel" from "{anchor}"\n' >> /etc/pf.conf
+pfctl -f /etc/pf.conf
+pfctl -e 2>/dev/null || true
+"#,
+        rules = rules_path.display(),
+        anchor = PF_ANCHOR_PATH,
+    );
+
+    if let Err(e) = std::fs::write(&script_path, script) {
+        let _ = std::fs::remove_file(&rules_path);
+        return Err(format!("Failed to create temporary script: {}", e));
+    }
+
+    let output = tokio::process::Command::new("osascript")
+        .args([
+            "-e",
+            &format!(
+                "do shell script \"/bin/sh '{}'\" with administrator privileges",
+                script_path.display()
+            ),
+        ])
+        .output()
+        .await;
+
+    // Clean up temp files
+    let _ = std::fs::remove_file(&rules_path);
+    let _ = std::fs::remove_file(&script_path);
+
+    match output {
+        Ok(out) if out.status.success() => Ok(()),
+        Ok(out) => {
+            let stderr = String::from_utf8_lossy(&out.stderr);
+            // osascript reports error -128 when the user cancels the admin prompt
+            if stderr.contains("-128") || stderr.contains("User canceled") {
+                Err("Administrator permission was declined".to_string())
+            } else {
+                Err(format!("Failed to apply pf rules: {}", stderr.trim()))
+            }
+        }
+        Err(e) => Err(format!("Failed to execute osascript: {}", e)),
+    }
+}
+
+/// Get firewall information for a specific port.
+/// `protocol` is "udp" (default), "tcp" or "both".
 #[tauri::command]
-pub async fn get_firewall_info(port: u16, server_name: String) -> Result<FirewallInfo, ()> {
+pub async fn get_firewall_info(
+    port: u16,
+    server_name: String,
+    protocol: Option<String>,
+) -> Result<FirewallInfo, ()> {
     let os = get_os();
     let rule_name = format!("HyPanel - {}", server_name);
 
+    let protocol = match FirewallProtocol::parse(protocol.as_deref()) {
+        Ok(p) => p,
+        Err(e) => {
+            return Ok(FirewallInfo {
+                os: os.to_string(),
+                firewall_type: None,
+                firewall_enabled: false,
+                profiles: None,
+                rule_exists: false,
+                rule_name,
+                port,
+                protocol: String::new(),
+                command_to_add: String::new(),
+                command_to_remove: String::new(),
+                error: Some(e),
+            });
+        }
+    };
+
     #[cfg(target_os = "windows")]
     {
-        let firewall_enabled = check_windows_firewall_enabled();
+        let profiles = check_windows_firewall_profiles();
+        // Enabled if any profile is on; assume enabled if we can't check
+        let firewall_enabled = profiles
+            .as_ref()
+            .map(|p| p.values().any(|&enabled| enabled))
+            .unwrap_or(true);
         let rule_exists = check_windows_rule_exists(&rule_name);
 
         Ok(FirewallInfo {
             os: os.to_string(),
             firewall_type: Some("Windows Firewall".to_string()),
             firewall_enabled,
+            profiles,
             rule_exists,
             rule_name: rule_name.clone(),
             port,
-            command_to_add: format!(
-                "New-NetFirewallRule -DisplayName \"{}\" -Direction Inbound -Protocol UDP -LocalPort {} -Action Allow",
-                rule_name, port
-            ),
+            protocol: protocol.as_str().to_string(),
+            command_to_add: windows_add_rule_script(&rule_name, port, protocol),
             command_to_remove: format!(
                 "Remove-NetFirewallRule -DisplayName \"{}\"",
                 rule_name
@@ -160,18 +640,37 @@ pub async fn get_firewall_info(port: u16, server_name: String) -> Result<Firewal
     {
         let firewall_type = detect_linux_firewall();
 
-        let (rule_exists, cmd_add, cmd_remove) = match firewall_type {
+        let sudo_commands = |build: &dyn Fn(&str) -> String| {
+            protocol
+                .protocols()
+                .iter()
+                .map(|proto| build(proto))
+                .collect::<Vec<_>>()
+                .join(" && ")
+        };
+
+        // Listing rules needs root, so this is only known when the app runs as root
+        let rule_exists = firewall_type
+            .and_then(|firewall| {
+                let listing = read_linux_rules_unprivileged(firewall)?;
+                Some(has_linux_rules(&parse_linux_named_rules(firewall, &listing, &rule_name), port, protocol))
+            })
+            .unwrap_or(false);
+
+        let (cmd_add, cmd_remove) = match firewall_type {
             Some("ufw") => (
-                check_ufw_rule_exists(port),
-                format!("sudo ufw allow {}/udp comment '{}'", port, rule_name),
-                format!("sudo ufw delete allow {}/udp", port),
+                sudo_commands(&|proto| format!("sudo ufw allow {}/{} comment '{}'", port, proto, rule_name)),
+                sudo_commands(&|proto| format!("sudo ufw delete allow {}/{}", port, proto)),
             ),
             Some("iptables") => (
-                check_iptables_rule_exists(port),
-                format!("sudo iptables -A INPUT -p udp --dport {} -j ACCEPT -m comment --comment \"{}\"", port, rule_name),
-                format!("sudo iptables -D INPUT -p udp --dport {} -j ACCEPT", port),
+                sudo_commands(&|proto| {
+                    format!("sudo iptables -A INPUT -p {} --dport {} -j ACCEPT -m comment --comment \"{}\"", proto, port, rule_name)
+                }),
+                sudo_commands(&|proto| {
+                    format!("sudo iptables -D INPUT -p {} --dport {} -j ACCEPT -m comment --comment \"{}\"", proto, port, rule_name)
+                }),
             ),
-            _ => (false, String::new(), String::new()),
+            _ => (String::new(), String::new()),
         };
 
         // For Linux, if we detected a firewall it's likely active
@@ -181,9 +680,11 @@ pub async fn get_firewall_info(port: u16, server_name: String) -> Result<Firewal
             os: os.to_string(),
             firewall_type: firewall_type.map(|s| s.to_string()),
             firewall_enabled,
+            profiles: None,
             rule_exists,
             rule_name,
             port,
+            protocol: protocol.as_str().to_string(),
             command_to_add: cmd_add,
             command_to_remove: cmd_remove,
             error: if firewall_type.is_none() {
@@ -196,21 +697,29 @@ pub async fn get_firewall_info(port: u16, server_name: String) -> Result<Firewal
 
     #[cfg(target_os = "macos")]
     {
-        // macOS uses pf (packet filter) which is more complex
-        // For now, just return info without automatic detection
+        // macOS rules live in a dedicated pf anchor managed by HyPanel
+        let rule_exists = check_pf_rule_exists(&rule_name);
+
         Ok(FirewallInfo {
             os: os.to_string(),
-            firewall_type: Some("macOS Firewall".to_string()),
-            firewall_enabled: true, // Assume enabled, macOS firewall is usually on
-            rule_exists: false,
-            rule_name,
+            firewall_type: Some("pf".to_string()),
+            firewall_enabled: true, // pfctl -s info requires root, assume enabled
+            profiles: None,
+            rule_exists,
+            rule_name: rule_name.clone(),
             port,
+            protocol: protocol.as_str().to_string(),
             command_to_add: format!(
-                "# Add to /etc/pf.conf:\npass in proto udp from any to any port {}",
-                port
+                "echo '{}' | sudo tee -a {} && sudo pfctl -f /etc/pf.conf",
+                pf_rule_line(port, protocol, &rule_name),
+                PF_ANCHOR_PATH
+            ),
+            command_to_remove: format!(
+                "sudo sed -i '' '/label \"{}\"/d' {} && sudo pfctl -f /etc/pf.conf",
+                rule_name.replace('"', ""),
+                PF_ANCHOR_PATH
             ),
-            command_to_remove: "# Remove the rule from /etc/pf.conf".to_string(),
-            error: Some("macOS firewall requires manual configuration".to_string()),
+            error: None,
         })
     }
 
@@ -220,9 +729,11 @@ pub async fn get_firewall_info(port: u16, server_name: String) -> Result<Firewal
             os: os.to_string(),
             firewall_type: None,
             firewall_enabled: false,
+            profiles: None,
             rule_exists: false,
             rule_name,
             port,
+            protocol: protocol.as_str().to_string(),
             command_to_add: String::new(),
             command_to_remove: String::new(),
             error: Some("Unsupported operating system".to_string()),
@@ -230,27 +741,99 @@ pub async fn get_firewall_info(port: u16, server_name: String) -> Result<Firewal
     }
 }
 
-/// Add firewall rule (requires elevated permissions on Windows)
+/// Get firewall information for an instance, reading the port from its config.json
+#[tauri::command]
+pub async fn get_instance_firewall_info(
+    instance_path: String,
+    server_name: String,
+    protocol: Option<String>,
+) -> Result<InstanceFirewallInfo, ()> {
+    let (port, port_source, note) = match read_config_port(&instance_path) {
+        Some(port) => (port, "config", None),
+        None => (
+            DEFAULT_SERVER_PORT,
+            "default",
+            Some(format!(
+                "No port found in config.json, using the default port {}",
+                DEFAULT_SERVER_PORT
+            )),
+        ),
+    };
+
+    let info = get_firewall_info(port, server_name, protocol).await?;
+
+    Ok(InstanceFirewallInfo {
+        info,
+        port_source: port_source.to_string(),
+        note,
+    })
+}
+
+/// Result for an add request whose rule was already in place
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+fn rule_unchanged() -> FirewallResult {
+    FirewallResult {
+        success: true,
+        message: "Firewall rule already exists".to_string(),
+        error: None,
+        action: Some("unchanged".to_string()),
+    }
+}
+
+/// Result for a rule that was created, or replaced because it opened another port
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+fn rule_added(port: u16, protocol: FirewallProtocol, updated: bool) -> FirewallResult {
+    let action = if updated { "updated" } else { "created" };
+    FirewallResult {
+        success: true,
+        message: format!("Firewall rule {} for {} port {}", action, protocol.label(), port),
+        error: None,
+        action: Some(action.to_string()),
+    }
+}
+
+/// Add firewall rule (requires elevated permissions on Windows).
+/// `protocol` is "udp" (default), "tcp" or "both". A rule with this name that opens a
+/// different port (e.g. after the server's port changed) is replaced.
 #[tauri::command]
-pub async fn add_firewall_rule(_port: u16, server_name: String) -> Result<FirewallResult, ()> {
+pub async fn add_firewall_rule(
+    _port: u16,
+    server_name: String,
+    protocol: Option<String>,
+) -> Result<FirewallResult, ()> {
     let _rule_name = format!("HyPanel - {}", server_name);
 
-    #[cfg(target_os = "windows")]
-    {
-        // Check if rule already exists
-        if check_windows_rule_exists(&_rule_name) {
+    let _protocol = match FirewallProtocol::parse(protocol.as_deref()) {
+        Ok(p) => p,
+        Err(e) => {
             return Ok(FirewallResult {
-                success: true,
-                message: "Firewall rule already exists".to_string(),
-                error: None,
+                success: false,
+                message: "Invalid protocol".to_string(),
+                error: Some(e),
+                action: None,
             });
         }
+    };
+
+    #[cfg(target_os = "windows")]
+    {
+        // A rule for another port or protocol set is replaced
+        let rule_exists = check_windows_rule_exists(&_rule_name);
+        let stale = rule_exists && !rules_match(&windows_rule_entries(&_rule_name), _port, _protocol);
+
+        if rule_exists && !stale {
+            return Ok(rule_unchanged());
+        }
 
         // Write script to a temp file to avoid argument escaping issues
-        let script = format!(
-            "$ruleName = '{}'; New-NetFirewallRule -DisplayName $ruleName -Direction Inbound -Protocol UDP -LocalPort {} -Action Allow",
-            _rule_name.replace("'", "''"), _port
-        );
+        let mut script = windows_add_rule_script(&_rule_name, _port, _protocol);
+        if stale {
+            script = format!(
+                "Remove-NetFirewallRule -DisplayName '{}'; {}",
+                _rule_name.replace('\'', "''"),
+                script
+            );
+        }
 
         let temp_dir = std::env::temp_dir();
         let script_path = temp_dir.join("hypanel_firewall.ps1");
@@ -260,6 +843,7 @@ pub async fn add_firewall_rule(_port: u16, server_name: String) -> Result<Firewa
                 success: false,
                 message: "Failed to create temporary script".to_string(),
                 error: Some(e.to_string()),
+                action: None,
             });
         }
 
@@ -280,18 +864,15 @@ pub async fn add_firewall_rule(_port: u16, server_name: String) -> Result<Firewa
                 // Give Windows a moment to process
                 std::thread::sleep(std::time::Duration::from_millis(500));
 
-                // Verify the rule was created
-                if check_windows_rule_exists(&_rule_name) {
-                    Ok(FirewallResult {
-                        success: true,
-                        message: format!("Firewall rule created for UDP port {}", _port),
-                        error: None,
-                    })
+                // Verify the rule was created for the right port and protocols
+                if rules_match(&windows_rule_entries(&_rule_name), _port, _protocol) {
+                    Ok(rule_added(_port, _protocol, stale))
                 } else {
                     Ok(FirewallResult {
                         success: false,
                         message: "Failed to create firewall rule. Try running as administrator.".to_string(),
                         error: None,
+                        action: None,
                     })
                 }
             }
@@ -299,28 +880,103 @@ pub async fn add_firewall_rule(_port: u16, server_name: String) -> Result<Firewa
                 success: false,
                 message: "Failed to execute PowerShell".to_string(),
                 error: Some(e.to_string()),
+                action: None,
             }),
         }
     }
 
     #[cfg(target_os = "linux")]
     {
-        // Linux requires sudo, which needs terminal interaction
-        // Return the command for the user to run manually
-        Ok(FirewallResult {
-            success: false,
-            message: "Linux firewall requires manual configuration with sudo".to_string(),
-            error: Some("Please run the firewall command manually in a terminal with sudo".to_string()),
-        })
+        let firewall = match detect_linux_firewall() {
+            Some(f) => f,
+            None => {
+                return Ok(FirewallResult {
+                    success: false,
+                    message: "No supported firewall detected".to_string(),
+                    error: Some("Neither ufw nor iptables is available".to_string()),
+                    action: None,
+                });
+            }
+        };
+
+        let has_stale = |rules: &[(u16, String)]| rules.iter().any(|(port, _)| *port != _port);
+
+        // Skip the password prompt when we can already see the rule (app running as root)
+        if let Some(listing) = read_linux_rules_unprivileged(firewall) {
+            let rules = parse_linux_named_rules(firewall, &listing, &_rule_name);
+            if has_linux_rules(&rules, _port, _protocol) && !has_stale(&rules) {
+                return Ok(rule_unchanged());
+            }
+        }
+
+        // Drop rules for the old port, then add the protocols that aren't open yet, in one prompt
+        let stale_condition = format!("[ \"$port\" != {} ]", _port);
+        let add: Vec<(u16, &str)> = _protocol.protocols().iter().map(|proto| (_port, *proto)).collect();
+        let script = linux_rules_script(firewall, &_rule_name, Some(&stale_condition), &add);
+
+        match run_with_pkexec(&script).await {
+            Ok(output) => {
+                let (before, after) = split_rule_listings(firewall, &output, &_rule_name);
+
+                if !has_linux_rules(&after, _port, _protocol) || has_stale(&after) {
+                    Ok(FirewallResult {
+                        success: false,
+                        message: "Firewall command ran but the rule was not found".to_string(),
+                        error: None,
+                        action: None,
+                    })
+                } else if has_linux_rules(&before, _port, _protocol) && !has_stale(&before) {
+                    Ok(rule_unchanged())
+                } else {
+                    Ok(rule_added(_port, _protocol, has_stale(&before)))
+                }
+            }
+            Err(e) => Ok(FirewallResult {
+                success: false,
+                message: "Failed to create firewall rule".to_string(),
+                error: Some(e),
+                action: None,
+            }),
+        }
     }
 
     #[cfg(target_os = "macos")]
     {
-        Ok(FirewallResult {
-            success: false,
-            message: "macOS firewall requires manual configuration".to_string(),
-            error: Some("Please configure the firewall manually".to_string()),
-        })
+        // A rule for another port or protocol set is replaced
+        let rule_exists = check_pf_rule_exists(&_rule_name);
+        let stale = rule_exists && !rules_match(&pf_rule_entries(&_rule_name), _port, _protocol);
+
+        if rule_exists && !stale {
+            return Ok(rule_unchanged());
+        }
+
+        let label = format!("label \"{}\"", _rule_name.replace('"', ""));
+        let mut rules: Vec<String> = read_pf_anchor_rules()
+            .into_iter()
+            .filter(|l| !l.ends_with(&label))
+            .collect();
+        rules.push(pf_rule_line(_port, _protocol, &_rule_name));
+
+        match apply_pf_anchor_rules(&rules).await {
+            Ok(()) => {
+                if rules_match(&pf_rule_entries(&_rule_name), _port, _protocol) {
+                    Ok(rule_added(_port, _protocol, stale))
+                } else {
+                    Ok(FirewallResult {
+                        success: false,
+                        message: "Failed to create firewall rule".to_string(),
+                        error: None,
+                        action: None,
+                    })
+                }
+            }
+            Err(e) => Ok(FirewallResult {
+                success: false,
+                message: "Failed to create firewall rule".to_string(),
+                error: Some(e),
+                action: None,
+            }),
+        }
     }
 
     #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
@@ -329,15 +985,34 @@ pub async fn add_firewall_rule(_port: u16, server_name: String) -> Result<Firewa
             success: false,
             message: "Unsupported operating system".to_string(),
             error: Some("Cannot configure firewall on this OS".to_string()),
+            action: None,
         })
     }
 }
 
-/// Remove firewall rule
+/// Remove firewall rule (the port is required on Linux, where rules are matched by port).
+/// `protocol` is "udp" (default), "tcp" or "both"; Windows and macOS remove the rule by name
+/// whatever its protocol.
 #[tauri::command]
-pub async fn remove_firewall_rule(server_name: String) -> Result<FirewallResult, ()> {
+pub async fn remove_firewall_rule(
+    server_name: String,
+    _port: Option<u16>,
+    protocol: Option<String>,
+) -> Result<FirewallResult, ()> {
     let _rule_name = format!("HyPanel - {}", server_name);
 
+    let _protocol = match FirewallProtocol::parse(protocol.as_deref()) {
+        Ok(p) => p,
+        Err(e) => {
+            return Ok(FirewallResult {
+                success: false,
+                message: "Invalid protocol".to_string(),
+                error: Some(e),
+                action: None,
+            });
+        }
+    };
+
     #[cfg(target_os = "windows")]
     {
         if !check_windows_rule_exists(&_rule_name) {
@@ -345,6 +1020,7 @@ pub async fn remove_firewall_rule(server_name: String) -> Result<FirewallResult,
                 success: true,
                 message: "Firewall rule does not exist".to_string(),
                 error: None,
+                action: None,
             });
         }
 
@@ -367,12 +1043,14 @@ pub async fn remove_firewall_rule(server_name: String) -> Result<FirewallResult,
                         success: true,
                         message: format!("Firewall rule '{}' removed successfully", _rule_name),
                         error: None,
+                        action: None,
                     })
                 } else {
                     Ok(FirewallResult {
                         success: false,
                         message: "Failed to remove firewall rule".to_string(),
                         error: None,
+                        action: None,
                     })
                 }
             }
@@ -380,16 +1058,368 @@ pub async fn remove_firewall_rule(server_name: String) -> Result<FirewallResult,
                 success: false,
                 message: "Failed to execute PowerShell".to_string(),
                 error: Some(e.to_string()),
+                action: None,
+            }),
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        if !check_pf_rule_exists(&_rule_name) {
+            return Ok(FirewallResult {
+                success: true,
+                message: "Firewall rule does not exist".to_string(),
+                error: None,
+                action: None,
+            });
+        }
+
+        let label = format!("label \"{}\"", _rule_name.replace('"', ""));
+        let rules: Vec<String> = read_pf_anchor_rules()
+            .into_iter()
+            .filter(|l| !l.ends_with(&label))
+            .collect();
+
+        match apply_pf_anchor_rules(&rules).await {
+            Ok(()) if !check_pf_rule_exists(&_rule_name) => Ok(FirewallResult {
+                success: true,
+                message: format!("Firewall rule '{}' removed successfully", _rule_name),
+                error: None,
+                action: None,
+            }),
+            Ok(()) => Ok(FirewallResult {
+                success: false,
+                message: "Failed to remove firewall rule".to_string(),
+                error: None,
+                action: None,
+            }),
+            Err(e) => Ok(FirewallResult {
+                success: false,
+                message: "Failed to remove firewall rule".to_string(),
+                error: Some(e),
+                action: None,
+            }),
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let port = match _port {
+            Some(p) => p,
+            None => {
+                return Ok(FirewallResult {
+                    success: false,
+                    message: "Port is required to remove a Linux firewall rule".to_string(),
+                    error: None,
+                    action: None,
+                });
+            }
+        };
+
+        let firewall = match detect_linux_firewall() {
+            Some(f) => f,
+            None => {
+                return Ok(FirewallResult {
+                    success: false,
+                    message: "No supported firewall detected".to_string(),
+                    error: Some("Neither ufw nor iptables is available".to_string()),
+                    action: None,
+                });
+            }
+        };
+
+        // The script deletes only this rule's entries for the port, listing rules before and after
+        let condition = linux_rule_condition(port, _protocol);
+        let script = linux_rules_script(firewall, &_rule_name, Some(&condition), &[]);
+        let matches = |rules: &[(u16, String)]| {
+            rules
+                .iter()
+                .any(|(p, proto)| *p == port && _protocol.protocols().contains(&proto.as_str()))
+        };
+
+        match run_with_pkexec(&script).await {
+            Ok(output) => {
+                let (before, after) = split_rule_listings(firewall, &output, &_rule_name);
+
+                if !matches(&before) {
+                    Ok(FirewallResult {
+                        success: true,
+                        message: "Firewall rule does not exist".to_string(),
+                        error: None,
+                        action: None,
+                    })
+                } else if !matches(&after) {
+                    Ok(FirewallResult {
+                        success: true,
+                        message: format!("Firewall rule '{}' removed successfully", _rule_name),
+                        error: None,
+                        action: None,
+                    })
+                } else {
+                    Ok(FirewallResult {
+                        success: false,
+                        message: "Failed to remove firewall rule".to_string(),
+                        error: None,
+                        action: None,
+                    })
+                }
+            }
+            Err(e) => Ok(FirewallResult {
+                success: false,
+                message: "Failed to remove firewall rule".to_string(),
+                error: Some(e),
+                action: None,
             }),
         }
     }
 
-    #[cfg(not(target_os = "windows"))]
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
     {
         Ok(FirewallResult {
             success: false,
             message: "Manual removal required".to_string(),
             error: Some("Please remove the firewall rule manually".to_string()),
+            action: None,
         })
     }
 }
+
+/// Get the machine's public IPv4/IPv6 addresses
+#[tauri::command]
+pub async fn get_public_ip() -> PublicIpResult {
+    let (ipv4, ipv6) = lookup_public_ips().await;
+
+    PublicIpResult {
+        success: ipv4.is_some() || ipv6.is_some(),
+        error: if ipv4.is_none() && ipv6.is_none() {
+            Some("Could not determine public IP address".to_string())
+        } else {
+            None
+        },
+        ipv4,
+        ipv6,
+    }
+}
+
+/// Report the public address for a port and whether the UDP port can be bound locally.
+/// A true external UDP probe isn't possible without a relay, so this is a best-effort self-test.
+#[tauri::command]
+pub async fn check_server_reachable(port: u16) -> ReachabilityResult {
+    let (public_ipv4, public_ipv6) = lookup_public_ips().await;
+    let local_bind_ok = is_udp_port_available("0.0.0.0", port);
+
+    let address = public_ipv4
+        .as_ref()
+        .map(|ip| format!("{}:{}", ip, port))
+        .or_else(|| public_ipv6.as_ref().map(|ip| format!("[{}]:{}", ip, port)));
+
+    ReachabilityResult {
+        error: if address.is_none() {
+            Some("Could not determine public IP address".to_string())
+        } else {
+            None
+        },
+        public_ipv4,
+        public_ipv6,
+        port,
+        address,
+        local_bind_ok,
+    }
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Check whether a UDP port can be bound (Hytale uses QUIC over UDP)
+pub fn is_udp_port_available(host: &str, port: u16) -> bool {
+    match std::net::UdpSocket::bind((host, port)) {
+        Ok(_) => true,
+        Err(e) => e.kind() != std::io::ErrorKind::AddrInUse,
+    }
+}
+
+/// Look up public IPv4 and IPv6 addresses concurrently
+async fn lookup_public_ips() -> (Option<String>, Option<String>) {
+    let client = match reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(5))
+        .build()
+    {
+        Ok(c) => c,
+        Err(e) => {
+            println!("[network] Failed to create HTTP client: {}", e);
+            return (None, None);
+        }
+    };
+
+    let (ipv4, ipv6) = futures_util::future::join(
+        fetch_ip(&client, PUBLIC_IPV4_URL),
+        fetch_ip(&client, PUBLIC_IPV6_URL),
+    )
+    .await;
+
+    (
+        ipv4.filter(|ip| ip.parse::<std::net::Ipv4Addr>().is_ok()),
+        ipv6.filter(|ip| ip.parse::<std::net::Ipv6Addr>().is_ok()),
+    )
+}
+
+/// Fetch a plain-text IP address from a lookup service
+async fn fetch_ip(client: &reqwest::Client, url: &str) -> Option<String> {
+    let response = client.get(url).send().await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let text = response.text().await.ok()?;
+    let ip = text.trim().to_string();
+    if ip.is_empty() {
+        None
+    } else {
+        Some(ip)
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::path::{Path, PathBuf};
+
+    const NAME: &str = "HyPanel - Survival";
+
+    /// Stand-in `ufw` keeping its rules in a file next to it
+    const FAKE_UFW: &str = r#"#!/bin/sh
+db="$(dirname "$0")/rules"
+touch "$db"
+case "$1" in
+  status) printf 'Status: active\n\nTo                         Action      From\n'; cat "$db" ;;
+  allow) grep -q "^$2 " "$db" || printf '%-26s ALLOW       Anywhere                   # %s\n' "$2" "$4" >> "$db" ;;
+  delete) grep -v "^$3 " "$db" > "$db.new"; mv "$db.new" "$db" ;;
+esac
+"#;
+
+    /// Stand-in `iptables` keeping `-S INPUT` style lines in a file next to it
+    const FAKE_IPTABLES: &str = r#"#!/bin/sh
+db="$(dirname "$0")/rules"
+touch "$db"
+op="$1"
+[ "$op" = "-S" ] && { cat "$db"; exit 0; }
+shift; shift
+line="-A INPUT -p $2 -m $2 --dport $4 -m comment --comment \"${10}\" -j ACCEPT"
+case "$op" in
+  -C) grep -qxF -- "$line" "$db" ;;
+  -A) echo "$line" >> "$db" ;;
+  -D) grep -qxF -- "$line" "$db" || exit 1; grep -vxF -- "$line" "$db" > "$db.new"; mv "$db.new" "$db" ;;
+esac
+"#;
+
+    /// Empty scratch directory holding the fake firewall tool and its rules file
+    fn fake_firewall(name: &str, tool: &str, script: &str, rules: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("hypanel-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let tool_path = dir.join(tool);
+        std::fs::write(&tool_path, script).unwrap();
+        std::fs::set_permissions(&tool_path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        std::fs::write(dir.join("rules"), rules).unwrap();
+        dir
+    }
+
+    /// Run a rules script (without pkexec) against the fake tool in `dir`
+    fn run_script(dir: &Path, script: &str) -> String {
+        let path = format!("{}:{}", dir.display(), std::env::var("PATH").unwrap_or_default());
+        let output = Command::new("sh").args(["-c", script]).env("PATH", path).output().unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8_lossy(&output.stdout).to_string()
+    }
+
+    fn rules(entries: &[(u16, &str)]) -> Vec<(u16, String)> {
+        entries.iter().map(|(port, proto)| (*port, proto.to_string())).collect()
+    }
+
+    #[test]
+    fn parses_ufw_rules_by_exact_comment() {
+        let listing = "Status: active\n\n\
+            To                         Action      From\n\
+            --                         ------      ----\n\
+            5520/udp                   ALLOW       Anywhere                   # HyPanel - Survival\n\
+            5520/tcp                   ALLOW       Anywhere                   # HyPanel - Survival\n\
+            5521/udp                   ALLOW       Anywhere                   # HyPanel - Survival 2\n\
+            22/tcp                     ALLOW       Anywhere\n\
+            5520/udp (v6)              ALLOW       Anywhere (v6)              # HyPanel - Survival\n";
+
+        assert_eq!(parse_linux_named_rules("ufw", listing, NAME), rules(&[(5520, "tcp"), (5520, "udp")]));
+        assert_eq!(parse_linux_named_rules("ufw", listing, "HyPanel - Survival 2"), rules(&[(5521, "udp")]));
+        assert!(parse_linux_named_rules("ufw", listing, "HyPanel - Creative").is_empty());
+    }
+
+    #[test]
+    fn parses_iptables_rules_by_exact_comment() {
+        let listing = "-P INPUT ACCEPT\n\
+            -A INPUT -p udp -m udp --dport 5520 -m comment --comment \"HyPanel - Survival\" -j ACCEPT\n\
+            -A INPUT -p tcp -m tcp --dport 5520 -m comment --comment \"HyPanel - Survival 2\" -j ACCEPT\n\
+            -A INPUT -p tcp -m tcp --dport 22 -j ACCEPT\n";
+
+        assert_eq!(parse_linux_named_rules("iptables", listing, NAME), rules(&[(5520, "udp")]));
+        assert_eq!(
+            parse_linux_named_rules("iptables", listing, "HyPanel - Survival 2"),
+            rules(&[(5520, "tcp")])
+        );
+    }
+
+    #[test]
+    fn has_linux_rules_needs_every_protocol() {
+        let open = rules(&[(5520, "udp")]);
+        assert!(has_linux_rules(&open, 5520, FirewallProtocol::Udp));
+        assert!(!has_linux_rules(&open, 5520, FirewallProtocol::Both));
+        assert!(!has_linux_rules(&open, 5521, FirewallProtocol::Udp));
+    }
+
+    #[test]
+    fn ufw_script_replaces_stale_ports_and_keeps_other_rules() {
+        let dir = fake_firewall(
+            "ufw-script",
+            "ufw",
+            FAKE_UFW,
+            "5519/udp                   ALLOW       Anywhere                   # HyPanel - Survival\n\
+             6000/udp                   ALLOW       Anywhere                   # HyPanel - Other\n",
+        );
+
+        let script = linux_rules_script("ufw", NAME, Some("[ \"$port\" != 5520 ]"), &[(5520, "udp"), (5520, "tcp")]);
+        let (before, after) = split_rule_listings("ufw", &run_script(&dir, &script), NAME);
+
+        assert_eq!(before, rules(&[(5519, "udp")]));
+        assert_eq!(after, rules(&[(5520, "tcp"), (5520, "udp")]));
+        let remaining = std::fs::read_to_string(dir.join("rules")).unwrap();
+        assert_eq!(parse_linux_named_rules("ufw", &remaining, "HyPanel - Other"), rules(&[(6000, "udp")]));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn iptables_script_adds_once_and_removes_by_comment() {
+        let dir = fake_firewall(
+            "iptables-script",
+            "iptables",
+            FAKE_IPTABLES,
+            "-A INPUT -p udp -m udp --dport 5520 -m comment --comment \"HyPanel - Other\" -j ACCEPT\n",
+        );
+
+        // Adding twice leaves a single rule
+        let add = linux_rules_script("iptables", NAME, None, &[(5520, "udp")]);
+        run_script(&dir, &add);
+        let (before, after) = split_rule_listings("iptables", &run_script(&dir, &add), NAME);
+        assert_eq!(before, rules(&[(5520, "udp")]));
+        assert_eq!(after, rules(&[(5520, "udp")]));
+
+        // Removing only touches this rule's entries, not another rule on the same port
+        let condition = linux_rule_condition(5520, FirewallProtocol::Udp);
+        let remove = linux_rules_script("iptables", NAME, Some(&condition), &[]);
+        let (before, after) = split_rule_listings("iptables", &run_script(&dir, &remove), NAME);
+        assert_eq!(before, rules(&[(5520, "udp")]));
+        assert!(after.is_empty());
+        let remaining = std::fs::read_to_string(dir.join("rules")).unwrap();
+        assert_eq!(parse_linux_named_rules("iptables", &remaining, "HyPanel - Other"), rules(&[(5520, "udp")]));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
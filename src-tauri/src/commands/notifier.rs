@@ -0,0 +1,234 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::database::{self, DbPool};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Server events that can trigger a webhook notification
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WebhookEvent {
+    ServerStarted,
+    ServerStopped,
+    ServerCrashed,
+    PlayerJoined,
+    UpdateAvailable,
+}
+
+impl WebhookEvent {
+    /// Settings key that toggles notifications for this event
+    fn setting_key(self) -> &'static str {
+        match self {
+            WebhookEvent::ServerStarted => "webhook_notify_server_started",
+            WebhookEvent::ServerStopped => "webhook_notify_server_stopped",
+            WebhookEvent::ServerCrashed => "webhook_notify_server_crashed",
+            WebhookEvent::PlayerJoined => "webhook_notify_player_joined",
+            WebhookEvent::UpdateAvailable => "webhook_notify_update_available",
+        }
+    }
+
+    /// Whether this event notifies when the user hasn't chosen yet
+    fn enabled_by_default(self) -> bool {
+        // Player joins are noisy on busy servers, so they are opt-in
+        !matches!(self, WebhookEvent::PlayerJoined)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookSettings {
+    pub webhook_url: Option<String>,
+    pub notify_server_started: bool,
+    pub notify_server_stopped: bool,
+    pub notify_server_crashed: bool,
+    pub notify_player_joined: bool,
+    pub notify_update_available: bool,
+}
+
+impl Default for WebhookSettings {
+    fn default() -> Self {
+        Self {
+            webhook_url: None,
+            notify_server_started: WebhookEvent::ServerStarted.enabled_by_default(),
+            notify_server_stopped: WebhookEvent::ServerStopped.enabled_by_default(),
+            notify_server_crashed: WebhookEvent::ServerCrashed.enabled_by_default(),
+            notify_player_joined: WebhookEvent::PlayerJoined.enabled_by_default(),
+            notify_update_available: WebhookEvent::UpdateAvailable.enabled_by_default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookTestResult {
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// Name shown as the message author in Discord
+const WEBHOOK_USERNAME: &str = "HyPanel";
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Get webhook notification settings
+#[tauri::command]
+pub async fn get_webhook_settings(app: AppHandle) -> WebhookSettings {
+    let pool = match app.try_state::<DbPool>() {
+        Some(p) => p.inner().clone(),
+        None => return WebhookSettings::default(),
+    };
+
+    let webhook_url = database::get_setting(&pool, "webhook_url")
+        .await
+        .ok()
+        .flatten()
+        .filter(|url| !url.trim().is_empty());
+
+    WebhookSettings {
+        webhook_url,
+        notify_server_started: is_event_enabled(&pool, WebhookEvent::ServerStarted).await,
+        notify_server_stopped: is_event_enabled(&pool, WebhookEvent::ServerStopped).await,
+        notify_server_crashed: is_event_enabled(&pool, WebhookEvent::ServerCrashed).await,
+        notify_player_joined: is_event_enabled(&pool, WebhookEvent::PlayerJoined).await,
+        notify_update_available: is_event_enabled(&pool, WebhookEvent::UpdateAvailable).await,
+    }
+}
+
+/// Set webhook notification settings
+#[tauri::command]
+pub async fn set_webhook_settings(app: AppHandle, settings: WebhookSettings) -> bool {
+    let pool = match app.try_state::<DbPool>() {
+        Some(p) => p.inner().clone(),
+        None => return false,
+    };
+
+    let url = settings.webhook_url.unwrap_or_default();
+    let mut ok = database::set_setting(&pool, "webhook_url", url.trim()).await.is_ok();
+
+    let toggles = [
+        (WebhookEvent::ServerStarted, settings.notify_server_started),
+        (WebhookEvent::ServerStopped, settings.notify_server_stopped),
+        (WebhookEvent::ServerCrashed, settings.notify_server_crashed),
+        (WebhookEvent::PlayerJoined, settings.notify_player_joined),
+        (WebhookEvent::UpdateAvailable, settings.notify_update_available),
+    ];
+
+    for (event, enabled) in toggles {
+        ok &= database::set_setting(&pool, event.setting_key(), if enabled { "true" } else { "false" })
+            .await
+            .is_ok();
+    }
+
+    ok
+}
+
+/// Send a test message to a webhook URL
+#[tauri::command]
+pub async fn test_webhook(webhook_url: String) -> WebhookTestResult {
+    match post_webhook(&webhook_url, "HyPanel webhook test: notifications are working.").await {
+        Ok(()) => WebhookTestResult {
+            success: true,
+            error: None,
+        },
+        Err(e) => WebhookTestResult {
+            success: false,
+            error: Some(e),
+        },
+    }
+}
+
+// ============================================================================
+// Notification dispatch
+// ============================================================================
+
+/// Send a notification in the background if a webhook is configured and the event is enabled.
+/// Delivery failures are logged and otherwise ignored.
+pub fn notify(app: &AppHandle, event: WebhookEvent, message: String) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let pool = match app.try_state::<DbPool>() {
+            Some(p) => p.inner().clone(),
+            None => return,
+        };
+
+        send_if_enabled(&pool, event, &message).await;
+    });
+}
+
+/// Like `notify`, prefixing the message with the instance name
+pub fn notify_instance(app: &AppHandle, event: WebhookEvent, instance_id: &str, message: String) {
+    let app = app.clone();
+    let instance_id = instance_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        let pool = match app.try_state::<DbPool>() {
+            Some(p) => p.inner().clone(),
+            None => return,
+        };
+
+        let name = match database::get_instance_by_id(&pool, &instance_id).await {
+            Ok(Some(instance)) => instance.name,
+            _ => instance_id,
+        };
+
+        send_if_enabled(&pool, event, &format!("**{}**: {}", name, message)).await;
+    });
+}
+
+async fn send_if_enabled(pool: &DbPool, event: WebhookEvent, message: &str) {
+    let url = match database::get_setting(pool, "webhook_url").await {
+        Ok(Some(url)) if !url.trim().is_empty() => url,
+        _ => return,
+    };
+
+    if !is_event_enabled(pool, event).await {
+        return;
+    }
+
+    if let Err(e) = post_webhook(&url, message).await {
+        println!("[notifier] Failed to deliver {:?} notification: {}", event, e);
+    }
+}
+
+async fn is_event_enabled(pool: &DbPool, event: WebhookEvent) -> bool {
+    database::get_setting(pool, event.setting_key())
+        .await
+        .ok()
+        .flatten()
+        .map(|v| v == "true")
+        .unwrap_or(event.enabled_by_default())
+}
+
+/// POST a plain message to a Discord-compatible webhook
+async fn post_webhook(url: &str, message: &str) -> Result<(), String> {
+    let url = url.trim();
+    if !url.starts_with("https://") && !url.starts_with("http://") {
+        return Err("Webhook URL must start with http:// or https://".to_string());
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let body = serde_json::json!({
+        "username": WEBHOOK_USERNAME,
+        "content": message,
+    });
+
+    let response = client
+        .post(url)
+        .header("Content-Type", "application/json")
+        .body(body.to_string())
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Webhook returned HTTP {}", response.status()));
+    }
+
+    Ok(())
+}
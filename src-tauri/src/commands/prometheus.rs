@@ -0,0 +1,303 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, State};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use super::metrics::{collect_all_server_metrics, collect_system_metrics, MetricsState};
+use super::server::ServerState;
+use crate::database::{self, DbPool};
+
+/// Default port for the Prometheus exporter
+const DEFAULT_EXPORTER_PORT: u16 = 9520;
+
+/// Largest request we bother reading; scrapes are a single small GET
+const MAX_REQUEST_BYTES: usize = 8192;
+
+/// Pause after a failed accept before trying again
+const ACCEPT_ERROR_BACKOFF: std::time::Duration = std::time::Duration::from_millis(100);
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Start an HTTP server exposing metrics in Prometheus text format at /metrics.
+/// Binds to localhost unless `bind_all` is set. Returns the bound address.
+#[tauri::command]
+pub async fn start_metrics_server(
+    app: AppHandle,
+    metrics_state: State<'_, Arc<Mutex<MetricsState>>>,
+    port: Option<u16>,
+    bind_all: Option<bool>,
+) -> Result<String, String> {
+    let pool = app.try_state::<DbPool>().map(|p| p.inner().clone());
+
+    let port = match port {
+        Some(p) => p,
+        None => match pool {
+            Some(ref pool) => database::get_setting(pool, "metrics_server_port")
+                .await
+                .ok()
+                .flatten()
+                .and_then(|v| v.parse::<u16>().ok())
+                .unwrap_or(DEFAULT_EXPORTER_PORT),
+            None => DEFAULT_EXPORTER_PORT,
+        },
+    };
+
+    let bind_all = match bind_all {
+        Some(b) => b,
+        None => match pool {
+            Some(ref pool) => database::get_setting(pool, "metrics_server_bind_all")
+                .await
+                .ok()
+                .flatten()
+                .map(|v| v == "true")
+                .unwrap_or(false),
+            None => false,
+        },
+    };
+
+    let host = if bind_all { "0.0.0.0" } else { "127.0.0.1" };
+    let address = format!("{}:{}", host, port);
+
+    // Free the port first if we're restarting on the same address
+    let previous = metrics_state.lock().unwrap().exporter_task.take();
+    if let Some(previous) = previous {
+        previous.abort();
+    }
+
+    let listener = TcpListener::bind(&address)
+        .await
+        .map_err(|e| format!("Failed to bind {}: {}", address, e))?;
+
+    if let Some(ref pool) = pool {
+        let r1 = database::set_setting(pool, "metrics_server_port", &port.to_string()).await;
+        let r2 = database::set_setting(
+            pool,
+            "metrics_server_bind_all",
+            if bind_all { "true" } else { "false" },
+        )
+        .await;
+        if r1.is_err() || r2.is_err() {
+            println!("[prometheus] WARNING: Failed to save exporter settings");
+        }
+    }
+
+    let server_state = app.state::<Arc<Mutex<ServerState>>>().inner().clone();
+    let metrics_arc = metrics_state.inner().clone();
+
+    let task = tauri::async_runtime::spawn(async move {
+        println!("[prometheus] Serving metrics on http://{}/metrics", address);
+        loop {
+            let (stream, _) = match listener.accept().await {
+                Ok(conn) => conn,
+                Err(e) => {
+                    // Back off so a persistent error (e.g. out of file descriptors) can't spin
+                    println!("[prometheus] Accept error: {}", e);
+                    tokio::time::sleep(ACCEPT_ERROR_BACKOFF).await;
+                    continue;
+                }
+            };
+
+            let server_state = server_state.clone();
+            let metrics_arc = metrics_arc.clone();
+            let pool = pool.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = handle_connection(stream, &server_state, &metrics_arc, pool.as_ref()).await {
+                    println!("[prometheus] Connection error: {}", e);
+                }
+            });
+        }
+    });
+
+    metrics_state.lock().unwrap().exporter_task = Some(task);
+
+    Ok(format!("{}:{}", host, port))
+}
+
+/// Stop the Prometheus metrics server
+#[tauri::command]
+pub fn stop_metrics_server(metrics_state: State<'_, Arc<Mutex<MetricsState>>>) -> bool {
+    let mut metrics = metrics_state.lock().unwrap();
+    match metrics.exporter_task.take() {
+        Some(task) => {
+            task.abort();
+            println!("[prometheus] Metrics server stopped");
+            true
+        }
+        None => false,
+    }
+}
+
+// ============================================================================
+// HTTP handling
+// ============================================================================
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    server_state: &Arc<Mutex<ServerState>>,
+    metrics_state: &Arc<Mutex<MetricsState>>,
+    pool: Option<&DbPool>,
+) -> std::io::Result<()> {
+    // Read until the end of the request headers
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    while request.len() < MAX_REQUEST_BYTES {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        request.extend_from_slice(&buf[..n]);
+        if request.windows(4).any(|w| w == b"\r\n\r\n") {
+            break;
+        }
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let mut parts = request.lines().next().unwrap_or("").split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let path = parts.next().unwrap_or("");
+
+    let (status, content_type, body) = if method != "GET" {
+        ("405 Method Not Allowed", "text/plain", "Method Not Allowed\n".to_string())
+    } else if path == "/metrics" || path.starts_with("/metrics?") {
+        let names = match pool {
+            Some(pool) => instance_names(pool).await,
+            None => HashMap::new(),
+        };
+        // The sysinfo refresh behind the metrics is synchronous
+        let (server_state, metrics_state) = (server_state.clone(), metrics_state.clone());
+        let body = tokio::task::spawn_blocking(move || render_metrics(&server_state, &metrics_state, &names))
+            .await
+            .map_err(std::io::Error::other)?;
+        ("200 OK", "text/plain; version=0.0.4; charset=utf-8", body)
+    } else {
+        ("404 Not Found", "text/plain", "Not Found\n".to_string())
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Map instance IDs to their display names for metric labels
+async fn instance_names(pool: &DbPool) -> HashMap<String, String> {
+    database::get_all_instances(pool)
+        .await
+        .map(|instances| instances.into_iter().map(|i| (i.id, i.name)).collect())
+        .unwrap_or_default()
+}
+
+/// Render the current metrics in Prometheus text exposition format
+fn render_metrics(
+    server_state: &Mutex<ServerState>,
+    metrics_state: &Mutex<MetricsState>,
+    names: &HashMap<String, String>,
+) -> String {
+    let servers = collect_all_server_metrics(server_state, metrics_state);
+    let system = collect_system_metrics(metrics_state);
+
+    let player_counts: HashMap<String, usize> = {
+        let state_guard = server_state.lock().unwrap();
+        state_guard
+            .processes
+            .iter()
+            .map(|(id, process_arc)| {
+                let count = process_arc
+                    .lock()
+                    .map(|p| p.online_players.len())
+                    .unwrap_or(0);
+                (id.clone(), count)
+            })
+            .collect()
+    };
+
+    let mut out = String::new();
+
+    // System metrics
+    write_header(&mut out, "hypanel_system_cpu_usage_percent", "Global CPU usage of the host");
+    let _ = writeln!(out, "hypanel_system_cpu_usage_percent {}", system.cpu_usage);
+    write_header(&mut out, "hypanel_system_cpu_count", "Number of logical CPUs on the host");
+    let _ = writeln!(out, "hypanel_system_cpu_count {}", system.cpu_count);
+    write_header(&mut out, "hypanel_system_memory_total_bytes", "Total host memory");
+    let _ = writeln!(out, "hypanel_system_memory_total_bytes {}", mb_to_bytes(system.total_memory_mb));
+    write_header(&mut out, "hypanel_system_memory_used_bytes", "Used host memory");
+    let _ = writeln!(out, "hypanel_system_memory_used_bytes {}", mb_to_bytes(system.used_memory_mb));
+    write_header(&mut out, "hypanel_system_memory_available_bytes", "Available host memory");
+    let _ = writeln!(out, "hypanel_system_memory_available_bytes {}", mb_to_bytes(system.available_memory_mb));
+
+    // Per-server metrics
+    write_header(&mut out, "hypanel_servers_running", "Number of running server instances");
+    let _ = writeln!(out, "hypanel_servers_running {}", servers.len());
+
+    write_header(&mut out, "hypanel_server_cpu_usage_percent", "CPU usage of the server process");
+    for m in &servers {
+        if let Some(cpu) = m.cpu_usage {
+            let _ = writeln!(out, "hypanel_server_cpu_usage_percent{{{}}} {}", labels(&m.instance_id, names), cpu);
+        }
+    }
+
+    write_header(&mut out, "hypanel_server_memory_bytes", "Resident memory of the server process");
+    for m in &servers {
+        if let Some(mem) = m.memory_mb {
+            let _ = writeln!(out, "hypanel_server_memory_bytes{{{}}} {}", labels(&m.instance_id, names), mb_to_bytes(mem));
+        }
+    }
+
+    write_header(&mut out, "hypanel_server_uptime_seconds", "Seconds since the server process started");
+    for m in &servers {
+        if let Some(uptime) = m.uptime_seconds {
+            let _ = writeln!(out, "hypanel_server_uptime_seconds{{{}}} {}", labels(&m.instance_id, names), uptime);
+        }
+    }
+
+    write_header(&mut out, "hypanel_server_players_online", "Players currently connected");
+    for m in &servers {
+        let count = player_counts.get(&m.instance_id).copied().unwrap_or(0);
+        let _ = writeln!(out, "hypanel_server_players_online{{{}}} {}", labels(&m.instance_id, names), count);
+    }
+
+    write_header(&mut out, "hypanel_server_tps", "Last reported server ticks per second");
+    for m in &servers {
+        if let Some(tps) = m.tps {
+            let _ = writeln!(out, "hypanel_server_tps{{{}}} {}", labels(&m.instance_id, names), tps);
+        }
+    }
+
+    out
+}
+
+fn write_header(out: &mut String, name: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+}
+
+fn labels(instance_id: &str, names: &HashMap<String, String>) -> String {
+    let name = names.get(instance_id).map(String::as_str).unwrap_or(instance_id);
+    format!(
+        "instance_id=\"{}\",instance_name=\"{}\"",
+        escape_label(instance_id),
+        escape_label(name)
+    )
+}
+
+/// Escape a label value per the Prometheus text format
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn mb_to_bytes(mb: f64) -> u64 {
+    (mb * 1024.0 * 1024.0) as u64
+}
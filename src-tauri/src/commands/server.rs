@@ -1,12 +1,25 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::io::{BufRead, BufReader, Write};
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufReader, Write};
 use std::process::{Child, Command, Stdio};
 use std::sync::{Arc, Mutex};
 use std::path::Path;
-use tauri::{AppHandle, Emitter, State};
+use tauri::{AppHandle, Emitter, Manager, State};
 use chrono::{DateTime, Utc};
 
+use super::config::{parse_bind_port, read_config_port, DEFAULT_SERVER_PORT};
+use super::backup::DATABASE_UNAVAILABLE;
+use super::errors::ErrorCode;
+use super::instances::parse_env_vars;
+use super::logs::{extract_log_level, lossy_lines};
+use super::metrics::{
+    check_jvm_args, find_processes_running_jar, is_process_alive, max_heap_mb, memory_snapshot_mb, MetricsState,
+};
+use super::network::is_udp_port_available;
+use super::notifier::{notify_instance, WebhookEvent};
+use super::system::check_java_for_launch;
+use crate::database::{self, DbPool};
+
 // ============================================================================
 // Types
 // ============================================================================
@@ -28,12 +41,28 @@ pub struct ServerStatusInfo {
     pub started_at: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerUptime {
+    pub instance_id: String,
+    pub started_at: String,
+    pub uptime_seconds: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerOutput {
     pub instance_id: String,
     pub line: String,
     pub stream: String, // "stdout" or "stderr"
     pub timestamp: String,
+    pub spans: Option<Vec<OutputSpan>>,  // Styled segments, only when the line has ANSI codes
+}
+
+/// A run of console text sharing the same style
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OutputSpan {
+    pub text: String,
+    pub color: Option<String>,  // Color name like "red"/"bright_blue", or "#rrggbb"
+    pub bold: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,12 +70,33 @@ pub struct StartResult {
     pub success: bool,
     pub pid: Option<u32>,
     pub error: Option<String>,
+    pub error_code: Option<ErrorCode>,
+    pub requires_auth: bool,  // Stored auth status is unknown/unauthenticated, so expect the auth flow
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StopResult {
     pub success: bool,
     pub error: Option<String>,
+    pub timeout_secs: Option<u64>,  // Effective wait between SIGTERM and a forced kill
+}
+
+/// The exact settings a server was launched with, so it can be restarted identically
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaunchConfig {
+    pub instance_path: String,
+    pub working_dir: String,
+    pub java_path: String,  // Resolved executable ("java" when none was configured)
+    pub jvm_args: Option<String>,
+    pub server_args: Option<String>,
+    pub env_vars: HashMap<String, String>,
+    pub launch_wrapper: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthCommandResult {
+    pub success: bool,
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -71,6 +121,13 @@ pub struct AuthSuccessEvent {
     pub auth_mode: String,  // e.g. "OAUTH_DEVICE"
 }
 
+/// Emitted when the server reports that authentication failed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthFailedEvent {
+    pub instance_id: String,
+    pub message: String,
+}
+
 /// Represents an online player
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OnlinePlayer {
@@ -102,6 +159,83 @@ pub struct OnlinePlayersResponse {
     pub count: usize,
 }
 
+/// Event emitted when a new TPS reading is parsed from server output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TpsUpdateEvent {
+    pub instance_id: String,
+    pub tps: f32,
+    pub timestamp: String,
+}
+
+/// Event emitted once the server logs that it is accepting connections
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerReadyEvent {
+    pub instance_id: String,
+    pub startup_ms: i64,  // Time from process spawn to the ready line
+    pub timestamp: String,
+}
+
+/// Event emitted when a server process exits on its own (crash or in-game stop)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerExitEvent {
+    pub instance_id: String,
+    pub code: Option<i32>,
+    pub signal: Option<i32>,     // Unix only: signal that terminated the process
+    pub last_lines: Vec<String>, // Most recent stdout/stderr lines, oldest first
+}
+
+/// A server process exit recorded for crash loop detection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExitRecord {
+    pub exited_at: DateTime<Utc>,
+    pub code: Option<i32>,
+    pub signal: Option<i32>,
+    pub abnormal: bool,  // Anything other than exit code 0, unless requested by stop_server
+}
+
+/// Event emitted when a server keeps crashing shortly after being started
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashLoopEvent {
+    pub instance_id: String,
+    pub crashes: usize,      // Abnormal exits within the window
+    pub window_secs: i64,
+}
+
+/// Event emitted when a server hasn't logged its ready line within the startup timeout
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerStartTimeoutEvent {
+    pub instance_id: String,
+    pub timeout_secs: u64,
+    pub killed: bool,  // The process was killed because of the timeout
+}
+
+/// Event emitted when the heaps of all running servers plus a starting one exceed the memory budget
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerMemoryWarningEvent {
+    pub instance_id: String,
+    pub requested_mb: u64,  // -Xmx of the server being started
+    pub running_mb: u64,    // Sum of -Xmx of servers already running
+    pub limit_mb: u64,      // Physical memory minus MEMORY_GUARD_RESERVE_MB
+    pub blocked: bool,      // The start was refused (memory_guard_block setting)
+}
+
+/// Outcome for one instance of a start-all/stop-all operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkOperationResult {
+    pub instance_id: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// Event emitted as each instance of a bulk operation finishes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkOperationProgress {
+    pub operation: String, // "start" or "stop"
+    pub result: BulkOperationResult,
+    pub completed: usize,
+    pub total: usize,
+}
+
 // ============================================================================
 // Server State Management
 // ============================================================================
@@ -110,23 +244,266 @@ pub struct ServerProcess {
     pub child: Child,
     #[allow(dead_code)]
     pub instance_id: String,
+    pub instance_path: String,
     pub started_at: DateTime<Utc>,
     pub stdin_tx: Option<std::sync::mpsc::Sender<String>>,
     pub online_players: HashMap<String, OnlinePlayer>,  // uuid -> player
+    pub tps: Option<f32>,  // Last TPS reading parsed from stdout
+    pub ready: bool,       // Set once the server logs that it has booted
+    pub stop_requested: bool,  // Set by stop_server so the exit isn't reported as a crash
+    pub recent_lines: Arc<Mutex<VecDeque<String>>>,  // Latest output lines for crash reports
+    pub output_buffer: Arc<Mutex<OutputBuffer>>,  // Console history for reconnecting views
+    pub output_filter: Arc<Mutex<OutputFilter>>,  // Which lines are emitted as server-output
+    pub output_batcher: Arc<Mutex<OutputBatcher>>,  // Per-line or batched server-output events
+    pub output_subscribers: OutputSubscribers,  // Temporary listeners for stdout lines
+    pub io_threads: Vec<std::thread::JoinHandle<()>>,  // stdin/stdout/stderr/monitor threads
+    pub launch_config: LaunchConfig,
+    #[cfg(windows)]
+    pub job: Option<ProcessJob>,  // Job Object holding the server's process tree
+}
+
+/// Windows Job Object holding a server and every process it spawns. It's created with
+/// JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE, so the whole tree dies when the job is terminated
+/// or its last handle is closed, which includes HyPanel itself exiting.
+#[cfg(windows)]
+pub struct ProcessJob(windows_sys::Win32::Foundation::HANDLE);
+
+// The handle is only used through the owning ServerProcess's mutex
+#[cfg(windows)]
+unsafe impl Send for ProcessJob {}
+
+#[cfg(windows)]
+impl ProcessJob {
+    /// Create a kill-on-close job and move `child` into it. Processes the child
+    /// spawns from then on join the job automatically.
+    fn assign(child: &Child) -> Result<Self, String> {
+        use std::os::windows::io::AsRawHandle;
+        use windows_sys::Win32::System::JobObjects::{
+            AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+            SetInformationJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+        };
+
+        unsafe {
+            let handle = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+            if handle.is_null() {
+                return Err(format!("Failed to create job object: {}", std::io::Error::last_os_error()));
+            }
+            // Closes the handle if a later step fails
+            let job = Self(handle);
+
+            let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+            info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+            let configured = SetInformationJobObject(
+                job.0,
+                JobObjectExtendedLimitInformation,
+                &info as *const JOBOBJECT_EXTENDED_LIMIT_INFORMATION as *const std::ffi::c_void,
+                std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+            );
+            if configured == 0 {
+                return Err(format!("Failed to configure job object: {}", std::io::Error::last_os_error()));
+            }
+
+            if AssignProcessToJobObject(job.0, child.as_raw_handle() as windows_sys::Win32::Foundation::HANDLE) == 0 {
+                return Err(format!("Failed to assign server to job object: {}", std::io::Error::last_os_error()));
+            }
+
+            Ok(job)
+        }
+    }
+
+    /// Kill every process in the job. Returns false if the call failed.
+    fn terminate(&self) -> bool {
+        unsafe { windows_sys::Win32::System::JobObjects::TerminateJobObject(self.0, 1) != 0 }
+    }
+}
+
+#[cfg(windows)]
+impl Drop for ProcessJob {
+    fn drop(&mut self) {
+        unsafe {
+            windows_sys::Win32::Foundation::CloseHandle(self.0);
+        }
+    }
+}
+
+/// A server process started by a previous app session and re-adopted on launch.
+/// Its stdin/stdout pipes are gone, so it can only be monitored and stopped by PID.
+#[derive(Debug, Clone)]
+pub struct AdoptedProcess {
+    pub pid: u32,
+    pub instance_path: String,
+    pub started_at: DateTime<Utc>,
+}
+
+/// Channels that receive every stdout line (ANSI codes stripped) while registered.
+/// Senders whose receiver has gone away are dropped on the next line.
+pub type OutputSubscribers = Arc<Mutex<Vec<std::sync::mpsc::Sender<String>>>>;
+
+/// Number of output lines kept per server for exit reports
+const RECENT_LINES_CAPACITY: usize = 50;
+
+/// Append a line to a recent-output ring buffer, dropping the oldest when full
+fn push_recent_line(buffer: &Mutex<VecDeque<String>>, line: String) {
+    if let Ok(mut lines) = buffer.lock() {
+        if lines.len() >= RECENT_LINES_CAPACITY {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+}
+
+/// Default number of console lines kept per server for `get_recent_output`
+const DEFAULT_OUTPUT_BUFFER_LINES: usize = 1000;
+
+/// Upper bound for the configurable console history size
+const MAX_OUTPUT_BUFFER_LINES: usize = 10_000;
+
+/// Bounded console history, oldest lines dropped first
+pub struct OutputBuffer {
+    lines: VecDeque<ServerOutput>,
+    capacity: usize,
+}
+
+impl OutputBuffer {
+    fn new(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::with_capacity(capacity.min(DEFAULT_OUTPUT_BUFFER_LINES)),
+            capacity,
+        }
+    }
+
+    fn push(&mut self, output: ServerOutput) {
+        if self.lines.len() >= self.capacity {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(output);
+    }
+}
+
+/// Log levels from least to most severe, as returned by `extract_log_level`
+const LOG_LEVELS: [&str; 5] = ["TRACE", "DEBUG", "INFO", "WARN", "ERROR"];
+
+/// Server-side filter for `server-output` events. Filtered lines are still buffered
+/// and parsed; they just aren't sent to the frontend. The default passes everything.
+#[derive(Debug, Clone, Default)]
+pub struct OutputFilter {
+    min_level: Option<usize>,       // Index into LOG_LEVELS
+    streams: Option<Vec<String>>,   // "stdout"/"stderr"; None means both
+}
+
+impl OutputFilter {
+    /// Lines without a recognizable level (e.g. stack trace continuations) always pass the level check
+    fn allows(&self, stream: &str, line: &str) -> bool {
+        if let Some(ref streams) = self.streams {
+            if !streams.iter().any(|s| s == stream) {
+                return false;
+            }
+        }
+
+        match (self.min_level, extract_log_level(line)) {
+            (Some(min), Some(level)) => LOG_LEVELS
+                .iter()
+                .position(|l| *l == level)
+                .map(|rank| rank >= min)
+                .unwrap_or(true),
+            _ => true,
+        }
+    }
+}
+
+/// Default window for batched `server-output-batch` events
+const DEFAULT_OUTPUT_BATCH_MS: u64 = 50;
+
+/// Allowed range for the batching window
+const MIN_OUTPUT_BATCH_MS: u64 = 10;
+const MAX_OUTPUT_BATCH_MS: u64 = 1000;
+
+/// How often the flush thread checks for due batches while batching is off
+const OUTPUT_BATCH_IDLE_TICK: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// Sends console lines to the frontend, either one `server-output` event per line
+/// (the default) or as `server-output-batch` events holding every line from a window.
+#[derive(Debug, Default)]
+pub struct OutputBatcher {
+    window: Option<std::time::Duration>,  // None = per-line events
+    pending: Vec<ServerOutput>,
+    oldest: Option<std::time::Instant>,  // When the first pending line was queued
+}
+
+impl OutputBatcher {
+    /// Emit a line now, or queue it while batching; a batch is sent once its window has passed
+    fn emit(&mut self, app: &AppHandle, output: &ServerOutput) {
+        if self.window.is_none() {
+            let _ = app.emit("server-output", output);
+            return;
+        }
+
+        self.pending.push(output.clone());
+        self.oldest.get_or_insert_with(std::time::Instant::now);
+        self.flush_due(app);
+    }
+
+    /// Send the pending lines if the oldest has waited a full window
+    fn flush_due(&mut self, app: &AppHandle) {
+        if let (Some(window), Some(oldest)) = (self.window, self.oldest) {
+            if oldest.elapsed() >= window {
+                self.flush(app);
+            }
+        }
+    }
+
+    /// Send all pending lines as one `server-output-batch` event
+    fn flush(&mut self, app: &AppHandle) {
+        self.oldest = None;
+        if !self.pending.is_empty() {
+            let batch = std::mem::take(&mut self.pending);
+            let _ = app.emit("server-output-batch", &batch);
+        }
+    }
 }
 
 pub struct ServerState {
     pub processes: HashMap<String, Arc<Mutex<ServerProcess>>>,
+    pub exit_history: HashMap<String, VecDeque<ExitRecord>>,  // instance_id -> recent exits, oldest first
+    pub adopted: HashMap<String, AdoptedProcess>,  // instance_id -> server left running by a previous session
 }
 
 impl ServerState {
     pub fn new() -> Self {
         Self {
             processes: HashMap::new(),
+            exit_history: HashMap::new(),
+            adopted: HashMap::new(),
         }
     }
 }
 
+/// Exits remembered per instance
+const EXIT_HISTORY_CAPACITY: usize = 20;
+
+/// Abnormal exits within CRASH_LOOP_WINDOW_SECS that count as a crash loop
+const CRASH_LOOP_THRESHOLD: usize = 3;
+
+/// Window for crash loop detection
+const CRASH_LOOP_WINDOW_SECS: i64 = 600;
+
+impl ServerState {
+    /// Check if an instance has a running server, including an adopted one
+    pub fn is_running(&self, instance_id: &str) -> bool {
+        self.processes.contains_key(instance_id) || self.adopted.contains_key(instance_id)
+    }
+
+    /// Check if a path lives inside the directory of a running instance
+    pub fn is_path_in_running_instance(&self, path: &Path) -> bool {
+        self.processes.values().any(|process_arc| {
+            process_arc
+                .lock()
+                .map(|process| path.starts_with(&process.instance_path))
+                .unwrap_or(false)
+        }) || self.adopted.values().any(|adopted| path.starts_with(&adopted.instance_path))
+    }
+}
+
 impl Default for ServerState {
     fn default() -> Self {
         Self::new()
@@ -139,6 +516,7 @@ impl Default for ServerState {
 
 /// Start a server instance
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn start_server(
     app: AppHandle,
     state: State<'_, Arc<Mutex<ServerState>>>,
@@ -147,17 +525,23 @@ pub async fn start_server(
     java_path: Option<String>,
     jvm_args: Option<String>,
     server_args: Option<String>,
+    env_vars: Option<HashMap<String, String>>,
+    skip_port_check: Option<bool>,
+    startup_timeout_secs: Option<u64>,
+    kill_on_startup_timeout: Option<bool>,
 ) -> Result<StartResult, ()> {
     println!("[start_server] Starting instance: {}", instance_id);
 
     // Check if already running
     {
         let state_guard = state.lock().unwrap();
-        if state_guard.processes.contains_key(&instance_id) {
+        if state_guard.is_running(&instance_id) {
             return Ok(StartResult {
                 success: false,
                 pid: None,
                 error: Some("Server is already running".to_string()),
+                error_code: Some(ErrorCode::AlreadyRunning),
+                requires_auth: false,
             });
         }
     }
@@ -189,6 +573,8 @@ pub async fn start_server(
             success: false,
             pid: None,
             error: Some(format!("Server JAR not found: {:?}", server_jar)),
+            error_code: Some(ErrorCode::ServerFilesMissing),
+            requires_auth: false,
         });
     }
 
@@ -204,11 +590,178 @@ pub async fn start_server(
             success: false,
             pid: None,
             error: Some(format!("Assets.zip not found: {:?}", assets_path)),
+            error_code: Some(ErrorCode::ServerFilesMissing),
+            requires_auth: false,
+        });
+    }
+
+    // Catch a server for this instance started outside the app or left over from a crash
+    if let Some(metrics_state) = app.try_state::<Arc<Mutex<MetricsState>>>() {
+        let metrics_state = metrics_state.inner().clone();
+        let jar = server_jar.clone();
+        let pids = tokio::task::spawn_blocking(move || find_processes_running_jar(&metrics_state, &jar))
+            .await
+            .unwrap_or_default();
+        if let Some(pid) = pids.first() {
+            println!("[start_server] Found a server already running from {:?} (PID {})", server_jar, pid);
+            let _ = app.emit("server-status-change", ServerStatusInfo {
+                status: ServerStatus::Stopped,
+                instance_id: instance_id.clone(),
+                pid: None,
+                started_at: None,
+            });
+            return Ok(StartResult {
+                success: false,
+                pid: None,
+                error: Some(format!(
+                    "A server appears to already be running for this instance (PID {})",
+                    pid
+                )),
+                error_code: Some(ErrorCode::AlreadyRunning),
+                requires_auth: false,
+            });
+        }
+    }
+
+    // Catch an old or missing Java before it turns into a classfile error
+    let java_check = {
+        let java_exe = java_exe.clone();
+        tokio::task::spawn_blocking(move || check_java_for_launch(&java_exe))
+            .await
+            .unwrap_or_else(|e| Err(format!("Failed to check Java: {}", e)))
+    };
+    if let Err(e) = java_check {
+        println!("[start_server] {}", e);
+        let _ = app.emit("server-status-change", ServerStatusInfo {
+            status: ServerStatus::Stopped,
+            instance_id: instance_id.clone(),
+            pid: None,
+            started_at: None,
+        });
+        return Ok(StartResult {
+            success: false,
+            pid: None,
+            error: Some(e),
+            error_code: Some(ErrorCode::JavaUnavailable),
+            requires_auth: false,
         });
     }
 
-    // Build command arguments
-    let mut cmd = Command::new(&java_exe);
+    // Refuse to launch with JVM args that are certain to fail
+    if let Some(ref jvm) = jvm_args {
+        if let Some(metrics_state) = app.try_state::<Arc<Mutex<MetricsState>>>() {
+            let (total_mb, available_mb) = memory_snapshot_mb(&metrics_state);
+            let validation = check_jvm_args(jvm, total_mb, available_mb);
+            if !validation.valid {
+                println!("[start_server] Invalid JVM args: {:?}", validation.errors);
+                let _ = app.emit("server-status-change", ServerStatusInfo {
+                    status: ServerStatus::Stopped,
+                    instance_id: instance_id.clone(),
+                    pid: None,
+                    started_at: None,
+                });
+                return Ok(StartResult {
+                    success: false,
+                    pid: None,
+                    error: Some(format!("Invalid JVM arguments: {}", validation.errors.join("; "))),
+                    error_code: Some(ErrorCode::InvalidJvmArgs),
+                    requires_auth: false,
+                });
+            }
+        }
+    }
+
+    // Don't let the combined heaps of all running servers outgrow the machine
+    if let Some(warning) = check_memory_budget(&app, &state, &instance_id, jvm_args.as_deref()) {
+        let block = match app.try_state::<DbPool>() {
+            Some(pool) => get_memory_guard_block_setting(pool.inner()).await,
+            None => false,
+        };
+        let warning = ServerMemoryWarningEvent { blocked: block, ..warning };
+        let message = format!(
+            "Starting needs {} MB of heap on top of {} MB for running servers, over the {} MB budget",
+            warning.requested_mb, warning.running_mb, warning.limit_mb
+        );
+        println!("[start_server] {}{}", if block { "" } else { "WARNING: " }, message);
+        let _ = app.emit("server-memory-warning", warning);
+
+        if block {
+            let _ = app.emit("server-status-change", ServerStatusInfo {
+                status: ServerStatus::Stopped,
+                instance_id: instance_id.clone(),
+                pid: None,
+                started_at: None,
+            });
+            return Ok(StartResult {
+                success: false,
+                pid: None,
+                error: Some(message),
+                error_code: Some(ErrorCode::InsufficientMemory),
+                requires_auth: false,
+            });
+        }
+    }
+
+    // Make sure the configured port is free (can be skipped for intentional port reuse)
+    if !skip_port_check.unwrap_or(false) {
+        let (bind_host, port) = resolve_bind_address(&instance_path, server_args.as_deref());
+        if !is_udp_port_available(&bind_host, port) {
+            println!("[start_server] Port {} is already in use", port);
+            let _ = app.emit("server-status-change", ServerStatusInfo {
+                status: ServerStatus::Stopped,
+                instance_id: instance_id.clone(),
+                pid: None,
+                started_at: None,
+            });
+            return Ok(StartResult {
+                success: false,
+                pid: None,
+                error: Some(format!("Port {} is already in use", port)),
+                error_code: Some(ErrorCode::PortInUse),
+                requires_auth: false,
+            });
+        }
+    }
+
+    let stored_instance = match app.try_state::<DbPool>() {
+        Some(pool) => database::get_instance_by_id(&pool, &instance_id).await.ok().flatten(),
+        None => None,
+    };
+
+    // A server that has never logged in will stop at the auth prompt right after booting
+    let requires_auth = stored_instance
+        .as_ref()
+        .map(|i| matches!(i.auth_status.as_deref(), None | Some("unknown") | Some("unauthenticated")))
+        .unwrap_or(false);
+
+    // Resolve per-instance environment variables
+    // (fall back to the stored values when the caller doesn't pass any)
+    let env_vars = env_vars
+        .unwrap_or_else(|| parse_env_vars(stored_instance.as_ref().and_then(|i| i.env_vars.as_deref())));
+
+    // Build command arguments, through the instance's launch wrapper if it has one
+    let launch_wrapper = stored_instance
+        .and_then(|i| i.launch_wrapper)
+        .filter(|w| !w.trim().is_empty());
+    let mut cmd = match launch_command(&java_exe, launch_wrapper.as_deref()) {
+        Ok(cmd) => cmd,
+        Err(e) => {
+            println!("[start_server] Invalid launch wrapper: {}", e);
+            let _ = app.emit("server-status-change", ServerStatusInfo {
+                status: ServerStatus::Stopped,
+                instance_id: instance_id.clone(),
+                pid: None,
+                started_at: None,
+            });
+            return Ok(StartResult {
+                success: false,
+                pid: None,
+                error: Some(format!("Invalid launch wrapper: {}", e)),
+                error_code: Some(ErrorCode::InvalidConfig),
+                requires_auth: false,
+            });
+        }
+    };
 
     // Add JVM arguments if provided
     if let Some(ref jvm) = jvm_args {
@@ -242,11 +795,31 @@ pub async fn start_server(
     let server_folder = server_dir.join("Server");
     cmd.current_dir(&server_folder);
 
+    // Apply per-instance environment variables on top of the inherited environment
+    cmd.envs(&env_vars);
+
     // Configure stdio
     cmd.stdin(Stdio::piped());
     cmd.stdout(Stdio::piped());
     cmd.stderr(Stdio::piped());
 
+    // Run in its own process group so stopping can signal anything the JVM spawned
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+
+    let launch_config = LaunchConfig {
+        instance_path: instance_path.clone(),
+        working_dir: server_folder.to_string_lossy().to_string(),
+        java_path: java_exe.clone(),
+        jvm_args: jvm_args.clone(),
+        server_args: server_args.clone(),
+        env_vars: env_vars.clone(),
+        launch_wrapper: launch_wrapper.clone(),
+    };
+
     println!("[start_server] Spawning process in {:?}", server_folder);
 
     // Spawn the process
@@ -264,6 +837,8 @@ pub async fn start_server(
                 success: false,
                 pid: None,
                 error: Some(format!("Failed to start server: {}", e)),
+                error_code: Some(ErrorCode::from_io(&e)),
+                requires_auth: false,
             });
         }
     };
@@ -272,21 +847,55 @@ pub async fn start_server(
     let started_at = Utc::now();
     println!("[start_server] Process spawned with PID: {}", pid);
 
+    // Put the server in a Job Object so a forced stop takes down everything it spawned
+    #[cfg(windows)]
+    let job = match ProcessJob::assign(&child) {
+        Ok(job) => Some(job),
+        Err(e) => {
+            println!("[start_server] WARNING: {}; stopping will fall back to taskkill", e);
+            None
+        }
+    };
+
     // Create channel for stdin
     let (stdin_tx, stdin_rx) = std::sync::mpsc::channel::<String>();
 
+    let output_buffer_lines = match app.try_state::<DbPool>() {
+        Some(pool) => get_output_buffer_lines(pool.inner()).await,
+        None => DEFAULT_OUTPUT_BUFFER_LINES,
+    };
+
     // Take stdout and stderr
     let stdout = child.stdout.take();
     let stderr = child.stderr.take();
     let mut stdin = child.stdin.take();
 
+    let recent_lines = Arc::new(Mutex::new(VecDeque::with_capacity(RECENT_LINES_CAPACITY)));
+    let output_buffer = Arc::new(Mutex::new(OutputBuffer::new(output_buffer_lines)));
+    let output_filter = Arc::new(Mutex::new(OutputFilter::default()));
+    let output_batcher = Arc::new(Mutex::new(OutputBatcher::default()));
+    let output_subscribers: OutputSubscribers = Arc::new(Mutex::new(Vec::new()));
+
     // Create process wrapper
     let process = Arc::new(Mutex::new(ServerProcess {
         child,
         instance_id: instance_id.clone(),
+        instance_path: instance_path.clone(),
         started_at,
         stdin_tx: Some(stdin_tx),
         online_players: HashMap::new(),
+        tps: None,
+        ready: false,
+        stop_requested: false,
+        recent_lines: recent_lines.clone(),
+        output_buffer: output_buffer.clone(),
+        output_filter: output_filter.clone(),
+        output_batcher: output_batcher.clone(),
+        output_subscribers: output_subscribers.clone(),
+        io_threads: Vec::new(),
+        launch_config,
+        #[cfg(windows)]
+        job,
     }));
 
     // Store in state
@@ -294,6 +903,7 @@ pub async fn start_server(
         let mut state_guard = state.lock().unwrap();
         state_guard.processes.insert(instance_id.clone(), process.clone());
     }
+    save_running_server(&app, &instance_id, Some((pid, started_at)));
 
     // Emit running status
     let _ = app.emit("server-status-change", ServerStatusInfo {
@@ -303,9 +913,12 @@ pub async fn start_server(
         started_at: Some(started_at.to_rfc3339()),
     });
 
+    // Keep the I/O and monitor threads so stop_server can wait for them
+    let mut io_threads = Vec::new();
+
     // Spawn thread to handle stdin
     let instance_id_stdin = instance_id.clone();
-    std::thread::spawn(move || {
+    io_threads.push(std::thread::spawn(move || {
         while let Ok(command) = stdin_rx.recv() {
             if let Some(ref mut stdin_writer) = stdin {
                 let cmd_with_newline = if command.ends_with('\n') {
@@ -324,28 +937,57 @@ pub async fn start_server(
             }
         }
         println!("[stdin:{}] Thread exiting", instance_id_stdin);
-    });
+    }));
 
     // Spawn thread to read stdout
     let app_stdout = app.clone();
     let instance_id_stdout = instance_id.clone();
     let state_for_stdout = state.inner().clone();
+    let recent_lines_stdout = recent_lines.clone();
+    let output_buffer_stdout = output_buffer.clone();
+    let output_filter_stdout = output_filter.clone();
+    let output_batcher_stdout = output_batcher.clone();
+    let subscribers_stdout = output_subscribers.clone();
     if let Some(stdout) = stdout {
-        std::thread::spawn(move || {
+        io_threads.push(std::thread::spawn(move || {
             let reader = BufReader::new(stdout);
             // Track auth profile name across lines
             let mut last_profile_name: Option<String> = None;
+            // Only report readiness once per launch
+            let mut server_ready = false;
 
-            for line in reader.lines() {
+            for line in lossy_lines(reader) {
                 match line {
                     Ok(text) => {
+                        push_recent_line(&recent_lines_stdout, text.clone());
+
+                        // Feed anyone waiting on command output
+                        if let Ok(mut subscribers) = subscribers_stdout.lock() {
+                            if !subscribers.is_empty() {
+                                let clean_line = strip_ansi_codes(&text);
+                                subscribers.retain(|tx| tx.send(clean_line.clone()).is_ok());
+                            }
+                        }
+
                         let output = ServerOutput {
                             instance_id: instance_id_stdout.clone(),
                             line: text.clone(),
                             stream: "stdout".to_string(),
                             timestamp: Utc::now().to_rfc3339(),
+                            spans: parse_ansi_spans(&text),
                         };
-                        let _ = app_stdout.emit("server-output", &output);
+                        let emit = output_filter_stdout
+                            .lock()
+                            .map(|filter| filter.allows("stdout", &text))
+                            .unwrap_or(true);
+                        if emit {
+                            if let Ok(mut batcher) = output_batcher_stdout.lock() {
+                                batcher.emit(&app_stdout, &output);
+                            }
+                        }
+                        if let Ok(mut buffer) = output_buffer_stdout.lock() {
+                            buffer.push(output);
+                        }
 
                         // Check if server needs authentication (before /auth login is executed)
                         if text.contains("No server tokens configured") {
@@ -395,6 +1037,17 @@ pub async fn start_server(
                                 auth_mode,
                             };
                             let _ = app_stdout.emit("server-auth-success", &success_event);
+                            save_auth_status(&app_stdout, &instance_id_stdout, "authenticated", last_profile_name.clone());
+                        }
+
+                        // Check for "Authentication failed: ..."
+                        if text.to_lowercase().contains("authentication failed") {
+                            let failed_event = AuthFailedEvent {
+                                instance_id: instance_id_stdout.clone(),
+                                message: strip_ansi_codes(&text).trim().to_string(),
+                            };
+                            let _ = app_stdout.emit("server-auth-failed", &failed_event);
+                            save_auth_status(&app_stdout, &instance_id_stdout, "unauthenticated", None);
                         }
 
                         // Check for player join
@@ -414,6 +1067,13 @@ pub async fn start_server(
                                 }
                             }
 
+                            notify_instance(
+                                &app_stdout,
+                                WebhookEvent::PlayerJoined,
+                                &instance_id_stdout,
+                                format!("{} joined the server", player.name),
+                            );
+
                             let join_event = PlayerJoinEvent {
                                 instance_id: instance_id_stdout.clone(),
                                 player,
@@ -439,33 +1099,97 @@ pub async fn start_server(
                             };
                             let _ = app_stdout.emit("player-left", &leave_event);
                         }
-                    }
-                    Err(e) => {
-                        println!("[stdout:{}] Read error: {}", instance_id_stdout, e);
-                        break;
-                    }
-                }
-            }
-            println!("[stdout:{}] Thread exiting", instance_id_stdout);
-        });
-    }
 
-    // Spawn thread to read stderr
-    let app_stderr = app.clone();
-    let instance_id_stderr = instance_id.clone();
-    if let Some(stderr) = stderr {
-        std::thread::spawn(move || {
-            let reader = BufReader::new(stderr);
-            for line in reader.lines() {
+                        // Check for the server finishing startup
+                        if !server_ready && is_server_ready_line(&text) {
+                            server_ready = true;
+
+                            if let Ok(state_guard) = state_for_stdout.lock() {
+                                if let Some(process_arc) = state_guard.processes.get(&instance_id_stdout) {
+                                    if let Ok(mut process) = process_arc.lock() {
+                                        process.ready = true;
+                                    }
+                                }
+                            }
+
+                            let ready_event = ServerReadyEvent {
+                                instance_id: instance_id_stdout.clone(),
+                                startup_ms: (Utc::now() - started_at).num_milliseconds(),
+                                timestamp: Utc::now().to_rfc3339(),
+                            };
+                            println!(
+                                "[stdout:{}] Server ready after {}ms",
+                                instance_id_stdout, ready_event.startup_ms
+                            );
+                            let _ = app_stdout.emit("server-ready", &ready_event);
+                        }
+
+                        // Check for tick rate reports
+                        if let Some(tps) = parse_tps(&text) {
+                            if let Ok(state_guard) = state_for_stdout.lock() {
+                                if let Some(process_arc) = state_guard.processes.get(&instance_id_stdout) {
+                                    if let Ok(mut process) = process_arc.lock() {
+                                        process.tps = Some(tps);
+                                    }
+                                }
+                            }
+
+                            let tps_event = TpsUpdateEvent {
+                                instance_id: instance_id_stdout.clone(),
+                                tps,
+                                timestamp: Utc::now().to_rfc3339(),
+                            };
+                            let _ = app_stdout.emit("tps-update", &tps_event);
+                        }
+                    }
+                    Err(e) => {
+                        println!("[stdout:{}] Read error: {}", instance_id_stdout, e);
+                        break;
+                    }
+                }
+            }
+            if let Ok(mut batcher) = output_batcher_stdout.lock() {
+                batcher.flush(&app_stdout);
+            }
+            println!("[stdout:{}] Thread exiting", instance_id_stdout);
+        }));
+    }
+
+    // Spawn thread to read stderr
+    let app_stderr = app.clone();
+    let instance_id_stderr = instance_id.clone();
+    let recent_lines_stderr = recent_lines.clone();
+    let output_buffer_stderr = output_buffer.clone();
+    let output_filter_stderr = output_filter.clone();
+    let output_batcher_stderr = output_batcher.clone();
+    if let Some(stderr) = stderr {
+        io_threads.push(std::thread::spawn(move || {
+            let reader = BufReader::new(stderr);
+            for line in lossy_lines(reader) {
                 match line {
                     Ok(text) => {
+                        push_recent_line(&recent_lines_stderr, text.clone());
+
+                        let emit = output_filter_stderr
+                            .lock()
+                            .map(|filter| filter.allows("stderr", &text))
+                            .unwrap_or(true);
+
                         let output = ServerOutput {
                             instance_id: instance_id_stderr.clone(),
+                            spans: parse_ansi_spans(&text),
                             line: text,
                             stream: "stderr".to_string(),
                             timestamp: Utc::now().to_rfc3339(),
                         };
-                        let _ = app_stderr.emit("server-output", &output);
+                        if emit {
+                            if let Ok(mut batcher) = output_batcher_stderr.lock() {
+                                batcher.emit(&app_stderr, &output);
+                            }
+                        }
+                        if let Ok(mut buffer) = output_buffer_stderr.lock() {
+                            buffer.push(output);
+                        }
                     }
                     Err(e) => {
                         println!("[stderr:{}] Read error: {}", instance_id_stderr, e);
@@ -473,20 +1197,42 @@ pub async fn start_server(
                     }
                 }
             }
+            if let Ok(mut batcher) = output_batcher_stderr.lock() {
+                batcher.flush(&app_stderr);
+            }
             println!("[stderr:{}] Thread exiting", instance_id_stderr);
-        });
+        }));
     }
 
+    // Flush batches when output goes quiet. Not kept in io_threads: it ends on its own once
+    // the readers and the process entry have let go of the batcher.
+    let app_batcher = app.clone();
+    let batcher_weak = Arc::downgrade(&output_batcher);
+    std::thread::spawn(move || loop {
+        let tick = match batcher_weak.upgrade() {
+            Some(batcher) => {
+                let mut batcher = batcher.lock().unwrap();
+                batcher.flush_due(&app_batcher);
+                batcher.window.unwrap_or(OUTPUT_BATCH_IDLE_TICK)
+            }
+            None => break,
+        };
+        std::thread::sleep(tick);
+    });
+
     // Spawn thread to monitor process exit
     let app_monitor = app.clone();
     let state_monitor = state.inner().clone();
     let instance_id_monitor = instance_id.clone();
-    std::thread::spawn(move || {
+    io_threads.push(std::thread::spawn(move || {
         // Wait for the process to exit
         loop {
             std::thread::sleep(std::time::Duration::from_millis(500));
 
             let mut should_cleanup = false;
+            let mut exit_status: Option<std::process::ExitStatus> = None;
+            let mut recent_lines_monitor: Option<Arc<Mutex<VecDeque<String>>>> = None;
+            let mut stop_requested = false;
             {
                 let state_guard = state_monitor.lock().unwrap();
                 if let Some(process_arc) = state_guard.processes.get(&instance_id_monitor) {
@@ -494,6 +1240,7 @@ pub async fn start_server(
                     match process.child.try_wait() {
                         Ok(Some(status)) => {
                             println!("[monitor:{}] Process exited with: {:?}", instance_id_monitor, status);
+                            exit_status = Some(status);
                             should_cleanup = true;
                         }
                         Ok(None) => {
@@ -504,6 +1251,10 @@ pub async fn start_server(
                             should_cleanup = true;
                         }
                     }
+                    if should_cleanup {
+                        recent_lines_monitor = Some(process.recent_lines.clone());
+                        stop_requested = process.stop_requested;
+                    }
                 } else {
                     // Process was removed from state (stopped by user)
                     break;
@@ -511,11 +1262,22 @@ pub async fn start_server(
             }
 
             if should_cleanup {
-                // Remove from state
-                {
+                #[cfg(unix)]
+                let signal = {
+                    use std::os::unix::process::ExitStatusExt;
+                    exit_status.and_then(|status| status.signal())
+                };
+                #[cfg(not(unix))]
+                let signal = None;
+                let code = exit_status.and_then(|status| status.code());
+
+                // Remove from state and remember the exit
+                let recent_crashes = {
                     let mut state_guard = state_monitor.lock().unwrap();
                     state_guard.processes.remove(&instance_id_monitor);
-                }
+                    record_exit(&mut state_guard, &instance_id_monitor, code, signal, stop_requested)
+                };
+                save_running_server(&app_monitor, &instance_id_monitor, None);
 
                 // Emit stopped status
                 let _ = app_monitor.emit("server-status-change", ServerStatusInfo {
@@ -525,20 +1287,91 @@ pub async fn start_server(
                     started_at: None,
                 });
 
-                let _ = app_monitor.emit("server-exit", &instance_id_monitor);
+                // Give the output readers a moment to drain the final lines
+                std::thread::sleep(std::time::Duration::from_millis(200));
+
+                let last_lines = recent_lines_monitor
+                    .and_then(|buffer| buffer.lock().ok().map(|lines| lines.iter().cloned().collect()))
+                    .unwrap_or_default();
+
+                let exit_event = ServerExitEvent {
+                    instance_id: instance_id_monitor.clone(),
+                    code,
+                    signal,
+                    last_lines,
+                };
+                // Exits requested through stop_server are normal stops whatever the code
+                // (a signalled JVM exits with 143); stop_server sends that notification itself
+                let crashed = !stop_requested && exit_event.code != Some(0);
+                if crashed {
+                    let reason = match (exit_event.code, exit_event.signal) {
+                        (Some(code), _) => format!("exit code {}", code),
+                        (None, Some(signal)) => format!("signal {}", signal),
+                        (None, None) => "unknown reason".to_string(),
+                    };
+                    notify_instance(
+                        &app_monitor,
+                        WebhookEvent::ServerCrashed,
+                        &instance_id_monitor,
+                        format!("Server crashed ({})", reason),
+                    );
+                } else if !stop_requested {
+                    notify_instance(&app_monitor, WebhookEvent::ServerStopped, &instance_id_monitor, "Server stopped".to_string());
+                }
+
+                let _ = app_monitor.emit("server-exit", &exit_event);
+
+                if crashed && recent_crashes >= CRASH_LOOP_THRESHOLD {
+                    println!(
+                        "[monitor:{}] Crash loop: {} abnormal exits in {}s",
+                        instance_id_monitor, recent_crashes, CRASH_LOOP_WINDOW_SECS
+                    );
+                    let _ = app_monitor.emit("server-crash-loop", CrashLoopEvent {
+                        instance_id: instance_id_monitor.clone(),
+                        crashes: recent_crashes,
+                        window_secs: CRASH_LOOP_WINDOW_SECS,
+                    });
+                }
                 break;
             }
         }
         println!("[monitor:{}] Thread exiting", instance_id_monitor);
-    });
+    }));
+
+    if let Ok(mut process_guard) = process.lock() {
+        process_guard.io_threads = io_threads;
+    }
+
+    if let Some(timeout_secs) = startup_timeout_secs.filter(|t| *t > 0) {
+        let kill = kill_on_startup_timeout.unwrap_or(false);
+        watch_startup_timeout(app.clone(), state.inner().clone(), process.clone(), timeout_secs, kill);
+    }
+
+    notify_instance(&app, WebhookEvent::ServerStarted, &instance_id, "Server started".to_string());
+
+    if requires_auth {
+        println!("[start_server] Instance {} is not authenticated yet", instance_id);
+        let _ = app.emit("server-auth-likely-needed", &instance_id);
+    }
 
     Ok(StartResult {
         success: true,
         pid: Some(pid),
         error: None,
+        error_code: None,
+        requires_auth,
     })
 }
 
+/// Console command sent to the server when stopping, unless overridden per instance
+const DEFAULT_STOP_COMMAND: &str = "stop";
+
+/// Seconds to wait for the stop command before falling back to signals
+const DEFAULT_STOP_COMMAND_TIMEOUT_SECS: u64 = 30;
+
+/// Seconds between SIGTERM and a forced kill, unless overridden per instance
+const DEFAULT_STOP_TIMEOUT_SECS: u64 = 10;
+
 /// Stop a server instance
 #[tauri::command]
 pub async fn stop_server(
@@ -552,16 +1385,23 @@ pub async fn stop_server(
     let process_arc = {
         let state_guard = state.lock().unwrap();
         match state_guard.processes.get(&instance_id) {
-            Some(p) => p.clone(),
+            Some(p) => Some(p.clone()),
+            None if state_guard.adopted.contains_key(&instance_id) => None,
             None => {
                 return Ok(StopResult {
                     success: false,
                     error: Some("Server is not running".to_string()),
+                    timeout_secs: None,
                 });
             }
         }
     };
 
+    // A server adopted from a previous session has no stdin, so it can only be signaled
+    let Some(process_arc) = process_arc else {
+        return Ok(stop_adopted_server(&app, &state, &instance_id).await);
+    };
+
     // Emit stopping status
     let _ = app.emit("server-status-change", ServerStatusInfo {
         status: ServerStatus::Stopping,
@@ -570,99 +1410,364 @@ pub async fn stop_server(
         started_at: None,
     });
 
-    // Try graceful shutdown first
-    let pid = {
-        let process = process_arc.lock().unwrap();
-        process.child.id()
+    // Look up the per-instance shutdown command and stop timeouts
+    let defaults = (
+        DEFAULT_STOP_COMMAND.to_string(),
+        DEFAULT_STOP_COMMAND_TIMEOUT_SECS,
+        DEFAULT_STOP_TIMEOUT_SECS,
+    );
+    let (stop_command, stop_command_timeout, timeout_secs) = match app.try_state::<DbPool>() {
+        Some(pool) => match database::get_instance_by_id(pool.inner(), &instance_id).await {
+            Ok(Some(instance)) => (
+                instance.stop_command.unwrap_or_else(|| DEFAULT_STOP_COMMAND.to_string()),
+                instance
+                    .stop_command_timeout_secs
+                    .map(|t| t.max(0) as u64)
+                    .unwrap_or(DEFAULT_STOP_COMMAND_TIMEOUT_SECS),
+                (instance.stop_timeout_secs.max(1)) as u64,
+            ),
+            _ => defaults,
+        },
+        None => defaults,
+    };
+
+    let (pid, stdin_tx) = {
+        let mut process = process_arc.lock().unwrap();
+        process.stop_requested = true;
+        (process.child.id(), process.stdin_tx.clone())
+    };
+
+    // Ask the server to stop itself so it saves chunks and flushes state
+    let mut exited = false;
+    let stop_command = stop_command.trim().to_string();
+    if !stop_command.is_empty() && stop_command_timeout > 0 {
+        if let Some(tx) = stdin_tx {
+            println!(
+                "[stop_server] Sending '{}' to PID {} (waiting up to {}s)",
+                stop_command, pid, stop_command_timeout
+            );
+            if tx.send(stop_command).is_ok() {
+                exited = wait_for_exit(&process_arc, std::time::Duration::from_secs(stop_command_timeout)).await;
+            }
+        }
+    }
+
+    close_process_io(&process_arc);
+
+    if exited {
+        println!("[stop_server] Process exited after shutdown command");
+    } else {
+        println!("[stop_server] Attempting graceful shutdown of PID: {}", pid);
+
+        if !signal_server(&process_arc, false) {
+            println!("[stop_server] Process already exited, skipping signals");
+        } else {
+            // Wait for process to exit (with timeout)
+            println!("[stop_server] Waiting up to {}s before forcing kill", timeout_secs);
+
+            if wait_for_exit(&process_arc, std::time::Duration::from_secs(timeout_secs)).await {
+                println!("[stop_server] Process exited gracefully");
+            } else {
+                println!("[stop_server] Timeout reached, forcing kill");
+                signal_server(&process_arc, true);
+
+                // Reap the killed process so it doesn't linger as a zombie
+                let _ = process_arc.lock().unwrap().child.wait();
+            }
+        }
+    }
+
+    let io_threads = detach_process(&state, &instance_id, &process_arc);
+    save_running_server(&app, &instance_id, None);
+
+    // Wait for the I/O and monitor threads so a quick restart starts clean
+    let join_id = instance_id.clone();
+    let _ = tokio::task::spawn_blocking(move || join_io_threads(&join_id, io_threads)).await;
+
+    // Emit stopped status
+    let _ = app.emit("server-status-change", ServerStatusInfo {
+        status: ServerStatus::Stopped,
+        instance_id: instance_id.clone(),
+        pid: None,
+        started_at: None,
+    });
+
+    println!("[stop_server] Server stopped successfully");
+
+    notify_instance(&app, WebhookEvent::ServerStopped, &instance_id, "Server stopped".to_string());
+
+    Ok(StopResult {
+        success: true,
+        error: None,
+        timeout_secs: Some(timeout_secs),
+    })
+}
+
+/// Stop a server adopted from a previous session: signal it, wait for the instance's
+/// stop timeout, then kill it. The stop command can't be sent since stdin is gone.
+async fn stop_adopted_server(app: &AppHandle, state: &Arc<Mutex<ServerState>>, instance_id: &str) -> StopResult {
+    let Some(adopted) = state.lock().unwrap().adopted.get(instance_id).cloned() else {
+        return StopResult {
+            success: false,
+            error: Some("Server is not running".to_string()),
+            timeout_secs: None,
+        };
+    };
+    let Some(metrics_state) = app.try_state::<Arc<Mutex<MetricsState>>>().map(|m| m.inner().clone()) else {
+        return StopResult {
+            success: false,
+            error: Some("Process information is unavailable".to_string()),
+            timeout_secs: None,
+        };
+    };
+
+    let _ = app.emit("server-status-change", ServerStatusInfo {
+        status: ServerStatus::Stopping,
+        instance_id: instance_id.to_string(),
+        pid: Some(adopted.pid),
+        started_at: None,
+    });
+
+    let timeout_secs = match app.try_state::<DbPool>() {
+        Some(pool) => match database::get_instance_by_id(pool.inner(), instance_id).await {
+            Ok(Some(instance)) => instance.stop_timeout_secs.max(1) as u64,
+            _ => DEFAULT_STOP_TIMEOUT_SECS,
+        },
+        None => DEFAULT_STOP_TIMEOUT_SECS,
     };
 
-    println!("[stop_server] Attempting graceful shutdown of PID: {}", pid);
+    println!("[stop_server] Signaling adopted PID {} (waiting up to {}s)", adopted.pid, timeout_secs);
+    let pid = adopted.pid;
+    tokio::task::spawn_blocking(move || {
+        signal_process_tree(pid, false);
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(timeout_secs);
+        while is_process_alive(&metrics_state, pid) {
+            if std::time::Instant::now() >= deadline {
+                println!("[stop_server] Timeout reached, forcing kill of adopted PID {}", pid);
+                signal_process_tree(pid, true);
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(250));
+        }
+    })
+    .await
+    .ok();
+
+    state.lock().unwrap().adopted.remove(instance_id);
+    save_running_server(app, instance_id, None);
+
+    let _ = app.emit("server-status-change", ServerStatusInfo {
+        status: ServerStatus::Stopped,
+        instance_id: instance_id.to_string(),
+        pid: None,
+        started_at: None,
+    });
+    notify_instance(app, WebhookEvent::ServerStopped, instance_id, "Server stopped".to_string());
+
+    StopResult {
+        success: true,
+        error: None,
+        timeout_secs: Some(timeout_secs),
+    }
+}
+
+/// How long stop_server waits for a server's I/O threads to finish
+const IO_THREAD_JOIN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// Close the stdin channel so the writer thread exits before any restart,
+/// and release anyone still waiting on command output
+fn close_process_io(process_arc: &Arc<Mutex<ServerProcess>>) {
+    let mut process = process_arc.lock().unwrap();
+    process.stdin_tx = None;
+    if let Ok(mut subscribers) = process.output_subscribers.lock() {
+        subscribers.clear();
+    }
+}
+
+/// Remove a stopped server from state and hand back its threads for joining.
+/// The monitor thread exits once it sees the process is gone.
+fn detach_process(
+    state: &Mutex<ServerState>,
+    instance_id: &str,
+    process_arc: &Arc<Mutex<ServerProcess>>,
+) -> Vec<std::thread::JoinHandle<()>> {
+    let mut state_guard = state.lock().unwrap();
+    state_guard.processes.remove(instance_id);
+    std::mem::take(&mut process_arc.lock().unwrap().io_threads)
+}
+
+/// Join finished threads, giving them up to IO_THREAD_JOIN_TIMEOUT.
+/// A thread can outlive the process if a grandchild still holds the pipe open;
+/// those are left detached rather than blocking the stop. Returns how many were detached.
+fn join_io_threads(instance_id: &str, threads: Vec<std::thread::JoinHandle<()>>) -> usize {
+    let start = std::time::Instant::now();
+    let mut pending = threads;
+
+    while !pending.is_empty() && start.elapsed() < IO_THREAD_JOIN_TIMEOUT {
+        let (finished, running): (Vec<_>, Vec<_>) = pending.into_iter().partition(|t| t.is_finished());
+        for thread in finished {
+            let _ = thread.join();
+        }
+        pending = running;
+        if !pending.is_empty() {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+    }
+
+    if !pending.is_empty() {
+        println!(
+            "[stop_server:{}] {} thread(s) still running after stop, detaching",
+            instance_id,
+            pending.len()
+        );
+    }
+    pending.len()
+}
+
+/// Ask the server and everything it spawned to terminate, or kill them with `force`.
+/// On Unix the server leads its own process group, so the whole group is signaled;
+/// on Windows a forced stop terminates the server's Job Object, and taskkill /T
+/// is used for the graceful request (and when the job couldn't be created).
+/// Does nothing and returns false if the process has already exited. The exit check
+/// and the signal happen under the process lock, so the monitor thread can't reap the
+/// child in between and let the OS hand its PID to an unrelated process.
+fn signal_server(process_arc: &Arc<Mutex<ServerProcess>>, force: bool) -> bool {
+    let mut process = process_arc.lock().unwrap();
+
+    if !matches!(process.child.try_wait(), Ok(None)) {
+        return false;
+    }
+
+    #[cfg(windows)]
+    let job_terminated = force && process.job.as_ref().is_some_and(|job| job.terminate());
+    #[cfg(not(windows))]
+    let job_terminated = false;
+
+    if !job_terminated {
+        signal_process_tree(process.child.id(), force);
+    }
+
+    if force {
+        // Make sure the server itself is gone even if the group signal failed
+        let _ = process.child.kill();
+    }
+
+    true
+}
 
-    // Platform-specific termination
+/// Send the terminate (or kill) signal to a server's process group on Unix,
+/// or its process tree on Windows
+fn signal_process_tree(pid: u32, force: bool) {
     #[cfg(unix)]
     {
-        use std::process::Command;
-        // Send SIGTERM
+        // A negative PID targets the process group led by the server
+        let signal = if force { "-KILL" } else { "-TERM" };
         let _ = Command::new("kill")
-            .args(["-TERM", &pid.to_string()])
+            .args([signal, "--", &format!("-{}", pid)])
             .output();
     }
 
     #[cfg(windows)]
     {
-        use std::process::Command;
-        // Use taskkill without /F first for graceful shutdown
-        let _ = Command::new("taskkill")
-            .args(["/PID", &pid.to_string()])
-            .output();
+        // Without /F, taskkill asks the processes to close
+        let pid = pid.to_string();
+        let mut args = Vec::new();
+        if force {
+            args.push("/F");
+        }
+        args.extend(["/T", "/PID", pid.as_str()]);
+        let _ = Command::new("taskkill").args(&args).output();
     }
+}
 
-    // Wait for process to exit (with timeout)
-    let timeout_secs = 10;
-    let start = std::time::Instant::now();
-
-    loop {
-        if start.elapsed().as_secs() >= timeout_secs {
-            println!("[stop_server] Timeout reached, forcing kill");
+/// Report (and optionally kill) a server that hasn't logged its ready line
+/// within `timeout_secs` of being spawned
+fn watch_startup_timeout(
+    app: AppHandle,
+    state: Arc<Mutex<ServerState>>,
+    process_arc: Arc<Mutex<ServerProcess>>,
+    timeout_secs: u64,
+    kill: bool,
+) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_secs(timeout_secs)).await;
+
+        let instance_id = process_arc.lock().unwrap().instance_id.clone();
+
+        // Only act on this exact launch; the server may have been stopped or restarted since
+        let still_booting = {
+            let state_guard = state.lock().unwrap();
+            let is_same_launch = state_guard
+                .processes
+                .get(&instance_id)
+                .map(|p| Arc::ptr_eq(p, &process_arc))
+                .unwrap_or(false);
+            is_same_launch && !process_arc.lock().unwrap().ready
+        };
+        if !still_booting {
+            return;
+        }
 
-            // Force kill
-            #[cfg(unix)]
-            {
-                let _ = std::process::Command::new("kill")
-                    .args(["-9", &pid.to_string()])
-                    .output();
-            }
+        println!(
+            "[start_server:{}] Not ready after {}s{}",
+            instance_id,
+            timeout_secs,
+            if kill { ", killing process" } else { "" }
+        );
+
+        // Mark the kill as requested so the monitor doesn't report it as a crash or
+        // count it toward the crash loop; it still cleans up once it sees the exit
+        if kill {
+            process_arc.lock().unwrap().stop_requested = true;
+        }
+        let killed = kill && signal_server(&process_arc, true);
+        if killed {
+            notify_instance(
+                &app,
+                WebhookEvent::ServerStopped,
+                &instance_id,
+                format!("Server killed after not starting within {}s", timeout_secs),
+            );
+        }
 
-            #[cfg(windows)]
-            {
-                let _ = std::process::Command::new("taskkill")
-                    .args(["/F", "/PID", &pid.to_string()])
-                    .output();
-            }
+        let _ = app.emit(
+            "server-start-timeout",
+            ServerStartTimeoutEvent {
+                instance_id,
+                timeout_secs,
+                killed,
+            },
+        );
+    });
+}
 
-            break;
-        }
+/// Polls the process until it exits or the timeout elapses, sleeping on the async
+/// runtime so other commands (and parallel stops) keep running meanwhile.
+/// Returns true if the process is gone (or its status can no longer be read).
+async fn wait_for_exit(process_arc: &Arc<Mutex<ServerProcess>>, timeout: std::time::Duration) -> bool {
+    let start = std::time::Instant::now();
 
+    loop {
         {
             let mut process = process_arc.lock().unwrap();
             match process.child.try_wait() {
-                Ok(Some(_)) => {
-                    println!("[stop_server] Process exited gracefully");
-                    break;
-                }
+                Ok(Some(_)) => return true,
                 Ok(None) => {
                     // Still running, continue waiting
                 }
                 Err(e) => {
                     println!("[stop_server] Error checking status: {}", e);
-                    break;
+                    return true;
                 }
             }
         }
 
-        std::thread::sleep(std::time::Duration::from_millis(200));
-    }
+        if start.elapsed() >= timeout {
+            return false;
+        }
 
-    // Remove from state
-    {
-        let mut state_guard = state.lock().unwrap();
-        state_guard.processes.remove(&instance_id);
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
     }
-
-    // Emit stopped status
-    let _ = app.emit("server-status-change", ServerStatusInfo {
-        status: ServerStatus::Stopped,
-        instance_id: instance_id.clone(),
-        pid: None,
-        started_at: None,
-    });
-
-    println!("[stop_server] Server stopped successfully");
-
-    Ok(StopResult {
-        success: true,
-        error: None,
-    })
 }
 
 /// Get the status of a server instance
@@ -683,11 +1788,19 @@ pub fn get_server_status(
                 started_at: Some(process.started_at.to_rfc3339()),
             }
         }
-        None => ServerStatusInfo {
-            status: ServerStatus::Stopped,
-            instance_id,
-            pid: None,
-            started_at: None,
+        None => match state_guard.adopted.get(&instance_id) {
+            Some(adopted) => ServerStatusInfo {
+                status: ServerStatus::Running,
+                instance_id,
+                pid: Some(adopted.pid),
+                started_at: Some(adopted.started_at.to_rfc3339()),
+            },
+            None => ServerStatusInfo {
+                status: ServerStatus::Stopped,
+                instance_id,
+                pid: None,
+                started_at: None,
+            },
         },
     }
 }
@@ -699,6 +1812,13 @@ pub fn get_all_server_statuses(
 ) -> Vec<ServerStatusInfo> {
     let state_guard = state.lock().unwrap();
 
+    let adopted = state_guard.adopted.iter().map(|(id, adopted)| ServerStatusInfo {
+        status: ServerStatus::Running,
+        instance_id: id.clone(),
+        pid: Some(adopted.pid),
+        started_at: Some(adopted.started_at.to_rfc3339()),
+    });
+
     state_guard.processes.iter().map(|(id, process_arc)| {
         let process = process_arc.lock().unwrap();
         ServerStatusInfo {
@@ -707,39 +1827,260 @@ pub fn get_all_server_statuses(
             pid: Some(process.child.id()),
             started_at: Some(process.started_at.to_rfc3339()),
         }
-    }).collect()
+    }).chain(adopted).collect()
+}
+
+/// Get when a running server started and for how long it has been up.
+/// Cheaper than get_server_metrics since it doesn't refresh system stats; None if stopped.
+#[tauri::command]
+pub fn get_server_uptime(
+    state: State<'_, Arc<Mutex<ServerState>>>,
+    instance_id: String,
+) -> Option<ServerUptime> {
+    let state_guard = state.lock().unwrap();
+    let started_at = match state_guard.processes.get(&instance_id) {
+        Some(process_arc) => process_arc.lock().unwrap().started_at,
+        None => state_guard.adopted.get(&instance_id)?.started_at,
+    };
+
+    Some(ServerUptime {
+        instance_id,
+        started_at: started_at.to_rfc3339(),
+        uptime_seconds: (Utc::now() - started_at).num_seconds().max(0) as u64,
+    })
+}
+
+/// Get the recent exits of an instance (oldest first), including crashes
+#[tauri::command]
+pub fn get_crash_history(
+    state: State<'_, Arc<Mutex<ServerState>>>,
+    instance_id: String,
+) -> Vec<ExitRecord> {
+    let state_guard = state.lock().unwrap();
+    state_guard
+        .exit_history
+        .get(&instance_id)
+        .map(|history| history.iter().cloned().collect())
+        .unwrap_or_default()
 }
 
+/// Maximum number of console commands remembered per instance
+const COMMAND_HISTORY_MAX_ENTRIES: u32 = 100;
+
 /// Send a command to the server's stdin
 #[tauri::command]
-pub fn send_server_command(
+pub async fn send_server_command(
+    app: AppHandle,
     state: State<'_, Arc<Mutex<ServerState>>>,
     instance_id: String,
     command: String,
 ) -> Result<bool, ()> {
     println!("[send_command:{}] Sending: {}", instance_id, command);
 
-    let state_guard = state.lock().unwrap();
+    let sent = {
+        let state_guard = state.lock().unwrap();
 
-    match state_guard.processes.get(&instance_id) {
-        Some(process_arc) => {
-            let process = process_arc.lock().unwrap();
-            if let Some(ref tx) = process.stdin_tx {
-                match tx.send(command) {
-                    Ok(_) => Ok(true),
-                    Err(e) => {
-                        println!("[send_command:{}] Error: {}", instance_id, e);
-                        Ok(false)
+        match state_guard.processes.get(&instance_id) {
+            Some(process_arc) => {
+                let process = process_arc.lock().unwrap();
+                if let Some(ref tx) = process.stdin_tx {
+                    match tx.send(command.clone()) {
+                        Ok(_) => true,
+                        Err(e) => {
+                            println!("[send_command:{}] Error: {}", instance_id, e);
+                            false
+                        }
                     }
+                } else {
+                    println!("[send_command:{}] No stdin channel", instance_id);
+                    false
                 }
-            } else {
-                println!("[send_command:{}] No stdin channel", instance_id);
-                Ok(false)
+            }
+            None => {
+                println!("[send_command:{}] Server not running", instance_id);
+                false
             }
         }
-        None => {
-            println!("[send_command:{}] Server not running", instance_id);
-            Ok(false)
+    };
+
+    // Remember the command for console history
+    let trimmed = command.trim();
+    if sent && !trimmed.is_empty() {
+        if let Some(pool) = app.try_state::<DbPool>() {
+            if let Err(e) = database::add_command_history(
+                pool.inner(),
+                &instance_id,
+                trimmed,
+                COMMAND_HISTORY_MAX_ENTRIES,
+            )
+            .await
+            {
+                println!("[send_command:{}] Failed to save history: {}", instance_id, e);
+            }
+        }
+    }
+
+    Ok(sent)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandResponse {
+    pub success: bool,
+    pub lines: Vec<String>,  // Output lines seen after the command, ANSI codes stripped
+    pub matched: bool,       // True if a line matched before the timeout
+    pub error: Option<String>,
+}
+
+/// Upper bound for how long send_command_await may wait
+const MAX_COMMAND_AWAIT_MS: u64 = 60_000;
+
+/// Send a command and collect stdout lines until one contains `match_pattern`
+/// (case-insensitive) or `timeout_ms` elapses. Lines are returned either way.
+#[tauri::command]
+pub async fn send_command_await(
+    state: State<'_, Arc<Mutex<ServerState>>>,
+    instance_id: String,
+    command: String,
+    match_pattern: String,
+    timeout_ms: Option<u64>,
+) -> Result<CommandResponse, ()> {
+    println!("[send_command_await:{}] Sending: {}", instance_id, command);
+
+    let fail = |error: &str| CommandResponse {
+        success: false,
+        lines: Vec::new(),
+        matched: false,
+        error: Some(error.to_string()),
+    };
+
+    let (line_rx, stdin_tx) = {
+        let state_guard = state.lock().unwrap();
+        let process_arc = match state_guard.processes.get(&instance_id) {
+            Some(p) => p.clone(),
+            None => return Ok(fail("Server is not running")),
+        };
+        let process = process_arc.lock().unwrap();
+        let stdin_tx = match process.stdin_tx {
+            Some(ref tx) => tx.clone(),
+            None => return Ok(fail("Server has no stdin channel")),
+        };
+
+        // Subscribe before sending so the response can't slip past
+        let (line_tx, line_rx) = std::sync::mpsc::channel::<String>();
+        process.output_subscribers.lock().unwrap().push(line_tx);
+        (line_rx, stdin_tx)
+    };
+
+    if stdin_tx.send(command).is_err() {
+        return Ok(fail("Failed to send command"));
+    }
+
+    let timeout = std::time::Duration::from_millis(timeout_ms.unwrap_or(5_000).min(MAX_COMMAND_AWAIT_MS));
+    let pattern = match_pattern.to_lowercase();
+
+    let response = tauri::async_runtime::spawn_blocking(move || {
+        let deadline = std::time::Instant::now() + timeout;
+        let mut lines = Vec::new();
+
+        loop {
+            let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+            match line_rx.recv_timeout(remaining) {
+                Ok(line) => {
+                    let matched = line.to_lowercase().contains(&pattern);
+                    lines.push(line);
+                    if matched {
+                        return CommandResponse {
+                            success: true,
+                            lines,
+                            matched: true,
+                            error: None,
+                        };
+                    }
+                }
+                // Timed out, or the server exited and the channel closed
+                Err(_) => break,
+            }
+        }
+
+        CommandResponse {
+            success: true,
+            lines,
+            matched: false,
+            error: None,
+        }
+    })
+    .await;
+
+    Ok(response.unwrap_or_else(|e| fail(&format!("Wait task failed: {}", e))))
+}
+
+/// Send the same command to every running server.
+/// Returns instance_id -> whether the command was delivered; adopted servers are
+/// always `false` since they have no stdin.
+#[tauri::command]
+pub fn broadcast_command(
+    state: State<'_, Arc<Mutex<ServerState>>>,
+    command: String,
+) -> HashMap<String, bool> {
+    println!("[broadcast_command] Sending to all servers: {}", command);
+
+    let state_guard = state.lock().unwrap();
+    let mut results = HashMap::new();
+
+    for (instance_id, process_arc) in state_guard.processes.iter() {
+        let process = process_arc.lock().unwrap();
+        let sent = match process.stdin_tx {
+            Some(ref tx) => match tx.send(command.clone()) {
+                Ok(_) => true,
+                Err(e) => {
+                    println!("[broadcast_command:{}] Error: {}", instance_id, e);
+                    false
+                }
+            },
+            None => {
+                println!("[broadcast_command:{}] No stdin channel", instance_id);
+                false
+            }
+        };
+        results.insert(instance_id.clone(), sent);
+    }
+
+    // Servers adopted from a previous session have no stdin handle to write to
+    for instance_id in state_guard.adopted.keys() {
+        println!(
+            "[broadcast_command:{}] Not sent: server was adopted from a previous session and has no console input",
+            instance_id
+        );
+        results.insert(instance_id.clone(), false);
+    }
+
+    results
+}
+
+/// Get the most recent console commands sent to an instance, oldest first
+#[tauri::command]
+pub async fn get_command_history(
+    app: AppHandle,
+    instance_id: String,
+    limit: Option<u32>,
+) -> Result<Vec<String>, ()> {
+    let limit = limit
+        .unwrap_or(COMMAND_HISTORY_MAX_ENTRIES)
+        .min(COMMAND_HISTORY_MAX_ENTRIES);
+
+    let pool = match app.try_state::<DbPool>() {
+        Some(p) => p,
+        None => return Ok(Vec::new()),
+    };
+
+    match database::get_command_history(pool.inner(), &instance_id, limit).await {
+        Ok(history) => Ok(history),
+        Err(e) => {
+            println!("[get_command_history:{}] Error: {}", instance_id, e);
+            Ok(Vec::new())
         }
     }
 }
@@ -771,10 +2112,824 @@ pub fn get_online_players(
     }
 }
 
+/// Get the most recent console output of a running server, oldest first.
+/// Lets a reopened console view show history instead of starting blank.
+#[tauri::command]
+pub fn get_recent_output(
+    state: State<'_, Arc<Mutex<ServerState>>>,
+    instance_id: String,
+    limit: Option<usize>,
+) -> Vec<ServerOutput> {
+    let buffer = {
+        let state_guard = state.lock().unwrap();
+        match state_guard.processes.get(&instance_id) {
+            Some(process_arc) => process_arc.lock().unwrap().output_buffer.clone(),
+            None => return Vec::new(),
+        }
+    };
+
+    let buffer = buffer.lock().unwrap();
+    let limit = limit.unwrap_or(buffer.lines.len()).min(buffer.lines.len());
+    buffer.lines.iter().skip(buffer.lines.len() - limit).cloned().collect()
+}
+
+/// Limit which console lines a running server emits as `server-output` events.
+/// `min_level` is one of TRACE/DEBUG/INFO/WARN/ERROR; `streams` lists "stdout"/"stderr".
+/// Pass None for either to stop filtering on it. Resets when the server restarts.
+#[tauri::command]
+pub fn set_output_filter(
+    state: State<'_, Arc<Mutex<ServerState>>>,
+    instance_id: String,
+    min_level: Option<String>,
+    streams: Option<Vec<String>>,
+) -> Result<bool, String> {
+    let min_level = match min_level {
+        Some(level) => {
+            let level = level.to_uppercase();
+            match LOG_LEVELS.iter().position(|l| *l == level) {
+                Some(rank) => Some(rank),
+                None => return Err(format!("Unknown log level: {}", level)),
+            }
+        }
+        None => None,
+    };
+
+    if let Some(ref streams) = streams {
+        if let Some(bad) = streams.iter().find(|s| *s != "stdout" && *s != "stderr") {
+            return Err(format!("Unknown stream: {}", bad));
+        }
+    }
+
+    let filter = {
+        let state_guard = state.lock().unwrap();
+        match state_guard.processes.get(&instance_id) {
+            Some(process_arc) => process_arc.lock().unwrap().output_filter.clone(),
+            None => return Ok(false),
+        }
+    };
+
+    println!(
+        "[set_output_filter:{}] min_level={:?}, streams={:?}",
+        instance_id, min_level.map(|i| LOG_LEVELS[i]), streams
+    );
+    *filter.lock().unwrap() = OutputFilter { min_level, streams };
+    Ok(true)
+}
+
+/// Switch a running server between one `server-output` event per line (the default) and
+/// `server-output-batch` events carrying every line from a `window_ms` window (default 50ms).
+/// Batching cuts IPC overhead for very chatty servers. Resets when the server restarts.
+#[tauri::command]
+pub fn set_output_batching(
+    app: AppHandle,
+    state: State<'_, Arc<Mutex<ServerState>>>,
+    instance_id: String,
+    enabled: bool,
+    window_ms: Option<u64>,
+) -> Result<bool, String> {
+    let window_ms = window_ms.unwrap_or(DEFAULT_OUTPUT_BATCH_MS);
+    if !(MIN_OUTPUT_BATCH_MS..=MAX_OUTPUT_BATCH_MS).contains(&window_ms) {
+        return Err(format!(
+            "Batch window must be between {} and {} ms",
+            MIN_OUTPUT_BATCH_MS, MAX_OUTPUT_BATCH_MS
+        ));
+    }
+
+    let batcher = {
+        let state_guard = state.lock().unwrap();
+        match state_guard.processes.get(&instance_id) {
+            Some(process_arc) => process_arc.lock().unwrap().output_batcher.clone(),
+            None => return Ok(false),
+        }
+    };
+
+    println!(
+        "[set_output_batching:{}] enabled={}, window_ms={}",
+        instance_id, enabled, window_ms
+    );
+    let mut batcher = batcher.lock().unwrap();
+    // Send anything queued under the old setting before switching
+    batcher.flush(&app);
+    batcher.window = enabled.then(|| std::time::Duration::from_millis(window_ms));
+    Ok(true)
+}
+
+/// Get the settings a running server was launched with
+#[tauri::command]
+pub fn get_launch_config(
+    state: State<'_, Arc<Mutex<ServerState>>>,
+    instance_id: String,
+) -> Option<LaunchConfig> {
+    let state_guard = state.lock().unwrap();
+    state_guard
+        .processes
+        .get(&instance_id)
+        .map(|process_arc| process_arc.lock().unwrap().launch_config.clone())
+}
+
+/// Get how many console lines are kept per server
+#[tauri::command]
+pub async fn get_console_buffer_lines(app: AppHandle) -> usize {
+    match app.try_state::<DbPool>() {
+        Some(pool) => get_output_buffer_lines(pool.inner()).await,
+        None => DEFAULT_OUTPUT_BUFFER_LINES,
+    }
+}
+
+/// Set how many console lines are kept per server (applies from the next start)
+#[tauri::command]
+pub async fn set_console_buffer_lines(app: AppHandle, lines: usize) -> bool {
+    let pool = match app.try_state::<DbPool>() {
+        Some(p) => p.inner().clone(),
+        None => return false,
+    };
+
+    let lines = lines.clamp(1, MAX_OUTPUT_BUFFER_LINES);
+    database::set_setting(&pool, "console_buffer_lines", &lines.to_string())
+        .await
+        .is_ok()
+}
+
+/// Check whether starts are refused (rather than warned about) when they exceed the memory budget
+#[tauri::command]
+pub async fn get_memory_guard_block(app: AppHandle) -> bool {
+    match app.try_state::<DbPool>() {
+        Some(pool) => get_memory_guard_block_setting(pool.inner()).await,
+        None => false,
+    }
+}
+
+/// Refuse starts that exceed the memory budget instead of only warning
+#[tauri::command]
+pub async fn set_memory_guard_block(app: AppHandle, block: bool) -> bool {
+    let pool = match app.try_state::<DbPool>() {
+        Some(p) => p.inner().clone(),
+        None => return false,
+    };
+
+    database::set_setting(&pool, "memory_guard_block", if block { "true" } else { "false" })
+        .await
+        .is_ok()
+}
+
+/// Console command that starts the device-code login flow
+const AUTH_LOGIN_COMMAND: &str = "/auth login device";
+
+/// Console command that switches credential storage from memory to an encrypted file
+const AUTH_PERSIST_COMMAND: &str = "/auth persistence Encrypted";
+
+/// Text (case-insensitive) in the server's reply once encrypted storage is active
+const AUTH_PERSIST_CONFIRMATION: &str = "encrypted";
+
+/// How long persist_auth waits for the server to confirm the switch
+const AUTH_PERSIST_TIMEOUT_MS: u64 = 10_000;
+
+/// Ask a running server to start the device-code login flow.
+/// The resulting output is picked up by `parse_auth_event` like any other auth prompt.
+#[tauri::command]
+pub fn start_auth(
+    state: State<'_, Arc<Mutex<ServerState>>>,
+    instance_id: String,
+) -> AuthCommandResult {
+    println!("[start_auth:{}] Requesting device login", instance_id);
+
+    match send_to_stdin(&state, &instance_id, AUTH_LOGIN_COMMAND) {
+        Ok(()) => AuthCommandResult {
+            success: true,
+            error: None,
+        },
+        Err(e) => AuthCommandResult {
+            success: false,
+            error: Some(e),
+        },
+    }
+}
+
+/// Ask a running server to store its credentials encrypted on disk, so they survive
+/// restarts. Meant for the prompt shown on `server-auth-needs-persistence`.
+#[tauri::command]
+pub async fn persist_auth(
+    app: AppHandle,
+    state: State<'_, Arc<Mutex<ServerState>>>,
+    instance_id: String,
+) -> Result<AuthCommandResult, ()> {
+    println!("[persist_auth:{}] Enabling encrypted credential storage", instance_id);
+
+    let Some(pool) = app.try_state::<DbPool>() else {
+        return Ok(AuthCommandResult {
+            success: false,
+            error: Some(DATABASE_UNAVAILABLE.to_string()),
+        });
+    };
+
+    // Only record the new mode once the server has confirmed it
+    let response = send_command_await(
+        state,
+        instance_id.clone(),
+        AUTH_PERSIST_COMMAND.to_string(),
+        AUTH_PERSIST_CONFIRMATION.to_string(),
+        Some(AUTH_PERSIST_TIMEOUT_MS),
+    )
+    .await?;
+    if !response.matched {
+        return Ok(AuthCommandResult {
+            success: false,
+            error: Some(
+                response
+                    .error
+                    .unwrap_or_else(|| "The server didn't confirm encrypted credential storage".to_string()),
+            ),
+        });
+    }
+
+    if let Err(e) = database::update_instance_auth(
+        pool.inner(),
+        &instance_id,
+        None,
+        Some("encrypted".to_string()),
+        None,
+    )
+    .await
+    {
+        println!("[persist_auth:{}] Failed to save persistence: {}", instance_id, e);
+        return Ok(AuthCommandResult {
+            success: false,
+            error: Some(format!("Failed to save persistence: {}", e)),
+        });
+    }
+
+    Ok(AuthCommandResult {
+        success: true,
+        error: None,
+    })
+}
+
+/// How often adopted servers are checked for having exited
+const ADOPTED_POLL_INTERVAL_SECS: u64 = 2;
+
+/// Re-adopt servers that a previous app session started and left running, so they
+/// show as running and can be monitored and stopped (called once after app setup).
+/// A recorded PID is only adopted if that process still runs the instance's server jar,
+/// so a PID reused by an unrelated process is never signaled.
+pub async fn adopt_orphaned_servers(app: AppHandle) {
+    let pool = match app.try_state::<DbPool>() {
+        Some(p) => p.inner().clone(),
+        None => return,
+    };
+    let (Some(state), Some(metrics_state)) = (
+        app.try_state::<Arc<Mutex<ServerState>>>().map(|s| s.inner().clone()),
+        app.try_state::<Arc<Mutex<MetricsState>>>().map(|m| m.inner().clone()),
+    ) else {
+        return;
+    };
+
+    let records = match database::get_running_servers(&pool).await {
+        Ok(r) => r,
+        Err(e) => {
+            println!("[adopt] ERROR: Failed to get running servers: {}", e);
+            return;
+        }
+    };
+
+    for record in records {
+        let instance = match database::get_instance_by_id(&pool, &record.instance_id).await {
+            Ok(Some(i)) => i,
+            _ => {
+                let _ = database::clear_running_server(&pool, &record.instance_id).await;
+                continue;
+            }
+        };
+
+        let pid = record.pid as u32;
+        let jar = Path::new(&instance.path).join("Server").join("HytaleServer.jar");
+        let metrics = metrics_state.clone();
+        let alive = tokio::task::spawn_blocking(move || find_processes_running_jar(&metrics, &jar).contains(&pid))
+            .await
+            .unwrap_or(false);
+
+        if !alive {
+            let _ = database::clear_running_server(&pool, &record.instance_id).await;
+            continue;
+        }
+
+        let started_at = DateTime::parse_from_rfc3339(&record.started_at)
+            .map(|t| t.with_timezone(&Utc))
+            .unwrap_or_else(|_| Utc::now());
+        println!("[adopt] Re-adopted {} (PID {})", instance.name, pid);
+        state.lock().unwrap().adopted.insert(
+            instance.id.clone(),
+            AdoptedProcess {
+                pid,
+                instance_path: instance.path.clone(),
+                started_at,
+            },
+        );
+
+        let _ = app.emit("server-status-change", ServerStatusInfo {
+            status: ServerStatus::Running,
+            instance_id: instance.id.clone(),
+            pid: Some(pid),
+            started_at: Some(started_at.to_rfc3339()),
+        });
+
+        watch_adopted_server(app.clone(), state.clone(), metrics_state.clone(), instance.id, pid);
+    }
+}
+
+/// Poll an adopted server until it exits or is stopped, then forget it
+fn watch_adopted_server(
+    app: AppHandle,
+    state: Arc<Mutex<ServerState>>,
+    metrics_state: Arc<Mutex<MetricsState>>,
+    instance_id: String,
+    pid: u32,
+) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(ADOPTED_POLL_INTERVAL_SECS)).await;
+
+            // Gone from state means stop_server handled it
+            let still_adopted = state
+                .lock()
+                .unwrap()
+                .adopted
+                .get(&instance_id)
+                .is_some_and(|adopted| adopted.pid == pid);
+            if !still_adopted {
+                break;
+            }
+
+            let metrics = metrics_state.clone();
+            let alive = tokio::task::spawn_blocking(move || is_process_alive(&metrics, pid))
+                .await
+                .unwrap_or(true);
+            if alive {
+                continue;
+            }
+
+            println!("[adopt:{}] Adopted PID {} exited", instance_id, pid);
+            state.lock().unwrap().adopted.remove(&instance_id);
+            save_running_server(&app, &instance_id, None);
+            let _ = app.emit("server-status-change", ServerStatusInfo {
+                status: ServerStatus::Stopped,
+                instance_id: instance_id.clone(),
+                pid: None,
+                started_at: None,
+            });
+            break;
+        }
+    });
+}
+
+/// Delay between autostarted servers so they don't all hit the disk at once
+const AUTOSTART_STAGGER_SECS: u64 = 5;
+
+/// Start all instances flagged for autostart (called once after app setup)
+pub async fn autostart_instances(app: AppHandle) {
+    let pool = match app.try_state::<DbPool>() {
+        Some(p) => p.inner().clone(),
+        None => return,
+    };
+
+    let disabled = database::get_setting(&pool, "autostart_disabled")
+        .await
+        .ok()
+        .flatten()
+        .map(|v| v == "true")
+        .unwrap_or(false);
+
+    if disabled {
+        println!("[autostart] Autostart is disabled, skipping");
+        return;
+    }
+
+    let instances = match database::get_autostart_instances(&pool).await {
+        Ok(i) => i,
+        Err(e) => {
+            println!("[autostart] ERROR: Failed to get instances: {}", e);
+            return;
+        }
+    };
+
+    for (i, instance) in instances.into_iter().enumerate() {
+        if i > 0 {
+            tokio::time::sleep(std::time::Duration::from_secs(AUTOSTART_STAGGER_SECS)).await;
+        }
+
+        println!("[autostart] Starting instance: {} ({})", instance.name, instance.id);
+        let state = app.state::<Arc<Mutex<ServerState>>>();
+        match start_server(
+            app.clone(),
+            state,
+            instance.id.clone(),
+            instance.path,
+            instance.java_path,
+            instance.jvm_args,
+            instance.server_args,
+            Some(parse_env_vars(instance.env_vars.as_deref())),
+            None,
+            None,
+            None,
+        )
+        .await
+        {
+            Ok(result) if !result.success => {
+                println!("[autostart] Failed to start {}: {:?}", instance.id, result.error);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Servers launched at once by start_all_servers unless a limit is given
+const BULK_START_MAX_CONCURRENCY: usize = 2;
+
+/// Start every instance that isn't already running, using its stored settings.
+/// At most `max_concurrency` starts are in flight, each holding its slot for the
+/// autostart stagger delay. Emits `bulk-operation-progress` as each one finishes.
+#[tauri::command]
+pub async fn start_all_servers(
+    app: AppHandle,
+    state: State<'_, Arc<Mutex<ServerState>>>,
+    max_concurrency: Option<usize>,
+) -> Result<Vec<BulkOperationResult>, ()> {
+    let pool = match app.try_state::<DbPool>() {
+        Some(p) => p.inner().clone(),
+        None => return Ok(Vec::new()),
+    };
+
+    let instances = match database::get_all_instances(&pool).await {
+        Ok(i) => i,
+        Err(e) => {
+            println!("[start_all_servers] ERROR: Failed to get instances: {}", e);
+            return Ok(Vec::new());
+        }
+    };
+
+    let instances: Vec<_> = {
+        let state_guard = state.lock().unwrap();
+        instances
+            .into_iter()
+            .filter(|i| !state_guard.is_running(&i.id))
+            .collect()
+    };
+
+    let total = instances.len();
+    let max_concurrency = max_concurrency.unwrap_or(BULK_START_MAX_CONCURRENCY).max(1);
+    println!("[start_all_servers] Starting {} instances ({} at a time)", total, max_concurrency);
+
+    let slots = Arc::new(tokio::sync::Semaphore::new(max_concurrency));
+    let completed = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+    let handles: Vec<_> = instances
+        .into_iter()
+        .map(|instance| {
+            let app = app.clone();
+            let slots = slots.clone();
+            let completed = completed.clone();
+            tauri::async_runtime::spawn(async move {
+                let _slot = slots.acquire_owned().await.ok();
+
+                let state = app.state::<Arc<Mutex<ServerState>>>();
+                let result = match start_server(
+                    app.clone(),
+                    state,
+                    instance.id.clone(),
+                    instance.path,
+                    instance.java_path,
+                    instance.jvm_args,
+                    instance.server_args,
+                    Some(parse_env_vars(instance.env_vars.as_deref())),
+                    None,
+                    None,
+                    None,
+                )
+                .await
+                {
+                    Ok(r) => BulkOperationResult {
+                        instance_id: instance.id,
+                        success: r.success,
+                        error: r.error,
+                    },
+                    Err(_) => BulkOperationResult {
+                        instance_id: instance.id,
+                        success: false,
+                        error: Some("Failed to start server".to_string()),
+                    },
+                };
+
+                emit_bulk_progress(&app, "start", &result, &completed, total);
+
+                // Keep the slot a little longer so the next server doesn't compete with this one's startup I/O
+                if result.success {
+                    tokio::time::sleep(std::time::Duration::from_secs(AUTOSTART_STAGGER_SECS)).await;
+                }
+                result
+            })
+        })
+        .collect();
+
+    Ok(collect_bulk_results("start_all_servers", handles).await)
+}
+
+/// Stop every running server in parallel.
+/// Emits `bulk-operation-progress` as each one finishes.
+#[tauri::command]
+pub async fn stop_all_servers(
+    app: AppHandle,
+    state: State<'_, Arc<Mutex<ServerState>>>,
+) -> Result<Vec<BulkOperationResult>, ()> {
+    let instance_ids: Vec<String> = {
+        let state_guard = state.lock().unwrap();
+        state_guard.processes.keys().chain(state_guard.adopted.keys()).cloned().collect()
+    };
+
+    let total = instance_ids.len();
+    println!("[stop_all_servers] Stopping {} servers", total);
+
+    let completed = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+    let handles: Vec<_> = instance_ids
+        .into_iter()
+        .map(|instance_id| {
+            let app = app.clone();
+            let completed = completed.clone();
+            tauri::async_runtime::spawn(async move {
+                let state = app.state::<Arc<Mutex<ServerState>>>();
+                let result = match stop_server(app.clone(), state, instance_id.clone()).await {
+                    Ok(r) => BulkOperationResult {
+                        instance_id,
+                        success: r.success,
+                        error: r.error,
+                    },
+                    Err(_) => BulkOperationResult {
+                        instance_id,
+                        success: false,
+                        error: Some("Failed to stop server".to_string()),
+                    },
+                };
+
+                emit_bulk_progress(&app, "stop", &result, &completed, total);
+                result
+            })
+        })
+        .collect();
+
+    Ok(collect_bulk_results("stop_all_servers", handles).await)
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
 
+fn emit_bulk_progress(
+    app: &AppHandle,
+    operation: &str,
+    result: &BulkOperationResult,
+    completed: &std::sync::atomic::AtomicUsize,
+    total: usize,
+) {
+    let completed = completed.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+    let _ = app.emit(
+        "bulk-operation-progress",
+        BulkOperationProgress {
+            operation: operation.to_string(),
+            result: result.clone(),
+            completed,
+            total,
+        },
+    );
+}
+
+/// Await bulk operation tasks, keeping their original order
+async fn collect_bulk_results(
+    tag: &str,
+    handles: Vec<tauri::async_runtime::JoinHandle<BulkOperationResult>>,
+) -> Vec<BulkOperationResult> {
+    let mut results = Vec::with_capacity(handles.len());
+    for handle in handles {
+        match handle.await {
+            Ok(result) => results.push(result),
+            Err(e) => println!("[{}] Task failed: {}", tag, e),
+        }
+    }
+
+    let failed = results.iter().filter(|r| !r.success).count();
+    println!("[{}] Done: {} succeeded, {} failed", tag, results.len() - failed, failed);
+    results
+}
+
+/// Split a command line into arguments. Whitespace separates arguments; single quotes
+/// keep text literally and double quotes allow \" and \\ escapes, like a POSIX shell.
+pub fn split_args(input: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => return Err("unterminated single quote".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\')) => current.push(c),
+                            Some(c) => {
+                                current.push('\\');
+                                current.push(c);
+                            }
+                            None => return Err("unterminated double quote".to_string()),
+                        },
+                        Some(c) => current.push(c),
+                        None => return Err("unterminated double quote".to_string()),
+                    }
+                }
+            }
+            c if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            c => {
+                in_arg = true;
+                current.push(c);
+            }
+        }
+    }
+
+    if in_arg {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+/// Build the command that launches Java, through `wrapper` when given.
+/// On Unix the wrapper is a command line (e.g. "taskset -c 0,1") run with Java as its last argument.
+#[cfg(not(windows))]
+fn launch_command(java_exe: &str, wrapper: Option<&str>) -> Result<Command, String> {
+    let tokens = split_args(wrapper.unwrap_or_default())?;
+    let Some((program, args)) = tokens.split_first() else {
+        return Ok(Command::new(java_exe));
+    };
+
+    let mut cmd = Command::new(program);
+    cmd.args(args).arg(java_exe);
+    Ok(cmd)
+}
+
+/// Build the command that launches Java, through `wrapper` when given.
+/// On Windows the wrapper is a priority class (idle, below_normal, normal, above_normal, high),
+/// also accepted in `start` form like "/belownormal".
+#[cfg(windows)]
+fn launch_command(java_exe: &str, wrapper: Option<&str>) -> Result<Command, String> {
+    use std::os::windows::process::CommandExt;
+
+    let mut cmd = Command::new(java_exe);
+    let priority = wrapper.unwrap_or_default().trim().trim_start_matches('/').to_lowercase();
+    let flags = match priority.replace('_', "").as_str() {
+        "" => return Ok(cmd),
+        "idle" | "low" => 0x0000_0040,
+        "belownormal" => 0x0000_4000,
+        "normal" => 0x0000_0020,
+        "abovenormal" => 0x0000_8000,
+        "high" => 0x0000_0080,
+        _ => return Err(format!("unknown priority '{}'", priority)),
+    };
+    cmd.creation_flags(flags);
+    Ok(cmd)
+}
+
+/// Check that a launch wrapper can be used to start a server
+pub fn validate_launch_wrapper(wrapper: &str) -> Result<(), String> {
+    launch_command("java", Some(wrapper)).map(|_| ())
+}
+
+/// Add an exit to an instance's history and return how many abnormal
+/// exits happened within the crash loop window. Exits requested through
+/// stop_server are never abnormal.
+fn record_exit(
+    state: &mut ServerState,
+    instance_id: &str,
+    code: Option<i32>,
+    signal: Option<i32>,
+    stop_requested: bool,
+) -> usize {
+    let now = Utc::now();
+    let history = state.exit_history.entry(instance_id.to_string()).or_default();
+    if history.len() >= EXIT_HISTORY_CAPACITY {
+        history.pop_front();
+    }
+    history.push_back(ExitRecord {
+        exited_at: now,
+        code,
+        signal,
+        abnormal: !stop_requested && code != Some(0),
+    });
+
+    history
+        .iter()
+        .filter(|exit| exit.abnormal && (now - exit.exited_at).num_seconds() <= CRASH_LOOP_WINDOW_SECS)
+        .count()
+}
+
+/// Memory kept free for the OS and the app when budgeting server heaps
+const MEMORY_GUARD_RESERVE_MB: u64 = 1024;
+
+/// Compare the -Xmx of a server about to start plus those already running against
+/// physical memory minus MEMORY_GUARD_RESERVE_MB. Physical rather than available memory
+/// is used because running servers' heaps are already missing from the available figure.
+/// Returns a (non-blocking) warning when over budget; servers without -Xmx aren't counted.
+fn check_memory_budget(
+    app: &AppHandle,
+    state: &Mutex<ServerState>,
+    instance_id: &str,
+    jvm_args: Option<&str>,
+) -> Option<ServerMemoryWarningEvent> {
+    let requested_mb = max_heap_mb(jvm_args?)?;
+    let metrics_state = app.try_state::<Arc<Mutex<MetricsState>>>()?;
+    let (total_mb, _) = memory_snapshot_mb(&metrics_state);
+    if total_mb == 0 {
+        return None;
+    }
+
+    let running_mb: u64 = {
+        let state_guard = state.lock().unwrap();
+        state_guard
+            .processes
+            .iter()
+            .filter(|(id, _)| id.as_str() != instance_id)
+            .filter_map(|(_, process_arc)| {
+                let process = process_arc.lock().unwrap();
+                process.launch_config.jvm_args.as_deref().and_then(max_heap_mb)
+            })
+            .sum()
+    };
+
+    let limit_mb = total_mb.saturating_sub(MEMORY_GUARD_RESERVE_MB);
+    if running_mb + requested_mb <= limit_mb {
+        return None;
+    }
+
+    Some(ServerMemoryWarningEvent {
+        instance_id: instance_id.to_string(),
+        requested_mb,
+        running_mb,
+        limit_mb,
+        blocked: false,
+    })
+}
+
+/// Whether starts over the memory budget are refused instead of warned about
+async fn get_memory_guard_block_setting(pool: &DbPool) -> bool {
+    database::get_setting(pool, "memory_guard_block")
+        .await
+        .ok()
+        .flatten()
+        .map(|v| v == "true")
+        .unwrap_or(false)
+}
+
+/// Configured console history size, falling back to the default
+async fn get_output_buffer_lines(pool: &DbPool) -> usize {
+    database::get_setting(pool, "console_buffer_lines")
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| v.parse::<usize>().ok())
+        .map(|lines| lines.clamp(1, MAX_OUTPUT_BUFFER_LINES))
+        .unwrap_or(DEFAULT_OUTPUT_BUFFER_LINES)
+}
+
+/// Write a line to a running server's stdin
+fn send_to_stdin(state: &Mutex<ServerState>, instance_id: &str, command: &str) -> Result<(), String> {
+    let state_guard = state.lock().unwrap();
+    let process_arc = state_guard
+        .processes
+        .get(instance_id)
+        .ok_or_else(|| "Server is not running".to_string())?;
+    let process = process_arc.lock().unwrap();
+
+    match process.stdin_tx {
+        Some(ref tx) => tx
+            .send(command.to_string())
+            .map_err(|e| format!("Failed to send command: {}", e)),
+        None => Err("Server has no stdin channel".to_string()),
+    }
+}
+
 /// Strip ANSI escape codes from a string
 fn strip_ansi_codes(s: &str) -> String {
     // Simple regex-free ANSI stripper for escape sequences like \x1b[...m
@@ -797,6 +2952,209 @@ fn strip_ansi_codes(s: &str) -> String {
     result
 }
 
+/// Names for the 8 basic ANSI colors, in SGR order
+const ANSI_COLOR_NAMES: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+
+/// Split a line with ANSI SGR escape codes into styled spans.
+/// Returns None when the line has no escape codes so plain lines stay cheap.
+fn parse_ansi_spans(s: &str) -> Option<Vec<OutputSpan>> {
+    if !s.contains('\x1b') {
+        return None;
+    }
+
+    let mut spans: Vec<OutputSpan> = Vec::new();
+    let mut text = String::new();
+    let mut color: Option<String> = None;
+    let mut bold = false;
+    let mut chars = s.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            text.push(c);
+            continue;
+        }
+
+        // Only CSI sequences (ESC [ ... final) carry styling; drop anything else
+        if chars.peek() != Some(&'[') {
+            continue;
+        }
+        chars.next();
+
+        let mut params = String::new();
+        let mut final_byte = None;
+        for next in chars.by_ref() {
+            if ('@'..='~').contains(&next) {
+                final_byte = Some(next);
+                break;
+            }
+            params.push(next);
+        }
+
+        if final_byte != Some('m') {
+            continue;
+        }
+
+        // Style is about to change; close the current span
+        if !text.is_empty() {
+            spans.push(OutputSpan {
+                text: std::mem::take(&mut text),
+                color: color.clone(),
+                bold,
+            });
+        }
+
+        apply_sgr(&params, &mut color, &mut bold);
+    }
+
+    if !text.is_empty() {
+        spans.push(OutputSpan { text, color, bold });
+    }
+
+    Some(spans)
+}
+
+/// Apply SGR parameters (the part between `ESC[` and `m`) to the current style
+fn apply_sgr(params: &str, color: &mut Option<String>, bold: &mut bool) {
+    let codes: Vec<u32> = if params.is_empty() {
+        vec![0]
+    } else {
+        params.split(';').map(|p| p.parse().unwrap_or(0)).collect()
+    };
+
+    let mut i = 0;
+    while i < codes.len() {
+        match codes[i] {
+            0 => {
+                *color = None;
+                *bold = false;
+            }
+            1 => *bold = true,
+            22 => *bold = false,
+            code @ 30..=37 => *color = Some(ANSI_COLOR_NAMES[(code - 30) as usize].to_string()),
+            39 => *color = None,
+            code @ 90..=97 => *color = Some(format!("bright_{}", ANSI_COLOR_NAMES[(code - 90) as usize])),
+            // Extended colors: 5;n (256-color) or 2;r;g;b (truecolor)
+            code @ (38 | 48) => {
+                let (parsed, used) = match codes.get(i + 1) {
+                    Some(5) => (codes.get(i + 2).map(|&n| ansi_256_color(n)), 2),
+                    Some(2) => match (codes.get(i + 2), codes.get(i + 3), codes.get(i + 4)) {
+                        (Some(&r), Some(&g), Some(&b)) => {
+                            (Some(format!("#{:02x}{:02x}{:02x}", r.min(255), g.min(255), b.min(255))), 4)
+                        }
+                        _ => (None, 4),
+                    },
+                    _ => (None, 0),
+                };
+                // Background colors are parsed only to skip their parameters
+                if code == 38 {
+                    if let Some(c) = parsed {
+                        *color = Some(c);
+                    }
+                }
+                i += used;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+}
+
+/// Convert a 256-color palette index to a color name or hex value
+fn ansi_256_color(n: u32) -> String {
+    match n {
+        0..=7 => ANSI_COLOR_NAMES[n as usize].to_string(),
+        8..=15 => format!("bright_{}", ANSI_COLOR_NAMES[(n - 8) as usize]),
+        16..=231 => {
+            let levels = [0, 95, 135, 175, 215, 255];
+            let idx = n - 16;
+            format!(
+                "#{:02x}{:02x}{:02x}",
+                levels[(idx / 36) as usize],
+                levels[(idx / 6 % 6) as usize],
+                levels[(idx % 6) as usize]
+            )
+        }
+        _ => {
+            let gray = 8 + 10 * (n.min(255) - 232);
+            format!("#{:02x}{:02x}{:02x}", gray, gray, gray)
+        }
+    }
+}
+
+/// Resolve the host and UDP port the server will bind to.
+/// `--bind` in the server arguments wins, then config.json, then the default port.
+pub fn resolve_bind_address(instance_path: &str, server_args: Option<&str>) -> (String, u16) {
+    let default_host = "0.0.0.0".to_string();
+
+    if let Some(args) = server_args {
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        for (i, part) in parts.iter().enumerate() {
+            let bind = if *part == "--bind" || *part == "-b" {
+                parts.get(i + 1).copied()
+            } else {
+                part.strip_prefix("--bind=")
+            };
+
+            if let Some(bind) = bind {
+                if let Some(port) = parse_bind_port(bind) {
+                    let host = bind
+                        .rsplit_once(':')
+                        .map(|(h, _)| h.trim_start_matches('[').trim_end_matches(']').to_string())
+                        .filter(|h| !h.is_empty())
+                        .unwrap_or(default_host);
+                    return (host, port);
+                }
+            }
+        }
+    }
+
+    let port = read_config_port(instance_path).unwrap_or(DEFAULT_SERVER_PORT);
+    (default_host, port)
+}
+
+/// Remember (or forget, with None) the PID of an instance's server process,
+/// so it can be re-adopted if the app exits while the server keeps running
+fn save_running_server(app: &AppHandle, instance_id: &str, running: Option<(u32, DateTime<Utc>)>) {
+    let app = app.clone();
+    let instance_id = instance_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        let pool = match app.try_state::<DbPool>() {
+            Some(p) => p.inner().clone(),
+            None => return,
+        };
+
+        let result = match running {
+            Some((pid, started_at)) => {
+                database::record_running_server(&pool, &instance_id, pid, &started_at.to_rfc3339()).await
+            }
+            None => database::clear_running_server(&pool, &instance_id).await,
+        };
+        if let Err(e) = result {
+            println!("[server:{}] Failed to save running process: {}", instance_id, e);
+        }
+    });
+}
+
+/// Persist an auth status change reported in server output, so the instance
+/// list stays accurate across restarts. The profile name is kept unless given.
+fn save_auth_status(app: &AppHandle, instance_id: &str, status: &str, profile_name: Option<String>) {
+    let app = app.clone();
+    let instance_id = instance_id.to_string();
+    let status = status.to_string();
+    tauri::async_runtime::spawn(async move {
+        let pool = match app.try_state::<DbPool>() {
+            Some(p) => p.inner().clone(),
+            None => return,
+        };
+
+        if let Err(e) =
+            database::update_instance_auth(&pool, &instance_id, Some(status.clone()), None, profile_name).await
+        {
+            println!("[auth:{}] Failed to save auth status '{}': {}", instance_id, status, e);
+        }
+    });
+}
+
 /// Parse authentication event from server output
 fn parse_auth_event(instance_id: &str, line: &str) -> Option<AuthEvent> {
     // Strip ANSI codes first (Hytale server uses colors)
@@ -889,3 +3247,220 @@ fn parse_player_leave(line: &str) -> Option<(String, String)> {
     }
     None
 }
+
+/// Check whether a line marks the end of server startup
+/// Matches lines ending in "Server Booted!" or "Server started", or a "Done (12.3s)!" line.
+/// Markers must end the message so chat or plugin output mentioning them doesn't count.
+fn is_server_ready_line(line: &str) -> bool {
+    let clean_line = strip_ansi_codes(line);
+    let lower = clean_line.to_lowercase();
+    let message = lower.trim_end().trim_end_matches(['!', '.']);
+
+    const READY_SUFFIXES: [&str; 2] = ["server booted", "server started"];
+    if READY_SUFFIXES.iter().any(|suffix| message.ends_with(suffix)) {
+        return true;
+    }
+
+    // "Done (<seconds>s)!" as the start of the message, after any log prefix like "[12:00:00 INFO]: "
+    let Some(idx) = lower.find("done (") else {
+        return false;
+    };
+    let before = lower[..idx].trim_end();
+    if !(before.is_empty() || before.ends_with(']') || before.ends_with(':')) {
+        return false;
+    }
+    let rest = &lower[idx + "done (".len()..];
+    let seconds: String = rest.chars().take_while(|c| c.is_ascii_digit() || *c == '.').collect();
+    !seconds.is_empty() && rest[seconds.len()..].starts_with("s)")
+}
+
+/// Parse the server tick rate from server output
+/// Matches lines like "TPS: 29.8", "tps=30" or "Tick time: 33.4ms" (converted to TPS).
+/// Returns None if the line doesn't look like a tick report, so format changes are harmless.
+fn parse_tps(line: &str) -> Option<f32> {
+    let clean_line = strip_ansi_codes(line);
+    let lower = clean_line.to_lowercase();
+
+    // Direct TPS report (skip matches inside words like "https")
+    for (idx, _) in lower.match_indices("tps") {
+        let preceded_by_word = lower[..idx]
+            .chars()
+            .next_back()
+            .map(|c| c.is_ascii_alphanumeric())
+            .unwrap_or(false);
+        if preceded_by_word {
+            continue;
+        }
+        if let Some(value) = parse_leading_number(&lower[idx + 3..]) {
+            if value.is_finite() && value >= 0.0 {
+                return Some(value);
+            }
+        }
+    }
+
+    // Tick duration report (milliseconds per tick)
+    let tick_keys = ["mspt", "tick time", "tick duration", "avg tick"];
+    for key in tick_keys {
+        if let Some(idx) = lower.find(key) {
+            let rest = &lower[idx + key.len()..];
+            if let Some(ms) = parse_leading_number(rest) {
+                if ms > 0.0 && ms.is_finite() {
+                    return Some(1000.0 / ms);
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Parse the first number after optional separators (":", "=", spaces)
+fn parse_leading_number(s: &str) -> Option<f32> {
+    let trimmed = s.trim_start_matches(|c: char| c == ':' || c == '=' || c.is_whitespace());
+    let number: String = trimmed
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+
+    if number.is_empty() {
+        return None;
+    }
+
+    number.parse::<f32>().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_tps_reads_tps_reports() {
+        assert_eq!(parse_tps("[Server] TPS: 29.8"), Some(29.8));
+        assert_eq!(parse_tps("world tps=30"), Some(30.0));
+        assert_eq!(parse_tps("Tick time: 50ms"), Some(20.0));
+    }
+
+    #[test]
+    fn parse_tps_strips_ansi_codes() {
+        assert_eq!(parse_tps("\x1b[32m[Server] TPS: 19.5\x1b[0m"), Some(19.5));
+        assert_eq!(parse_tps("\x1b[1;33mMSPT: 40\x1b[0m"), Some(25.0));
+    }
+
+    #[test]
+    fn parse_tps_ignores_other_lines() {
+        assert_eq!(parse_tps("[Server] Player Steve joined the game"), None);
+        assert_eq!(parse_tps("Downloading https://example.com/2.0/server.zip"), None);
+        assert_eq!(parse_tps("TPS is looking good"), None);
+        assert_eq!(parse_tps("Tick time: 0ms"), None);
+        assert_eq!(parse_tps(""), None);
+    }
+
+    #[test]
+    fn ready_line_matches_boot_messages() {
+        assert!(is_server_ready_line("[2026/01/15 12:00:00 INFO] [HytaleServer] Hytale Server Booted!"));
+        assert!(is_server_ready_line("\x1b[32m[Server] Server started\x1b[0m"));
+        assert!(is_server_ready_line("[12:00:00 INFO]: Done (12.345s)! For help, type \"help\""));
+        assert!(is_server_ready_line("Done (3s)!"));
+    }
+
+    #[test]
+    fn ready_line_ignores_mentions() {
+        assert!(!is_server_ready_line("[Chat] <Steve> server started lagging again"));
+        assert!(!is_server_ready_line("[Chat] <Steve> I'm done (finally) with the castle"));
+        assert!(!is_server_ready_line("[Backup] Backup done (world1)"));
+        assert!(!is_server_ready_line("[Chat] <Steve> almost done (5s) left"));
+        assert!(!is_server_ready_line("[Server] Starting server..."));
+    }
+
+    /// Start `cat` as a stand-in server with the same stdin writer and stdout reader
+    /// threads start_server uses: lines sent to stdin come back to the subscribers
+    #[cfg(unix)]
+    fn spawn_stub_process(state: &Mutex<ServerState>, instance_id: &str) -> Arc<Mutex<ServerProcess>> {
+        let mut child = Command::new("cat")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("failed to spawn cat");
+        let mut stdin = child.stdin.take().unwrap();
+        let stdout = child.stdout.take().unwrap();
+        let (stdin_tx, stdin_rx) = std::sync::mpsc::channel::<String>();
+        let output_subscribers: OutputSubscribers = Arc::new(Mutex::new(Vec::new()));
+
+        let writer = std::thread::spawn(move || {
+            while let Ok(command) = stdin_rx.recv() {
+                if stdin.write_all(format!("{}\n", command).as_bytes()).is_err() {
+                    break;
+                }
+            }
+        });
+        let subscribers = output_subscribers.clone();
+        let reader = std::thread::spawn(move || {
+            for line in lossy_lines(BufReader::new(stdout)).map_while(Result::ok) {
+                subscribers.lock().unwrap().retain(|tx| tx.send(line.clone()).is_ok());
+            }
+        });
+
+        let process = Arc::new(Mutex::new(ServerProcess {
+            child,
+            instance_id: instance_id.to_string(),
+            instance_path: String::new(),
+            started_at: Utc::now(),
+            stdin_tx: Some(stdin_tx),
+            online_players: HashMap::new(),
+            tps: None,
+            ready: false,
+            stop_requested: false,
+            recent_lines: Arc::new(Mutex::new(VecDeque::new())),
+            output_buffer: Arc::new(Mutex::new(OutputBuffer::new(10))),
+            output_filter: Arc::new(Mutex::new(OutputFilter::default())),
+            output_batcher: Arc::new(Mutex::new(OutputBatcher::default())),
+            output_subscribers,
+            io_threads: vec![writer, reader],
+            launch_config: LaunchConfig {
+                instance_path: String::new(),
+                working_dir: String::new(),
+                java_path: "cat".to_string(),
+                jvm_args: None,
+                server_args: None,
+                env_vars: HashMap::new(),
+                launch_wrapper: None,
+            },
+        }));
+        state.lock().unwrap().processes.insert(instance_id.to_string(), process.clone());
+        process
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stop_start_cycles_release_threads_and_channels() {
+        let state = Mutex::new(ServerState::new());
+
+        for _ in 0..5 {
+            let process_arc = spawn_stub_process(&state, "stub");
+            let (tx, rx) = std::sync::mpsc::channel();
+            let stdin_tx = {
+                let process = process_arc.lock().unwrap();
+                process.output_subscribers.lock().unwrap().push(tx);
+                process.stdin_tx.clone().unwrap()
+            };
+            stdin_tx.send("stop".to_string()).unwrap();
+            drop(stdin_tx);
+            assert_eq!(rx.recv_timeout(std::time::Duration::from_secs(5)).unwrap(), "stop");
+
+            // Same teardown as stop_server once the process has been told to stop
+            close_process_io(&process_arc);
+            let io_threads = detach_process(&state, "stub", &process_arc);
+            assert_eq!(join_io_threads("stub", io_threads), 0);
+
+            let mut process = process_arc.lock().unwrap();
+            assert!(process.child.wait().unwrap().success());
+            assert!(process.stdin_tx.is_none());
+            assert!(process.io_threads.is_empty());
+            assert!(process.output_subscribers.lock().unwrap().is_empty());
+            // Only the process itself still holds the subscriber list
+            assert_eq!(Arc::strong_count(&process.output_subscribers), 1);
+            assert!(rx.recv().is_err());
+            assert!(state.lock().unwrap().processes.is_empty());
+        }
+    }
+}
@@ -1,6 +1,37 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Manager};
+
+use crate::database::{self, DbPool};
+
+/// How long a cached Java detection result is reused
+const JAVA_CACHE_TTL_SECS: i64 = 24 * 60 * 60;
+
+/// Minimum Java major version the Hytale server runs on
+pub(crate) const REQUIRED_JAVA_MAJOR: u32 = 25;
+
+/// How long a pre-launch Java version check is reused for the same executable
+const LAUNCH_JAVA_CHECK_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// A cached pre-launch Java version lookup
+struct LaunchJavaCheck {
+    java_path: String,
+    modified: Option<SystemTime>,  // Executable mtime, so an upgraded JDK is re-checked
+    checked_at: Instant,
+    major_version: u32,
+}
+
+static LAUNCH_JAVA_CHECKS: Mutex<Vec<LaunchJavaCheck>> = Mutex::new(Vec::new());
+
+/// Java detection result stored in the settings table
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedJavaInfo {
+    checked_at: String,
+    info: JavaInfo,
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct JavaInfo {
@@ -22,9 +53,58 @@ pub struct SystemPaths {
 }
 
 /// Detects Java installation and validates it's Java 25+
-/// First checks PATH, then scans common installation directories
+/// First checks PATH, then scans common installation directories.
+/// Valid results are cached in the database; pass `force` to rescan. Failures aren't
+/// cached, so installing Java is picked up on the next check.
 #[tauri::command]
-pub async fn check_java() -> JavaInfo {
+pub async fn check_java(app: AppHandle, force: Option<bool>) -> JavaInfo {
+    let pool = app.try_state::<DbPool>().map(|p| p.inner().clone());
+
+    if !force.unwrap_or(false) {
+        if let Some(ref pool) = pool {
+            if let Some(info) = get_cached_java_info(pool).await {
+                return info;
+            }
+        }
+    }
+
+    let info = detect_java().await;
+    if !info.is_valid {
+        return info;
+    }
+
+    if let Some(ref pool) = pool {
+        let cached = CachedJavaInfo {
+            checked_at: chrono::Utc::now().to_rfc3339(),
+            info,
+        };
+        if let Ok(json) = serde_json::to_string(&cached) {
+            if let Err(e) = database::set_setting(pool, "java_check_cache", &json).await {
+                println!("[check_java] WARNING: Failed to cache Java info: {}", e);
+            }
+        }
+        return cached.info;
+    }
+
+    info
+}
+
+/// Read a cached Java detection result if it's still within the TTL
+async fn get_cached_java_info(pool: &DbPool) -> Option<JavaInfo> {
+    let json = database::get_setting(pool, "java_check_cache").await.ok()??;
+    let cached: CachedJavaInfo = serde_json::from_str(&json).ok()?;
+    let checked_at = chrono::DateTime::parse_from_rfc3339(&cached.checked_at).ok()?;
+
+    let age = chrono::Utc::now().signed_duration_since(checked_at);
+    if age.num_seconds() < JAVA_CACHE_TTL_SECS {
+        Some(cached.info)
+    } else {
+        None
+    }
+}
+
+/// Run the full Java detection (PATH first, then common install directories)
+async fn detect_java() -> JavaInfo {
     // Run the blocking operations in a separate thread
     let result = tokio::task::spawn_blocking(move || {
         // First, try the default java in PATH
@@ -52,7 +132,7 @@ pub async fn check_java() -> JavaInfo {
             vendor: None,
             is_valid: false,
             java_path: None,
-            error: Some("Java not found. Please install Java 25 or higher.".to_string()),
+            error: Some(format!("Java not found. Please install Java {} or higher.", REQUIRED_JAVA_MAJOR)),
         }
     }).await;
 
@@ -73,7 +153,7 @@ pub async fn check_java() -> JavaInfo {
 }
 
 /// Check a specific java executable and return its info
-fn check_java_executable(java_path: &str) -> Option<JavaInfo> {
+pub fn check_java_executable(java_path: &str) -> Option<JavaInfo> {
     let output = Command::new(java_path)
         .arg("--version")
         .output()
@@ -103,7 +183,7 @@ fn check_java_executable(java_path: &str) -> Option<JavaInfo> {
 
     let vendor = lines.get(1).map(|line| extract_vendor(line));
 
-    let is_valid = major_version.map(|v| v >= 25).unwrap_or(false);
+    let is_valid = major_version.map(|v| v >= REQUIRED_JAVA_MAJOR).unwrap_or(false);
 
     Some(JavaInfo {
         installed: true,
@@ -114,7 +194,8 @@ fn check_java_executable(java_path: &str) -> Option<JavaInfo> {
         java_path: Some(java_path.to_string()),
         error: if !is_valid {
             Some(format!(
-                "Java 25 or higher is required. Found version: {}",
+                "Java {} or higher is required. Found version: {}",
+                REQUIRED_JAVA_MAJOR,
                 major_version.unwrap_or(0)
             ))
         } else {
@@ -123,6 +204,53 @@ fn check_java_executable(java_path: &str) -> Option<JavaInfo> {
     })
 }
 
+/// Make sure a Java executable is new enough to launch the server.
+/// Successful version lookups are cached per executable (invalidated when the file
+/// changes), so repeated starts don't pay for `java --version` each time.
+pub fn check_java_for_launch(java_path: &str) -> Result<(), String> {
+    let modified = std::fs::metadata(java_path).and_then(|m| m.modified()).ok();
+
+    let cached = LAUNCH_JAVA_CHECKS.lock().ok().and_then(|checks| {
+        checks
+            .iter()
+            .find(|c| {
+                c.java_path == java_path
+                    && c.modified == modified
+                    && c.checked_at.elapsed() < LAUNCH_JAVA_CHECK_TTL
+            })
+            .map(|c| c.major_version)
+    });
+
+    let major = match cached {
+        Some(major) => major,
+        None => {
+            let major = check_java_executable(java_path)
+                .and_then(|info| info.major_version)
+                .ok_or_else(|| format!("Java not found or not runnable: {}", java_path))?;
+
+            if let Ok(mut checks) = LAUNCH_JAVA_CHECKS.lock() {
+                checks.retain(|c| c.java_path != java_path);
+                checks.push(LaunchJavaCheck {
+                    java_path: java_path.to_string(),
+                    modified,
+                    checked_at: Instant::now(),
+                    major_version: major,
+                });
+            }
+            major
+        }
+    };
+
+    if major < REQUIRED_JAVA_MAJOR {
+        return Err(format!(
+            "Java {}+ required, found {} ({})",
+            REQUIRED_JAVA_MAJOR, major, java_path
+        ));
+    }
+
+    Ok(())
+}
+
 /// Extract vendor name from java version output
 fn extract_vendor(line: &str) -> String {
     if line.contains("Temurin") {
@@ -402,3 +530,17 @@ fn get_hytale_launcher_path() -> Option<String> {
 
     None
 }
+
+/// Free space on the disk holding `path`, if it can be determined.
+/// The matching disk is the one with the longest mount point that prefixes the path.
+pub fn available_disk_space(path: &std::path::Path) -> Option<u64> {
+    let target = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+
+    disks
+        .list()
+        .iter()
+        .filter(|d| target.starts_with(d.mount_point()))
+        .max_by_key(|d| d.mount_point().as_os_str().len())
+        .map(|d| d.available_space())
+}
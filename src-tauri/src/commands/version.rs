@@ -1,15 +1,32 @@
 use serde::{Deserialize, Serialize};
-use std::process::Command;
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::Notify;
 
-use crate::database::{self, DbPool};
+use super::backup::DATABASE_UNAVAILABLE;
+use super::downloader::{
+    download_server_files, find_downloader_with_app, run_downloader, DownloadProgress, PRESERVED_CONFIG_FILES,
+};
+use super::notifier::{notify, WebhookEvent};
+use super::server::ServerState;
+use crate::database::{self, DbPool, Instance};
+
+/// Default interval between periodic version checks
+const DEFAULT_CHECK_INTERVAL_MINUTES: u64 = 30;
+
+fn default_check_interval_minutes() -> u64 {
+    DEFAULT_CHECK_INTERVAL_MINUTES
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VersionSettings {
     pub check_on_startup: bool,
     pub check_periodic: bool,
     pub check_on_server_start: bool,
+    #[serde(default = "default_check_interval_minutes")]
+    pub check_interval_minutes: u64,
 }
 
 impl Default for VersionSettings {
@@ -18,10 +35,26 @@ impl Default for VersionSettings {
             check_on_startup: true,
             check_periodic: false,
             check_on_server_start: true,
+            check_interval_minutes: DEFAULT_CHECK_INTERVAL_MINUTES,
         }
     }
 }
 
+/// Wakes the background version check task so it re-reads its settings
+pub struct VersionCheckNotifier(pub Arc<Notify>);
+
+impl VersionCheckNotifier {
+    pub fn new() -> Self {
+        Self(Arc::new(Notify::new()))
+    }
+}
+
+impl Default for VersionCheckNotifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VersionCheckResult {
     pub instance_id: String,
@@ -32,70 +65,144 @@ pub struct VersionCheckResult {
     pub version_unknown: bool,  // True if installed_version is None
 }
 
-/// Get the hytale-downloader executable name based on OS
-fn get_downloader_executable() -> &'static str {
-    if cfg!(target_os = "windows") {
-        "hytale-downloader-windows-amd64.exe"
-    } else if cfg!(target_os = "macos") {
-        "hytale-downloader-darwin-amd64"
-    } else {
-        "hytale-downloader-linux-amd64"
+/// Last successful available-version lookup, persisted across restarts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LastVersionCheck {
+    pub available_version: Option<String>,
+    pub checked_at: Option<String>,  // RFC 3339
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateInstanceResult {
+    pub success: bool,
+    pub previous_version: Option<String>,
+    pub new_version: Option<String>,
+    pub backup_path: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Returns true if version `a` is strictly newer than version `b`.
+/// Both are parsed as semver (a leading "v" is ignored); if either doesn't parse,
+/// falls back to a plain string inequality so unknown formats still surface updates.
+pub fn is_newer(a: &str, b: &str) -> bool {
+    let parse = |v: &str| semver::Version::parse(v.trim().trim_start_matches('v')).ok();
+
+    match (parse(a), parse(b)) {
+        (Some(va), Some(vb)) => va > vb,
+        _ => a.trim() != b.trim(),
     }
 }
 
-/// Find hytale-downloader path
-fn find_downloader(app: &AppHandle) -> Option<String> {
-    let exe_name = get_downloader_executable();
+/// Best-effort detection of the installed server version from
+/// `Server/HytaleServer.jar` (the `Implementation-Version` manifest entry)
+pub fn read_installed_version(instance_path: &str) -> Option<String> {
+    let jar_path = Path::new(instance_path).join("Server").join("HytaleServer.jar");
+    let file = std::fs::File::open(jar_path).ok()?;
+    let mut archive = zip::ZipArchive::new(file).ok()?;
+    let mut manifest = archive.by_name("META-INF/MANIFEST.MF").ok()?;
+
+    let mut content = String::new();
+    std::io::Read::read_to_string(&mut manifest, &mut content).ok()?;
+
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("Implementation-Version:"))
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
 
-    // Check app data directory
-    if let Ok(app_dir) = app.path().app_data_dir() {
-        let cli_path = app_dir.join(exe_name);
-        if cli_path.exists() {
-            return Some(cli_path.to_string_lossy().to_string());
+/// If an instance's version is unknown, try to read it from its server files and store it
+async fn fill_unknown_version(pool: &DbPool, instance: &mut Instance) {
+    if instance.installed_version.is_some() {
+        return;
+    }
+
+    let path = instance.path.clone();
+    let detected = tokio::task::spawn_blocking(move || read_installed_version(&path))
+        .await
+        .ok()
+        .flatten();
+
+    if let Some(version) = detected {
+        println!("[version] Detected version {} for {}", version, instance.id);
+        if let Err(e) = database::update_instance_version(pool, &instance.id, &version).await {
+            println!("[version] WARNING: Failed to store detected version: {}", e);
         }
+        instance.installed_version = Some(version);
     }
+}
 
-    // Check PATH
-    let which_cmd = if cfg!(target_os = "windows") {
-        "where"
-    } else {
-        "which"
-    };
+/// How long `hytale-downloader -print-version` may run before we give up
+const PRINT_VERSION_TIMEOUT: Duration = Duration::from_secs(30);
 
-    if let Ok(output) = Command::new(which_cmd).arg(exe_name).output() {
-        if output.status.success() {
-            if let Some(path) = String::from_utf8_lossy(&output.stdout).lines().next() {
-                let path = path.trim().to_string();
-                if !path.is_empty() {
-                    return Some(path);
-                }
+/// How long a fetched available version is reused
+const AVAILABLE_VERSION_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Last successfully fetched available version
+static AVAILABLE_VERSION_CACHE: Mutex<Option<(Instant, String)>> = Mutex::new(None);
+
+/// Get the available game version using hytale-downloader -print-version.
+/// Results are cached briefly; a hung downloader is killed after a timeout.
+async fn get_available_version(app: &AppHandle) -> Option<String> {
+    if let Ok(cache) = AVAILABLE_VERSION_CACHE.lock() {
+        if let Some((fetched_at, ref version)) = *cache {
+            if fetched_at.elapsed() < AVAILABLE_VERSION_CACHE_TTL {
+                return Some(version.clone());
             }
         }
     }
 
-    None
+    let downloader_path = find_downloader_with_app(Some(app))?;
+
+    let version = match run_downloader(&downloader_path, &["-print-version"], PRINT_VERSION_TIMEOUT).await {
+        Ok(output) if output.success() => Some(output.stdout),
+        Ok(_) => None,
+        Err(e) => {
+            println!("[version] WARNING: {}, skipping version check", e);
+            None
+        }
+    }
+    .filter(|v| !v.is_empty())?;
+
+    if let Ok(mut cache) = AVAILABLE_VERSION_CACHE.lock() {
+        *cache = Some((Instant::now(), version.clone()));
+    }
+
+    Some(version)
 }
 
-/// Get the available game version using hytale-downloader -print-version
-async fn get_available_version(app: &AppHandle) -> Option<String> {
-    let downloader_path = find_downloader(app)?;
+/// Persist the latest available version and when it was fetched
+async fn record_version_check(pool: &DbPool, version: &str) {
+    let r1 = database::set_setting(pool, "last_available_version", version).await;
+    let r2 = database::set_setting(pool, "last_version_check_at", &chrono::Utc::now().to_rfc3339()).await;
+    if r1.is_err() || r2.is_err() {
+        println!("[version] WARNING: Failed to save last version check");
+    }
+}
+
+/// Get the last known available version and when it was checked, without running the downloader
+#[tauri::command]
+pub async fn get_last_version_check(app: AppHandle) -> LastVersionCheck {
+    let pool = match app.try_state::<DbPool>() {
+        Some(p) => p.inner().clone(),
+        None => {
+            return LastVersionCheck {
+                available_version: None,
+                checked_at: None,
+            };
+        }
+    };
 
-    tokio::task::spawn_blocking(move || {
-        Command::new(&downloader_path)
-            .arg("-print-version")
-            .output()
+    LastVersionCheck {
+        available_version: database::get_setting(&pool, "last_available_version")
+            .await
             .ok()
-            .and_then(|output| {
-                if output.status.success() {
-                    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
-                } else {
-                    None
-                }
-            })
-    })
-    .await
-    .ok()
-    .flatten()
+            .flatten(),
+        checked_at: database::get_setting(&pool, "last_version_check_at")
+            .await
+            .ok()
+            .flatten(),
+    }
 }
 
 /// Get version checking settings
@@ -127,10 +234,13 @@ pub async fn get_version_settings(app: AppHandle) -> VersionSettings {
         .map(|v| v == "true")
         .unwrap_or(true);
 
+    let check_interval_minutes = get_check_interval_minutes(&pool).await;
+
     VersionSettings {
         check_on_startup,
         check_periodic,
         check_on_server_start,
+        check_interval_minutes,
     }
 }
 
@@ -163,7 +273,30 @@ pub async fn set_version_settings(app: AppHandle, settings: VersionSettings) ->
     )
     .await;
 
-    r1.is_ok() && r2.is_ok() && r3.is_ok()
+    let r4 = database::set_setting(
+        &pool,
+        "version_check_interval_minutes",
+        &settings.check_interval_minutes.max(1).to_string(),
+    )
+    .await;
+
+    // Let the background task pick up the new interval immediately
+    if let Some(notifier) = app.try_state::<VersionCheckNotifier>() {
+        notifier.0.notify_one();
+    }
+
+    r1.is_ok() && r2.is_ok() && r3.is_ok() && r4.is_ok()
+}
+
+/// Read the periodic check interval in minutes (defaults to 30)
+async fn get_check_interval_minutes(pool: &DbPool) -> u64 {
+    database::get_setting(pool, "version_check_interval_minutes")
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|m| *m > 0)
+        .unwrap_or(DEFAULT_CHECK_INTERVAL_MINUTES)
 }
 
 /// Check all instances for version updates
@@ -183,6 +316,10 @@ pub async fn check_all_versions(app: AppHandle) -> Vec<VersionCheckResult> {
     let available_version = get_available_version(&app).await;
     println!("[version] Available version: {:?}", available_version);
 
+    if let Some(ref version) = available_version {
+        record_version_check(&pool, version).await;
+    }
+
     // Get all instances
     let instances = match database::get_all_instances(&pool).await {
         Ok(i) => i,
@@ -194,10 +331,12 @@ pub async fn check_all_versions(app: AppHandle) -> Vec<VersionCheckResult> {
 
     let mut results = Vec::new();
 
-    for instance in instances {
+    for mut instance in instances {
+        fill_unknown_version(&pool, &mut instance).await;
+
         let version_unknown = instance.installed_version.is_none();
         let update_available = match (&instance.installed_version, &available_version) {
-            (Some(installed), Some(available)) => installed != available,
+            (Some(installed), Some(available)) => is_newer(available, installed),
             _ => false, // Only mark as update available when both versions are known
         };
 
@@ -225,16 +364,18 @@ pub async fn check_instance_version(app: AppHandle, instance_id: String) -> Opti
         None => return None,
     };
 
-    let instance = match database::get_instance_by_id(&pool, &instance_id).await {
+    let mut instance = match database::get_instance_by_id(&pool, &instance_id).await {
         Ok(Some(i)) => i,
         _ => return None,
     };
 
+    fill_unknown_version(&pool, &mut instance).await;
+
     let available_version = get_available_version(&app).await;
 
     let version_unknown = instance.installed_version.is_none();
     let update_available = match (&instance.installed_version, &available_version) {
-        (Some(installed), Some(available)) => installed != available,
+        (Some(installed), Some(available)) => is_newer(available, installed),
         _ => false,
     };
 
@@ -274,6 +415,143 @@ pub async fn update_instance_installed_version(
     }
 }
 
+/// Read the installed version from an instance's server files.
+/// If a registered instance lives at `instance_path`, its stored version is updated to match.
+#[tauri::command]
+pub async fn detect_installed_version(app: AppHandle, instance_path: String) -> Option<String> {
+    let path = instance_path.clone();
+    let version = tokio::task::spawn_blocking(move || read_installed_version(&path))
+        .await
+        .ok()
+        .flatten()?;
+
+    println!("[version] Detected version {} at {}", version, instance_path);
+
+    if let Some(pool) = app.try_state::<DbPool>() {
+        if let Ok(Some(instance)) = database::get_instance_by_path(pool.inner(), &instance_path).await {
+            if instance.installed_version.as_deref() != Some(version.as_str()) {
+                if let Err(e) = database::update_instance_version(pool.inner(), &instance.id, &version).await {
+                    println!("[version] WARNING: Failed to store detected version: {}", e);
+                }
+            }
+        }
+    }
+
+    Some(version)
+}
+
+/// Update an instance's server files in place to the latest available version
+#[tauri::command]
+pub async fn update_instance(app: AppHandle, instance_id: String) -> UpdateInstanceResult {
+    println!("[version] Updating instance in place: {}", instance_id);
+
+    let fail = |previous_version: Option<String>, backup_path: Option<String>, error: String| {
+        println!("[version] ERROR: {}", error);
+        UpdateInstanceResult {
+            success: false,
+            previous_version,
+            new_version: None,
+            backup_path,
+            error: Some(error),
+        }
+    };
+
+    let pool = match app.try_state::<DbPool>() {
+        Some(p) => p.inner().clone(),
+        None => return fail(None, None, DATABASE_UNAVAILABLE.to_string()),
+    };
+
+    let instance = match database::get_instance_by_id(&pool, &instance_id).await {
+        Ok(Some(i)) => i,
+        Ok(None) => return fail(None, None, "Instance not found".to_string()),
+        Err(e) => return fail(None, None, format!("Database error: {}", e)),
+    };
+    let previous_version = instance.installed_version.clone();
+
+    // Refuse to touch files of a running server
+    let is_running = app
+        .try_state::<Arc<Mutex<ServerState>>>()
+        .map(|state| state.lock().unwrap().is_running(&instance_id))
+        .unwrap_or(false);
+    if is_running {
+        return fail(previous_version, None, "Stop the server before updating it".to_string());
+    }
+
+    // Back up settings files before downloading
+    let server_dir = Path::new(&instance.path).join("Server");
+    let backup_dir = Path::new(&instance.path)
+        .join("backups")
+        .join(format!("pre-update-{}", chrono::Utc::now().format("%Y%m%d-%H%M%S")));
+    if let Err(e) = backup_preserved_files(&server_dir, &backup_dir) {
+        return fail(previous_version, None, format!("Failed to back up config files: {}", e));
+    }
+    let backup_path = Some(backup_dir.to_string_lossy().to_string());
+
+    let _ = app.emit(
+        "download-progress",
+        DownloadProgress {
+            status: "starting".to_string(),
+            percentage: Some(0.0),
+            message: "Backed up server settings, starting update...".to_string(),
+        },
+    );
+
+    let available_version = get_available_version(&app).await;
+
+    let result = download_server_files(app.clone(), instance.path.clone(), None, None, None).await;
+
+    // Restore settings regardless of the download outcome
+    if let Err(e) = restore_preserved_files(&backup_dir, &server_dir) {
+        println!("[version] WARNING: Failed to restore config files: {}", e);
+    }
+
+    if !result.success {
+        return fail(
+            previous_version,
+            backup_path,
+            result.error.unwrap_or_else(|| "Download failed".to_string()),
+        );
+    }
+
+    if let Some(ref version) = available_version {
+        update_instance_installed_version(app.clone(), instance_id.clone(), version.clone()).await;
+    }
+
+    println!("[version] Instance {} updated to {:?}", instance_id, available_version);
+
+    UpdateInstanceResult {
+        success: true,
+        previous_version,
+        new_version: available_version,
+        backup_path,
+        error: None,
+    }
+}
+
+/// Copy the preserved settings files into a backup directory
+fn backup_preserved_files(server_dir: &Path, backup_dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(backup_dir)?;
+    for name in PRESERVED_CONFIG_FILES {
+        let src = server_dir.join(name);
+        if src.exists() {
+            std::fs::copy(&src, backup_dir.join(name))?;
+        }
+    }
+    Ok(())
+}
+
+/// Copy backed up settings files back into the server directory
+fn restore_preserved_files(backup_dir: &Path, server_dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(server_dir)?;
+    for name in PRESERVED_CONFIG_FILES {
+        let src: PathBuf = backup_dir.join(name);
+        if src.exists() {
+            std::fs::copy(&src, server_dir.join(name))?;
+        }
+    }
+    Ok(())
+}
+
 /// Dismiss the version update banner for a specific version
 #[tauri::command]
 pub async fn dismiss_version_banner(app: AppHandle, version: String) -> bool {
@@ -313,12 +591,31 @@ pub async fn start_version_check_background_task(app: AppHandle) {
     println!("[version] Starting background version check task");
 
     loop {
-        // Wait 30 minutes between checks
-        tokio::time::sleep(Duration::from_secs(30 * 60)).await;
+        let pool = app.try_state::<DbPool>().map(|p| p.inner().clone());
+
+        let interval_minutes = match pool {
+            Some(ref pool) => get_check_interval_minutes(pool).await,
+            None => DEFAULT_CHECK_INTERVAL_MINUTES,
+        };
+        let interval = Duration::from_secs(interval_minutes * 60);
+
+        // Wait for the interval, or restart the wait if settings changed
+        match app.try_state::<VersionCheckNotifier>().map(|n| n.0.clone()) {
+            Some(notify) => {
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => {}
+                    _ = notify.notified() => {
+                        println!("[version] Settings changed, re-reading check interval");
+                        continue;
+                    }
+                }
+            }
+            None => tokio::time::sleep(interval).await,
+        }
 
         // Check if periodic checking is enabled
-        let pool = match app.try_state::<DbPool>() {
-            Some(p) => p.inner().clone(),
+        let pool = match pool {
+            Some(p) => p,
             None => continue,
         };
 
@@ -345,6 +642,8 @@ pub async fn start_version_check_background_task(app: AppHandle) {
             }
         };
 
+        record_version_check(&pool, &available_version).await;
+
         // Check if this version was dismissed
         let dismissed = database::get_setting(&pool, "dismissed_version")
             .await
@@ -364,10 +663,12 @@ pub async fn start_version_check_background_task(app: AppHandle) {
 
         let mut outdated_results = Vec::new();
 
-        for instance in instances {
+        for mut instance in instances {
+            fill_unknown_version(&pool, &mut instance).await;
+
             let version_unknown = instance.installed_version.is_none();
             let update_available = match &instance.installed_version {
-                Some(installed) => installed != &available_version,
+                Some(installed) => is_newer(&available_version, installed),
                 None => false, // Don't mark as update_available if version is unknown
             };
 
@@ -386,6 +687,23 @@ pub async fn start_version_check_background_task(app: AppHandle) {
 
         if !outdated_results.is_empty() {
             println!("[version] Found {} outdated instances, emitting event", outdated_results.len());
+
+            let names: Vec<&str> = outdated_results
+                .iter()
+                .filter(|r| r.update_available)
+                .map(|r| r.instance_name.as_str())
+                .collect();
+            if !names.is_empty() {
+                notify(
+                    &app,
+                    WebhookEvent::UpdateAvailable,
+                    format!(
+                        "Server version {} is available (outdated: {})",
+                        available_version,
+                        names.join(", ")
+                    ),
+                );
+            }
             let _ = app.emit(
                 "version-update-available",
                 VersionUpdateEvent {
@@ -396,3 +714,30 @@ pub async fn start_version_check_background_task(app: AppHandle) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_newer_compares_numerically() {
+        assert!(is_newer("1.2.10", "1.2.9"));
+        assert!(!is_newer("1.2.9", "1.2.10"));
+        assert!(is_newer("v2.0.0", "1.9.9"));
+        assert!(!is_newer("1.2.3", "v1.2.3"));
+    }
+
+    #[test]
+    fn is_newer_ranks_releases_above_prereleases() {
+        assert!(is_newer("1.0.0", "1.0.0-beta"));
+        assert!(!is_newer("1.0.0-beta", "1.0.0"));
+    }
+
+    #[test]
+    fn is_newer_falls_back_to_string_inequality() {
+        assert!(is_newer("2026.01.15-abc", "2026.01.10-def"));
+        assert!(is_newer("build-41", "1.0.0"));
+        assert!(!is_newer("build-41", "build-41"));
+        assert!(!is_newer(" build-41 ", "build-41"));
+    }
+}
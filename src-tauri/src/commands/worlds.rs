@@ -2,9 +2,15 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tauri::State;
 
-use super::config::JsonWriteResult;
+use super::config::{read_default_world, write_file_atomic, JsonWriteResult};
+use super::errors::ErrorCode;
+use super::server::ServerState;
+use super::system::available_disk_space;
 
 // ============================================================================
 // Types - World Info
@@ -20,6 +26,25 @@ pub struct WorldInfo {
     pub world_gen_name: Option<String>,
     pub is_ticking: Option<bool>,
     pub is_pvp_enabled: Option<bool>,
+    pub size_bytes: Option<u64>,
+    pub last_modified: Option<String>,
+    pub parse_error: Option<String>,  // Set when config.json didn't match the expected format
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldDetails {
+    pub success: bool,
+    pub path: String,
+    pub size_bytes: u64,
+    pub last_modified: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchDeleteResult {
+    pub success: bool,  // True only if every world was deleted
+    pub results: HashMap<String, JsonWriteResult>,
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -111,14 +136,65 @@ pub struct WorldConfigResult {
     pub error: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldSeedResult {
+    pub success: bool,
+    pub seed: Option<i64>,  // The numeric seed that was stored
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameplayConfigsResult {
+    pub success: bool,
+    pub configs: Vec<String>,  // Sorted preset names usable as GameplayConfig
+    /// False when Assets.zip couldn't be scanned, so only built-in defaults and mod presets are listed
+    pub complete: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveWorldConfigResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub error_code: Option<ErrorCode>,
+    /// Set when the config was saved but looks wrong (e.g. an unknown GameplayConfig)
+    pub warning: Option<String>,
+}
+
+/// Gameplay presets that every server ships with
+const KNOWN_GAMEPLAY_CONFIGS: [&str; 1] = ["Default"];
+
+/// Where gameplay presets live inside Assets.zip and mod packs
+const GAMEPLAY_CONFIGS_DIR: &str = "Server/GameplayConfigs/";
+
+/// Prefix of the hidden staging directory used while duplicating a world.
+/// Reserved: world names can't start with it.
+const TEMP_WORLD_PREFIX: &str = ".hypanel-dup-";
+
 // ============================================================================
 // Commands - List Worlds
 // ============================================================================
 
-/// List all worlds in the server's universe directory
+/// List all worlds in the server's universe directory.
+/// Size and last-modified are only computed when `include_details` is true, since
+/// walking large worlds is slow; use `get_world_details` for a single world.
 #[tauri::command]
-pub fn list_worlds(instance_path: String) -> WorldsListResult {
-    let worlds_dir = Path::new(&instance_path).join("Server").join("universe").join("worlds");
+pub async fn list_worlds(instance_path: String, include_details: Option<bool>) -> WorldsListResult {
+    let include_details = include_details.unwrap_or(false);
+
+    match tokio::task::spawn_blocking(move || collect_worlds(&instance_path, include_details)).await {
+        Ok(result) => result,
+        Err(e) => WorldsListResult {
+            success: false,
+            worlds: vec![],
+            error: Some(format!("Failed to list worlds: {}", e)),
+        },
+    }
+}
+
+/// Read every world in the universe directory, spread over at most one thread per core
+fn collect_worlds(instance_path: &str, include_details: bool) -> WorldsListResult {
+    let worlds_dir = Path::new(instance_path).join("Server").join("universe").join("worlds");
 
     if !worlds_dir.exists() {
         return WorldsListResult {
@@ -128,47 +204,12 @@ pub fn list_worlds(instance_path: String) -> WorldsListResult {
         };
     }
 
-    let mut worlds = Vec::new();
-
-    match fs::read_dir(&worlds_dir) {
-        Ok(entries) => {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.is_dir() {
-                    let config_path = path.join("config.json");
-                    let world_name = path.file_name()
-                        .map(|n| n.to_string_lossy().to_string())
-                        .unwrap_or_default();
-
-                    let mut world_info = WorldInfo {
-                        name: world_name,
-                        path: path.to_string_lossy().to_string(),
-                        uuid: None,
-                        seed: None,
-                        world_gen_type: None,
-                        world_gen_name: None,
-                        is_ticking: None,
-                        is_pvp_enabled: None,
-                    };
-
-                    // Try to read config.json to get more info
-                    if config_path.exists() {
-                        if let Ok(content) = fs::read_to_string(&config_path) {
-                            if let Ok(config) = serde_json::from_str::<WorldConfig>(&content) {
-                                world_info.uuid = Some(config.uuid.binary.clone());
-                                world_info.seed = Some(config.seed);
-                                world_info.world_gen_type = Some(config.world_gen.gen_type.clone());
-                                world_info.world_gen_name = Some(config.world_gen.name.clone());
-                                world_info.is_ticking = Some(config.is_ticking);
-                                world_info.is_pvp_enabled = Some(config.is_pvp_enabled);
-                            }
-                        }
-                    }
-
-                    worlds.push(world_info);
-                }
-            }
-        }
+    let world_dirs: Vec<PathBuf> = match fs::read_dir(&worlds_dir) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir() && !is_temp_world_dir(path))
+            .collect(),
         Err(e) => {
             return WorldsListResult {
                 success: false,
@@ -176,7 +217,27 @@ pub fn list_worlds(instance_path: String) -> WorldsListResult {
                 error: Some(format!("Failed to read worlds directory: {}", e)),
             };
         }
-    }
+    };
+
+    // Split the worlds into one chunk per available core
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = world_dirs.len().div_ceil(threads).max(1);
+
+    let mut worlds: Vec<WorldInfo> = std::thread::scope(|scope| {
+        let handles: Vec<_> = world_dirs
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|path| read_world_info(path, include_details))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        handles.into_iter().filter_map(|h| h.join().ok()).flatten().collect()
+    });
 
     // Sort by name
     worlds.sort_by(|a, b| a.name.cmp(&b.name));
@@ -188,6 +249,112 @@ pub fn list_worlds(instance_path: String) -> WorldsListResult {
     }
 }
 
+/// Build the listing entry for one world directory
+fn read_world_info(path: &Path, include_details: bool) -> WorldInfo {
+    let world_name = path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut world_info = WorldInfo {
+        name: world_name,
+        path: path.to_string_lossy().to_string(),
+        uuid: None,
+        seed: None,
+        world_gen_type: None,
+        world_gen_name: None,
+        is_ticking: None,
+        is_pvp_enabled: None,
+        size_bytes: None,
+        last_modified: None,
+        parse_error: None,
+    };
+
+    // Try to read config.json to get more info
+    let config_path = path.join("config.json");
+    if config_path.exists() {
+        match fs::read_to_string(&config_path) {
+            Ok(content) => match serde_json::from_str::<WorldConfig>(&content) {
+                Ok(config) => {
+                    world_info.uuid = Some(config.uuid.binary.clone());
+                    world_info.seed = Some(config.seed);
+                    world_info.world_gen_type = Some(config.world_gen.gen_type.clone());
+                    world_info.world_gen_name = Some(config.world_gen.name.clone());
+                    world_info.is_ticking = Some(config.is_ticking);
+                    world_info.is_pvp_enabled = Some(config.is_pvp_enabled);
+                }
+                Err(e) => {
+                    // Likely a different server version; salvage what we can
+                    world_info.parse_error = Some(format!("Unexpected config.json format: {}", e));
+                    if let Ok(value) = serde_json::from_str::<Value>(&content) {
+                        apply_partial_config(&mut world_info, &value);
+                    }
+                }
+            },
+            Err(e) => {
+                world_info.parse_error = Some(format!("Failed to read config.json: {}", e));
+            }
+        }
+    }
+
+    if include_details {
+        let (size, modified) = world_size_and_mtime(path);
+        world_info.size_bytes = Some(size);
+        world_info.last_modified = modified.map(format_system_time);
+    }
+
+    world_info
+}
+
+/// Fill whatever known fields are present in a config that didn't match `WorldConfig`
+fn apply_partial_config(world_info: &mut WorldInfo, config: &Value) {
+    world_info.uuid = config
+        .get("UUID")
+        .and_then(|uuid| uuid.get("$binary").or(Some(uuid)))
+        .and_then(|v| v.as_str())
+        .map(String::from);
+    world_info.seed = config.get("Seed").and_then(|v| v.as_i64());
+    if let Some(world_gen) = config.get("WorldGen") {
+        world_info.world_gen_type = world_gen.get("Type").and_then(|v| v.as_str()).map(String::from);
+        world_info.world_gen_name = world_gen.get("Name").and_then(|v| v.as_str()).map(String::from);
+    }
+    world_info.is_ticking = config.get("IsTicking").and_then(|v| v.as_bool());
+    world_info.is_pvp_enabled = config.get("IsPvpEnabled").and_then(|v| v.as_bool());
+}
+
+/// Get size on disk and last-modified time for a single world
+#[tauri::command]
+pub async fn get_world_details(world_path: String) -> WorldDetails {
+    let path = Path::new(&world_path);
+
+    if !path.exists() {
+        return WorldDetails {
+            success: false,
+            path: world_path,
+            size_bytes: 0,
+            last_modified: None,
+            error: Some("World directory not found".to_string()),
+        };
+    }
+
+    let path_clone = world_path.clone();
+    match tokio::task::spawn_blocking(move || world_size_and_mtime(Path::new(&path_clone))).await {
+        Ok((size_bytes, modified)) => WorldDetails {
+            success: true,
+            path: world_path,
+            size_bytes,
+            last_modified: modified.map(format_system_time),
+            error: None,
+        },
+        Err(e) => WorldDetails {
+            success: false,
+            path: world_path,
+            size_bytes: 0,
+            last_modified: None,
+            error: Some(format!("Failed to read world details: {}", e)),
+        },
+    }
+}
+
 // ============================================================================
 // Commands - World Config
 // ============================================================================
@@ -232,46 +399,182 @@ pub fn get_world_config(world_path: String) -> WorldConfigResult {
     }
 }
 
-/// Save world config to a specific world directory
+/// Save world config to a specific world directory.
+/// A GameplayConfig that doesn't match any known preset is still saved, with a warning.
+/// Checking the preset scans Assets.zip, so the work runs off the main thread.
 #[tauri::command]
-pub fn save_world_config(world_path: String, config: WorldConfig) -> JsonWriteResult {
-    let config_path = Path::new(&world_path).join("config.json");
+pub async fn save_world_config(world_path: String, config: WorldConfig) -> SaveWorldConfigResult {
+    match tokio::task::spawn_blocking(move || write_world_config(&world_path, &config)).await {
+        Ok(result) => result,
+        Err(e) => SaveWorldConfigResult {
+            success: false,
+            error: Some(format!("Failed to save world config: {}", e)),
+            error_code: Some(ErrorCode::Io),
+            warning: None,
+        },
+    }
+}
+
+/// Write a world's config.json and check its GameplayConfig against the known presets
+fn write_world_config(world_path: &str, config: &WorldConfig) -> SaveWorldConfigResult {
+    let config_path = Path::new(world_path).join("config.json");
 
-    let formatted = match serde_json::to_string_pretty(&config) {
+    let formatted = match serde_json::to_string_pretty(config) {
         Ok(s) => s,
         Err(e) => {
-            return JsonWriteResult {
+            return SaveWorldConfigResult {
                 success: false,
                 error: Some(format!("Failed to serialize world config: {}", e)),
+                error_code: Some(ErrorCode::InvalidConfig),
+                warning: None,
             };
         }
     };
 
     match fs::write(config_path, formatted) {
-        Ok(()) => JsonWriteResult {
+        Ok(()) => SaveWorldConfigResult {
             success: true,
             error: None,
+            error_code: None,
+            warning: check_gameplay_config(Path::new(world_path), &config.gameplay_config),
         },
-        Err(e) => JsonWriteResult {
+        Err(e) => SaveWorldConfigResult {
             success: false,
             error: Some(format!("Failed to write world config.json: {}", e)),
+            error_code: Some(ErrorCode::from_io(&e)),
+            warning: None,
+        },
+    }
+}
+
+/// List the gameplay presets a world's GameplayConfig can name: the built-in defaults,
+/// those in the server's Assets.zip, and those shipped by installed mods.
+#[tauri::command]
+pub async fn list_gameplay_configs(instance_path: String) -> GameplayConfigsResult {
+    match tokio::task::spawn_blocking(move || collect_gameplay_configs(Path::new(&instance_path))).await {
+        Ok(result) => result,
+        Err(e) => GameplayConfigsResult {
+            success: false,
+            configs: KNOWN_GAMEPLAY_CONFIGS.iter().map(|c| c.to_string()).collect(),
+            complete: false,
+            error: Some(format!("Failed to list gameplay configs: {}", e)),
         },
     }
 }
 
+/// Set one boolean toggle (e.g. "IsPvpEnabled") in a world's config.json and return
+/// the updated config. Other fields, including unknown ones, are written back unchanged.
+#[tauri::command]
+pub fn set_world_toggle(world_path: String, key: String, value: bool) -> WorldConfigResult {
+    let mut result = get_world_config(world_path.clone());
+    let Some(config) = result.config.as_mut() else {
+        return result;
+    };
+
+    let Some(toggle) = world_toggle_mut(config, &key) else {
+        return WorldConfigResult {
+            success: false,
+            config: None,
+            raw: None,
+            error: Some(format!("Unknown world toggle: {}", key)),
+        };
+    };
+    *toggle = value;
+
+    let formatted = match serde_json::to_string_pretty(config) {
+        Ok(s) => s,
+        Err(e) => {
+            return WorldConfigResult {
+                success: false,
+                config: None,
+                raw: None,
+                error: Some(format!("Failed to serialize world config: {}", e)),
+            };
+        }
+    };
+
+    let config_path = Path::new(&world_path).join("config.json");
+    if let Err(e) = write_file_atomic(&config_path, &formatted) {
+        return WorldConfigResult {
+            success: false,
+            config: None,
+            raw: None,
+            error: Some(format!("Failed to write world config.json: {}", e)),
+        };
+    }
+
+    println!("[set_world_toggle] {} = {} in {}", key, value, world_path);
+    result.raw = Some(formatted);
+    result
+}
+
+/// Set a world's seed from user input, which may be a number or any text.
+/// Text seeds are hashed with `seed_from_text`; returns the numeric seed that was stored.
+#[tauri::command]
+pub fn set_world_seed(world_path: String, seed_input: String) -> WorldSeedResult {
+    let fail = |error: String| WorldSeedResult {
+        success: false,
+        seed: None,
+        error: Some(error),
+    };
+
+    let seed = match resolve_seed(&seed_input) {
+        Ok(seed) => seed,
+        Err(e) => return fail(e),
+    };
+
+    let mut result = get_world_config(world_path.clone());
+    let Some(config) = result.config.as_mut() else {
+        return fail(result.error.unwrap_or_else(|| "Failed to read world config.json".to_string()));
+    };
+    config.seed = seed;
+
+    let formatted = match serde_json::to_string_pretty(config) {
+        Ok(s) => s,
+        Err(e) => return fail(format!("Failed to serialize world config: {}", e)),
+    };
+
+    let config_path = Path::new(&world_path).join("config.json");
+    if let Err(e) = write_file_atomic(&config_path, &formatted) {
+        return fail(format!("Failed to write world config.json: {}", e));
+    }
+
+    println!("[set_world_seed] Seed set to {} in {}", seed, world_path);
+    WorldSeedResult {
+        success: true,
+        seed: Some(seed),
+        error: None,
+    }
+}
+
 // ============================================================================
 // Commands - World Management
 // ============================================================================
 
-/// Delete a world directory
+/// Delete a world directory.
+/// Refuses while the owning server is running unless `force` is set.
 #[tauri::command]
-pub fn delete_world(world_path: String) -> JsonWriteResult {
+pub fn delete_world(
+    state: State<'_, Arc<Mutex<ServerState>>>,
+    world_path: String,
+    instance_id: Option<String>,
+    force: Option<bool>,
+) -> JsonWriteResult {
     let path = Path::new(&world_path);
 
     if !path.exists() {
         return JsonWriteResult {
             success: false,
             error: Some("World directory not found".to_string()),
+            error_code: Some(ErrorCode::NotFound),
+        };
+    }
+
+    if !force.unwrap_or(false) && is_world_in_use(&state, instance_id.as_deref(), path) {
+        return JsonWriteResult {
+            success: false,
+            error: Some("Cannot delete a world while its server is running".to_string()),
+            error_code: Some(ErrorCode::AlreadyRunning),
         };
     }
 
@@ -279,23 +582,118 @@ pub fn delete_world(world_path: String) -> JsonWriteResult {
         Ok(()) => JsonWriteResult {
             success: true,
             error: None,
+            error_code: None,
         },
         Err(e) => JsonWriteResult {
             success: false,
             error: Some(format!("Failed to delete world: {}", e)),
+            error_code: Some(ErrorCode::from_io(&e)),
         },
     }
 }
 
-/// Duplicate a world to a new directory
+/// Delete several worlds at once, reporting success per path.
+/// `confirmation` must be non-empty. Worlds of running servers and the instance's
+/// default world (`Defaults.World` in the server config.json) are skipped unless
+/// `force` is set. A failure on one world doesn't stop the others.
 #[tauri::command]
-pub fn duplicate_world(world_path: String, new_name: String) -> JsonWriteResult {
+pub fn delete_worlds(
+    state: State<'_, Arc<Mutex<ServerState>>>,
+    world_paths: Vec<String>,
+    confirmation: String,
+    force: Option<bool>,
+) -> BatchDeleteResult {
+    if confirmation.trim().is_empty() {
+        return BatchDeleteResult {
+            success: false,
+            results: HashMap::new(),
+            error: Some("Confirmation is required to delete worlds".to_string()),
+        };
+    }
+
+    let force = force.unwrap_or(false);
+    let mut results = HashMap::new();
+
+    for world_path in world_paths {
+        let path = Path::new(&world_path);
+
+        let result = if !path.exists() {
+            Err(("World directory not found".to_string(), ErrorCode::NotFound))
+        } else if !force && is_world_in_use(&state, None, path) {
+            Err((
+                "Cannot delete a world while its server is running".to_string(),
+                ErrorCode::AlreadyRunning,
+            ))
+        } else if !force && is_default_world(path) {
+            Err((
+                "This is the server's default world; force is required to delete it".to_string(),
+                ErrorCode::DefaultWorld,
+            ))
+        } else {
+            fs::remove_dir_all(path)
+                .map_err(|e| (format!("Failed to delete world: {}", e), ErrorCode::from_io(&e)))
+        };
+
+        let entry = match result {
+            Ok(()) => JsonWriteResult {
+                success: true,
+                error: None,
+                error_code: None,
+            },
+            Err((error, code)) => {
+                println!("[delete_worlds] Skipped {}: {}", world_path, error);
+                JsonWriteResult {
+                    success: false,
+                    error: Some(error),
+                    error_code: Some(code),
+                }
+            }
+        };
+        results.insert(world_path, entry);
+    }
+
+    BatchDeleteResult {
+        success: results.values().all(|r| r.success),
+        results,
+        error: None,
+    }
+}
+
+/// Duplicate a world to a new directory.
+/// Refuses while the owning server is running unless `force` is set, since chunks
+/// may be mid-write.
+#[tauri::command]
+pub fn duplicate_world(
+    state: State<'_, Arc<Mutex<ServerState>>>,
+    world_path: String,
+    new_name: String,
+    instance_id: Option<String>,
+    force: Option<bool>,
+) -> JsonWriteResult {
     let source_path = Path::new(&world_path);
+    let new_name = new_name.trim();
+
+    if let Err(e) = validate_world_name(new_name) {
+        return JsonWriteResult {
+            success: false,
+            error: Some(e),
+            error_code: None,
+        };
+    }
 
     if !source_path.exists() {
         return JsonWriteResult {
             success: false,
             error: Some("Source world not found".to_string()),
+            error_code: Some(ErrorCode::NotFound),
+        };
+    }
+
+    if !force.unwrap_or(false) && is_world_in_use(&state, instance_id.as_deref(), source_path) {
+        return JsonWriteResult {
+            success: false,
+            error: Some("Cannot duplicate a world while its server is running".to_string()),
+            error_code: Some(ErrorCode::AlreadyRunning),
         };
     }
 
@@ -305,40 +703,387 @@ pub fn duplicate_world(world_path: String, new_name: String) -> JsonWriteResult
             return JsonWriteResult {
                 success: false,
                 error: Some("Could not determine parent directory".to_string()),
+                error_code: None,
             };
         }
     };
 
-    let dest_path = parent.join(&new_name);
+    let dest_path = parent.join(new_name);
 
     if dest_path.exists() {
         return JsonWriteResult {
             success: false,
             error: Some(format!("World '{}' already exists", new_name)),
+            error_code: None,
         };
     }
 
-    // Copy directory recursively
-    match copy_dir_all(source_path, &dest_path) {
+    // Make sure the copy fits before starting, rather than failing halfway through
+    let (required, _) = world_size_and_mtime(source_path);
+    if let Some(available) = available_disk_space(parent) {
+        if available < required {
+            return JsonWriteResult {
+                success: false,
+                error: Some(format!(
+                    "Not enough disk space: world needs {} MB, {} MB available",
+                    required / (1024 * 1024),
+                    available / (1024 * 1024)
+                )),
+                error_code: None,
+            };
+        }
+    }
+
+    // Copy into a uniquely named hidden directory and rename into place, so a failed
+    // copy never shows up as a half-populated world
+    let temp_path = parent.join(format!("{}{}", TEMP_WORLD_PREFIX, uuid::Uuid::new_v4()));
+
+    let result = copy_dir_all(source_path, &temp_path)
+        .and_then(|()| fs::rename(&temp_path, &dest_path));
+
+    match result {
         Ok(()) => JsonWriteResult {
             success: true,
             error: None,
+            error_code: None,
         },
         Err(e) => {
-            // Try to clean up partial copy
-            let _ = fs::remove_dir_all(&dest_path);
+            let _ = fs::remove_dir_all(&temp_path);
             JsonWriteResult {
                 success: false,
                 error: Some(format!("Failed to duplicate world: {}", e)),
+                error_code: Some(ErrorCode::from_io(&e)),
             }
         }
     }
 }
 
+/// Rename a world directory in place
+#[tauri::command]
+pub fn rename_world(
+    state: State<'_, Arc<Mutex<ServerState>>>,
+    world_path: String,
+    new_name: String,
+) -> JsonWriteResult {
+    let source_path = Path::new(&world_path);
+    let new_name = new_name.trim();
+
+    if let Err(e) = validate_world_name(new_name) {
+        return JsonWriteResult {
+            success: false,
+            error: Some(e),
+            error_code: None,
+        };
+    }
+
+    if !source_path.exists() {
+        return JsonWriteResult {
+            success: false,
+            error: Some("World directory not found".to_string()),
+            error_code: Some(ErrorCode::NotFound),
+        };
+    }
+
+    if state.lock().unwrap().is_path_in_running_instance(source_path) {
+        return JsonWriteResult {
+            success: false,
+            error: Some("Cannot rename a world while its server is running".to_string()),
+            error_code: Some(ErrorCode::AlreadyRunning),
+        };
+    }
+
+    let parent = match source_path.parent() {
+        Some(p) => p,
+        None => {
+            return JsonWriteResult {
+                success: false,
+                error: Some("Could not determine parent directory".to_string()),
+                error_code: None,
+            };
+        }
+    };
+
+    let dest_path = parent.join(new_name);
+
+    if dest_path.exists() {
+        return JsonWriteResult {
+            success: false,
+            error: Some(format!("World '{}' already exists", new_name)),
+            error_code: None,
+        };
+    }
+
+    let old_name = source_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    if let Err(e) = fs::rename(source_path, &dest_path) {
+        return JsonWriteResult {
+            success: false,
+            error: Some(format!("Failed to rename world: {}", e)),
+            error_code: Some(ErrorCode::from_io(&e)),
+        };
+    }
+
+    // Keep any name stored in the world's config.json in sync
+    if let Err(e) = update_world_config_name(&dest_path, &old_name, new_name) {
+        println!("[rename_world] Warning: Failed to update config.json name: {}", e);
+    }
+
+    JsonWriteResult {
+        success: true,
+        error: None,
+        error_code: None,
+    }
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
 
+/// Recursively sum file sizes and find the newest file mtime in a directory
+fn world_size_and_mtime(path: &Path) -> (u64, Option<SystemTime>) {
+    let mut size = 0;
+    let mut newest: Option<SystemTime> = None;
+
+    if let Ok(entries) = fs::read_dir(path) {
+        for entry in entries.flatten() {
+            let metadata = match entry.metadata() {
+                Ok(m) => m,
+                Err(_) => continue,
+            };
+
+            if metadata.is_dir() {
+                let (sub_size, sub_newest) = world_size_and_mtime(&entry.path());
+                size += sub_size;
+                newest = newest.max(sub_newest);
+            } else if metadata.is_file() {
+                size += metadata.len();
+                newest = newest.max(metadata.modified().ok());
+            }
+        }
+    }
+
+    (size, newest)
+}
+
+/// Format a SystemTime as an RFC 3339 string
+fn format_system_time(time: SystemTime) -> String {
+    let datetime: chrono::DateTime<chrono::Utc> = time.into();
+    datetime.to_rfc3339()
+}
+
+/// Whether a directory is an in-progress (or abandoned) duplicate
+fn is_temp_world_dir(path: &Path) -> bool {
+    path.file_name()
+        .map(|n| n.to_string_lossy().starts_with(TEMP_WORLD_PREFIX))
+        .unwrap_or(false)
+}
+
+/// Whether the server owning a world is running, by instance ID if given,
+/// otherwise by mapping the world path back to a running instance's path
+fn is_world_in_use(state: &Mutex<ServerState>, instance_id: Option<&str>, world_dir: &Path) -> bool {
+    let state_guard = state.lock().unwrap();
+    let running = instance_id
+        .map(|id| state_guard.is_running(id))
+        .unwrap_or(false);
+
+    running || state_guard.is_path_in_running_instance(world_dir)
+}
+
+/// Whether a world is the one named in `Defaults.World` of its server's config.json.
+/// Worlds live in `<instance>/Server/universe/worlds/<name>`.
+fn is_default_world(world_dir: &Path) -> bool {
+    let world_name = match world_dir.file_name() {
+        Some(name) => name.to_string_lossy().to_string(),
+        None => return false,
+    };
+
+    world_dir
+        .ancestors()
+        .nth(4)
+        .and_then(read_default_world)
+        .map(|default| default == world_name)
+        .unwrap_or(false)
+}
+
+fn collect_gameplay_configs(instance_dir: &Path) -> GameplayConfigsResult {
+    let mut configs: Vec<String> = KNOWN_GAMEPLAY_CONFIGS.iter().map(|c| c.to_string()).collect();
+
+    let assets = scan_archive_gameplay_configs(&instance_dir.join("Assets.zip"));
+    let (complete, error) = match assets {
+        Ok(found) => {
+            configs.extend(found);
+            (true, None)
+        }
+        Err(e) => (false, Some(e)),
+    };
+
+    // Mods may be unpacked folders or .zip/.jar packs
+    if let Ok(entries) = fs::read_dir(instance_dir.join("Server").join("mods")) {
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.is_dir() {
+                configs.extend(scan_dir_gameplay_configs(&path.join(GAMEPLAY_CONFIGS_DIR)));
+            } else if matches!(
+                path.extension().and_then(|e| e.to_str()),
+                Some("zip") | Some("jar")
+            ) {
+                configs.extend(scan_archive_gameplay_configs(&path).unwrap_or_default());
+            }
+        }
+    }
+
+    configs.sort();
+    configs.dedup();
+
+    GameplayConfigsResult {
+        success: true,
+        configs,
+        complete,
+        error,
+    }
+}
+
+/// Preset names (file stems) of the .json files directly under `GAMEPLAY_CONFIGS_DIR` in an archive
+fn scan_archive_gameplay_configs(archive_path: &Path) -> Result<Vec<String>, String> {
+    let file = fs::File::open(archive_path)
+        .map_err(|e| format!("Failed to open {}: {}", archive_path.display(), e))?;
+    let archive = zip::ZipArchive::new(file)
+        .map_err(|e| format!("Failed to read {}: {}", archive_path.display(), e))?;
+
+    Ok(archive
+        .file_names()
+        .filter_map(|name| name.strip_prefix(GAMEPLAY_CONFIGS_DIR))
+        .filter(|rest| !rest.contains('/'))
+        .filter_map(|rest| rest.strip_suffix(".json"))
+        .filter(|stem| !stem.is_empty())
+        .map(|stem| stem.to_string())
+        .collect())
+}
+
+/// Preset names (file stems) of the .json files in an unpacked gameplay configs folder
+fn scan_dir_gameplay_configs(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("json"))
+        .filter_map(|path| path.file_stem().map(|s| s.to_string_lossy().to_string()))
+        .collect()
+}
+
+/// Warning for a GameplayConfig that names no known preset.
+/// Skipped when Assets.zip couldn't be scanned, since the preset list would be incomplete.
+/// Worlds live in `<instance>/Server/universe/worlds/<name>`.
+fn check_gameplay_config(world_dir: &Path, gameplay_config: &str) -> Option<String> {
+    let instance_dir = world_dir.ancestors().nth(4)?;
+    let available = collect_gameplay_configs(instance_dir);
+    if !available.complete || available.configs.iter().any(|c| c == gameplay_config) {
+        return None;
+    }
+
+    println!(
+        "[save_world_config] Unknown GameplayConfig '{}' in {}",
+        gameplay_config,
+        world_dir.display()
+    );
+    Some(format!(
+        "Gameplay config '{}' was not found; available presets: {}",
+        gameplay_config,
+        available.configs.join(", ")
+    ))
+}
+
+/// Turn seed input into a numeric seed. Input that parses as an i64 is used as-is;
+/// anything else is treated as a text seed. Surrounding whitespace is ignored.
+pub fn resolve_seed(input: &str) -> Result<i64, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("Seed cannot be empty".to_string());
+    }
+
+    Ok(input.parse::<i64>().unwrap_or_else(|_| seed_from_text(input)))
+}
+
+/// Hash a text seed the way Java's `String.hashCode` does (h = 31 * h + c over the
+/// UTF-16 code units, wrapping at 32 bits), widened to i64. This is what Minecraft
+/// does with text seeds, so the same text always gives the same world.
+fn seed_from_text(text: &str) -> i64 {
+    let hash = text
+        .encode_utf16()
+        .fold(0i32, |h, c| h.wrapping_mul(31).wrapping_add(c as i32));
+    hash as i64
+}
+
+/// The boolean field of a world config for a config.json key, if it is a known toggle
+fn world_toggle_mut<'a>(config: &'a mut WorldConfig, key: &str) -> Option<&'a mut bool> {
+    let toggle = match key {
+        "IsTicking" => &mut config.is_ticking,
+        "IsBlockTicking" => &mut config.is_block_ticking,
+        "IsPvpEnabled" => &mut config.is_pvp_enabled,
+        "IsFallDamageEnabled" => &mut config.is_fall_damage_enabled,
+        "IsGameTimePaused" => &mut config.is_game_time_paused,
+        "IsSpawningNPC" => &mut config.is_spawning_npc,
+        "IsSpawnMarkersEnabled" => &mut config.is_spawn_markers_enabled,
+        "IsAllNPCFrozen" => &mut config.is_all_npc_frozen,
+        "IsSavingPlayers" => &mut config.is_saving_players,
+        "IsSavingChunks" => &mut config.is_saving_chunks,
+        "IsUnloadingChunks" => &mut config.is_unloading_chunks,
+        "IsObjectiveMarkersEnabled" => &mut config.is_objective_markers_enabled,
+        "IsCompassUpdating" => &mut config.is_compass_updating,
+        "DeleteOnUniverseStart" => &mut config.delete_on_universe_start,
+        "DeleteOnRemove" => &mut config.delete_on_remove,
+        _ => return None,
+    };
+    Some(toggle)
+}
+
+/// Validate a world directory name
+fn validate_world_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("World name cannot be empty".to_string());
+    }
+    if name.contains('/') || name.contains('\\') || name == "." || name == ".." {
+        return Err("World name cannot contain path separators".to_string());
+    }
+    if name.starts_with(TEMP_WORLD_PREFIX) {
+        return Err(format!("World names starting with '{}' are reserved", TEMP_WORLD_PREFIX));
+    }
+    Ok(())
+}
+
+/// Update name fields in a world's config.json that match the old directory name
+fn update_world_config_name(world_dir: &Path, old_name: &str, new_name: &str) -> Result<(), String> {
+    let config_path = world_dir.join("config.json");
+    if !config_path.exists() {
+        return Ok(());
+    }
+
+    let content = fs::read_to_string(&config_path).map_err(|e| e.to_string())?;
+    let mut json: Value = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+
+    let mut changed = false;
+    if let Some(obj) = json.as_object_mut() {
+        for key in ["Name", "DisplayName"] {
+            if obj.get(key).and_then(|v| v.as_str()) == Some(old_name) {
+                obj.insert(key.to_string(), Value::String(new_name.to_string()));
+                changed = true;
+            }
+        }
+    }
+
+    if changed {
+        let formatted = serde_json::to_string_pretty(&json).map_err(|e| e.to_string())?;
+        fs::write(&config_path, formatted).map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
 /// Recursively copy a directory
 fn copy_dir_all(src: &Path, dst: &Path) -> std::io::Result<()> {
     fs::create_dir_all(dst)?;
@@ -358,3 +1103,46 @@ fn copy_dir_all(src: &Path, dst: &Path) -> std::io::Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_seeds_match_java_string_hash_code() {
+        assert_eq!(seed_from_text("hello"), 99162322);
+        assert_eq!(seed_from_text("Hello"), 69609650);
+        assert_eq!(seed_from_text("hello world"), 1794106052);
+        // Wraps at 32 bits like Java's int arithmetic
+        assert_eq!(seed_from_text("polygenelubricants"), i32::MIN as i64);
+        // Hashed over UTF-16 code units, so a non-BMP char counts as a surrogate pair
+        assert_eq!(seed_from_text("é"), 233);
+        assert_eq!(seed_from_text("😀"), 1772899);
+        assert_eq!(seed_from_text("Aa"), seed_from_text("BB"));
+    }
+
+    #[test]
+    fn numeric_seeds_pass_through() {
+        assert_eq!(resolve_seed("12345"), Ok(12345));
+        assert_eq!(resolve_seed("-42"), Ok(-42));
+        assert_eq!(resolve_seed("0"), Ok(0));
+        assert_eq!(resolve_seed("  7  "), Ok(7));
+        assert_eq!(resolve_seed(&i64::MAX.to_string()), Ok(i64::MAX));
+        assert_eq!(resolve_seed(&i64::MIN.to_string()), Ok(i64::MIN));
+    }
+
+    #[test]
+    fn non_numeric_seeds_are_hashed() {
+        assert_eq!(resolve_seed("hello"), Ok(99162322));
+        assert_eq!(resolve_seed("  hello  "), Ok(99162322));
+        assert_eq!(resolve_seed("12abc"), Ok(seed_from_text("12abc")));
+        // Out of i64 range, so it's text rather than a number
+        assert_eq!(resolve_seed("9223372036854775808"), Ok(seed_from_text("9223372036854775808")));
+    }
+
+    #[test]
+    fn empty_seed_is_rejected() {
+        assert!(resolve_seed("").is_err());
+        assert!(resolve_seed("   ").is_err());
+    }
+}
@@ -1,12 +1,13 @@
+use chrono::Utc;
 use sqlx::{sqlite::SqlitePoolOptions, FromRow, Pool, Sqlite};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::AppHandle;
 use tauri::Manager;
 
 pub type DbPool = Pool<Sqlite>;
 
 /// Get the database file path
-fn get_db_path(app: &AppHandle) -> PathBuf {
+pub fn get_db_path(app: &AppHandle) -> PathBuf {
     let app_data = app.path().app_data_dir().expect("Failed to get app data dir");
     std::fs::create_dir_all(&app_data).expect("Failed to create app data dir");
     app_data.join("hypanel.db")
@@ -19,6 +20,8 @@ pub async fn init_db(app: &AppHandle) -> Result<DbPool, sqlx::Error> {
 
     println!("[database] Initializing database at: {}", db_path.display());
 
+    apply_pending_restore(&db_path);
+
     let pool = SqlitePoolOptions::new()
         .max_connections(5)
         .connect(&db_url)
@@ -32,87 +35,336 @@ pub async fn init_db(app: &AppHandle) -> Result<DbPool, sqlx::Error> {
     Ok(pool)
 }
 
+/// Path where a backup waits to replace the database on next launch
+pub fn pending_restore_path(db_path: &Path) -> PathBuf {
+    db_path.with_extension("db.restore")
+}
+
+/// SQLite's WAL or SHM file for a database file (`<name>-wal` / `<name>-shm`)
+fn sidecar_path(db_path: &Path, suffix: &str) -> PathBuf {
+    let mut name = db_path.as_os_str().to_os_string();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Swap in a staged backup before the pool opens the database.
+/// The previous database is kept next to it as `hypanel.db.before-restore`, together
+/// with its WAL/SHM files so transactions not yet checkpointed aren't lost.
+fn apply_pending_restore(db_path: &Path) {
+    let pending = pending_restore_path(db_path);
+    if !pending.exists() {
+        return;
+    }
+
+    println!("[database] Applying staged database restore from: {}", pending.display());
+
+    let previous = db_path.with_extension("db.before-restore");
+    // Sidecars left from an earlier restore would be replayed into this copy
+    for suffix in ["-wal", "-shm"] {
+        let _ = std::fs::remove_file(sidecar_path(&previous, suffix));
+    }
+    if db_path.exists() {
+        if let Err(e) = std::fs::rename(db_path, &previous) {
+            println!("[database] ERROR: Failed to move current database aside: {}", e);
+            return;
+        }
+    }
+
+    // The WAL/SHM files belong to the old database: keep them with it so they aren't
+    // replayed into the restored one
+    for suffix in ["-wal", "-shm"] {
+        let sidecar = sidecar_path(db_path, suffix);
+        if sidecar.exists() {
+            if let Err(e) = std::fs::rename(&sidecar, sidecar_path(&previous, suffix)) {
+                println!("[database] WARNING: Failed to move {} aside: {}", sidecar.display(), e);
+                let _ = std::fs::remove_file(&sidecar);
+            }
+        }
+    }
+
+    if let Err(e) = std::fs::rename(&pending, db_path) {
+        println!("[database] ERROR: Failed to apply restore: {}", e);
+        // Put the original database back rather than starting empty
+        let _ = std::fs::rename(&previous, db_path);
+        for suffix in ["-wal", "-shm"] {
+            let _ = std::fs::rename(sidecar_path(&previous, suffix), sidecar_path(db_path, suffix));
+        }
+    }
+}
+
+/// Write a consistent copy of the live database to `destination`
+pub async fn backup_to(pool: &DbPool, destination: &Path) -> Result<(), sqlx::Error> {
+    sqlx::query("VACUUM INTO ?")
+        .bind(destination.to_string_lossy().to_string())
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+// ============================================================================
+// Migrations
+// ============================================================================
+
+/// A schema change applied once, in order, inside a transaction
+struct Migration {
+    version: i64,
+    description: &'static str,
+    statements: &'static [&'static str],
+}
+
+/// Ordered schema history. Append new steps at the end; never edit applied ones.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "initial schema",
+        statements: &[
+            r#"
+            CREATE TABLE IF NOT EXISTS instances (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                path TEXT NOT NULL UNIQUE,
+                java_path TEXT,
+                jvm_args TEXT,
+                server_args TEXT,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+                auth_status TEXT DEFAULT 'unknown',
+                auth_persistence TEXT DEFAULT 'memory',
+                auth_profile_name TEXT,
+                installed_version TEXT
+            )
+            "#,
+            r#"
+            CREATE TABLE IF NOT EXISTS settings (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            )
+            "#,
+        ],
+    },
+    Migration {
+        version: 2,
+        description: "instance autostart flag",
+        statements: &["ALTER TABLE instances ADD COLUMN autostart INTEGER NOT NULL DEFAULT 0"],
+    },
+    Migration {
+        version: 3,
+        description: "instance environment variables",
+        statements: &["ALTER TABLE instances ADD COLUMN env_vars TEXT"],
+    },
+    Migration {
+        version: 4,
+        description: "graceful stop command",
+        statements: &[
+            "ALTER TABLE instances ADD COLUMN stop_command TEXT",
+            "ALTER TABLE instances ADD COLUMN stop_command_timeout_secs INTEGER",
+        ],
+    },
+    Migration {
+        version: 5,
+        description: "console command history",
+        statements: &[
+            r#"
+            CREATE TABLE IF NOT EXISTS command_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                instance_id TEXT NOT NULL,
+                command TEXT NOT NULL,
+                sent_at TEXT NOT NULL
+            )
+            "#,
+            "CREATE INDEX IF NOT EXISTS idx_command_history_instance ON command_history (instance_id, id)",
+        ],
+    },
+    Migration {
+        version: 6,
+        description: "soft-deleted instances",
+        statements: &["ALTER TABLE instances ADD COLUMN deleted_at TEXT"],
+    },
+    Migration {
+        version: 7,
+        description: "graceful stop timeout",
+        statements: &["ALTER TABLE instances ADD COLUMN stop_timeout_secs INTEGER NOT NULL DEFAULT 10"],
+    },
+    Migration {
+        version: 8,
+        description: "per-instance settings",
+        statements: &[r#"
+            CREATE TABLE IF NOT EXISTS instance_settings (
+                instance_id TEXT NOT NULL,
+                key TEXT NOT NULL,
+                value TEXT NOT NULL,
+                PRIMARY KEY (instance_id, key)
+            )
+            "#],
+    },
+    Migration {
+        version: 9,
+        description: "manual instance ordering",
+        statements: &["ALTER TABLE instances ADD COLUMN sort_order INTEGER NOT NULL DEFAULT 0"],
+    },
+    Migration {
+        version: 10,
+        description: "launch wrapper command",
+        statements: &["ALTER TABLE instances ADD COLUMN launch_wrapper TEXT"],
+    },
+    Migration {
+        version: 11,
+        description: "instance notes",
+        statements: &["ALTER TABLE instances ADD COLUMN notes TEXT"],
+    },
+    Migration {
+        version: 12,
+        description: "running server processes",
+        statements: &[r#"
+            CREATE TABLE IF NOT EXISTS running_servers (
+                instance_id TEXT PRIMARY KEY,
+                pid INTEGER NOT NULL,
+                started_at TEXT NOT NULL
+            )
+            "#],
+    },
+];
+
 /// Run database migrations
 async fn run_migrations(pool: &DbPool) -> Result<(), sqlx::Error> {
     println!("[database] Running migrations...");
 
-    // Create instances table
     sqlx::query(
         r#"
-        CREATE TABLE IF NOT EXISTS instances (
-            id TEXT PRIMARY KEY,
-            name TEXT NOT NULL,
-            path TEXT NOT NULL UNIQUE,
-            java_path TEXT,
-            jvm_args TEXT,
-            server_args TEXT,
-            created_at TEXT NOT NULL DEFAULT (datetime('now')),
-            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            applied_at TEXT NOT NULL
         )
         "#,
     )
     .execute(pool)
     .await?;
 
-    // Create settings table
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS settings (
-            key TEXT PRIMARY KEY,
-            value TEXT NOT NULL
-        )
-        "#,
-    )
-    .execute(pool)
-    .await?;
+    let mut current = get_schema_version(pool).await?;
 
-    // Migration: Add auth columns to instances table
-    // Check if auth_status column exists by trying to select it
-    let has_auth_status = sqlx::query("SELECT auth_status FROM instances LIMIT 1")
-        .fetch_optional(pool)
-        .await
-        .is_ok();
+    // Databases created before versioned migrations have no recorded version
+    if current == 0 {
+        let legacy = detect_legacy_version(pool).await?;
+        if legacy > 0 {
+            println!("[database] Detected unversioned schema at v{}", legacy);
+            set_schema_version(pool, legacy).await?;
+            current = legacy;
+        }
+    }
 
-    if !has_auth_status {
-        println!("[database] Adding auth columns to instances table...");
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        println!(
+            "[database] Applying migration v{}: {}",
+            migration.version, migration.description
+        );
+
+        let mut tx = pool.begin().await?;
+        for statement in migration.statements {
+            sqlx::query(statement).execute(&mut *tx).await?;
+        }
+        sqlx::query("INSERT INTO schema_version (version, applied_at) VALUES (?, ?)")
+            .bind(migration.version)
+            .bind(Utc::now().to_rfc3339())
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+    }
+
+    println!("[database] Migrations completed (schema v{})", get_schema_version(pool).await?);
+
+    Ok(())
+}
+
+/// Highest applied migration version, or 0 for a new database
+async fn get_schema_version(pool: &DbPool) -> Result<i64, sqlx::Error> {
+    let row: (Option<i64>,) = sqlx::query_as("SELECT MAX(version) FROM schema_version")
+        .fetch_one(pool)
+        .await?;
+
+    Ok(row.0.unwrap_or(0))
+}
 
+async fn set_schema_version(pool: &DbPool, version: i64) -> Result<(), sqlx::Error> {
+    sqlx::query("INSERT OR IGNORE INTO schema_version (version, applied_at) VALUES (?, ?)")
+        .bind(version)
+        .bind(Utc::now().to_rfc3339())
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Work out which migration an unversioned database corresponds to.
+/// Older builds added columns one by one, so the schema is probed in order
+/// and the last step whose changes are present wins.
+async fn detect_legacy_version(pool: &DbPool) -> Result<i64, sqlx::Error> {
+    if !table_exists(pool, "instances").await? {
+        return Ok(0);
+    }
+
+    // Very old databases predate the auth and version columns; bring them up to v1
+    if !column_exists(pool, "auth_status").await {
+        println!("[database] Adding auth columns to instances table...");
         sqlx::query("ALTER TABLE instances ADD COLUMN auth_status TEXT DEFAULT 'unknown'")
             .execute(pool)
             .await?;
-
         sqlx::query("ALTER TABLE instances ADD COLUMN auth_persistence TEXT DEFAULT 'memory'")
             .execute(pool)
             .await?;
-
         sqlx::query("ALTER TABLE instances ADD COLUMN auth_profile_name TEXT")
             .execute(pool)
             .await?;
     }
-
-    // Migration: Add installed_version column to instances table
-    let has_installed_version = sqlx::query("SELECT installed_version FROM instances LIMIT 1")
-        .fetch_optional(pool)
-        .await
-        .is_ok();
-
-    if !has_installed_version {
+    if !column_exists(pool, "installed_version").await {
         println!("[database] Adding installed_version column to instances table...");
-
         sqlx::query("ALTER TABLE instances ADD COLUMN installed_version TEXT")
             .execute(pool)
             .await?;
     }
+    sqlx::query("CREATE TABLE IF NOT EXISTS settings (key TEXT PRIMARY KEY, value TEXT NOT NULL)")
+        .execute(pool)
+        .await?;
 
-    println!("[database] Migrations completed");
+    let mut version = 1;
+    let probes = [
+        (2, column_exists(pool, "autostart").await),
+        (3, column_exists(pool, "env_vars").await),
+        (4, column_exists(pool, "stop_command").await),
+        (5, table_exists(pool, "command_history").await?),
+        (6, column_exists(pool, "deleted_at").await),
+    ];
+    for (step, present) in probes {
+        if !present {
+            break;
+        }
+        version = step;
+    }
 
-    Ok(())
+    Ok(version)
+}
+
+async fn table_exists(pool: &DbPool, table: &str) -> Result<bool, sqlx::Error> {
+    let row: Option<(String,)> =
+        sqlx::query_as("SELECT name FROM sqlite_master WHERE type = 'table' AND name = ?")
+            .bind(table)
+            .fetch_optional(pool)
+            .await?;
+
+    Ok(row.is_some())
+}
+
+async fn column_exists(pool: &DbPool, column: &str) -> bool {
+    sqlx::query(&format!("SELECT {} FROM instances LIMIT 1", column))
+        .fetch_optional(pool)
+        .await
+        .is_ok()
 }
 
 // ============================================================================
 // Instance operations
 // ============================================================================
 
-use chrono::Utc;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -131,6 +383,23 @@ pub struct Instance {
     pub auth_profile_name: Option<String>,  // e.g. "Natxo"
     // Version tracking
     pub installed_version: Option<String>,  // e.g. "0.1.0"
+    // Start automatically when the app launches
+    pub autostart: bool,
+    // Extra environment variables for the server process, as a JSON object
+    pub env_vars: Option<String>,
+    // Console command sent on stop and how long to wait before signalling
+    pub stop_command: Option<String>,             // None = "stop"
+    pub stop_command_timeout_secs: Option<i64>,   // None = 30
+    // Set when the instance is in the trash
+    pub deleted_at: Option<String>,
+    // Seconds between SIGTERM and a forced kill
+    pub stop_timeout_secs: i64,
+    // Position in the user's manual ordering (lowest first)
+    pub sort_order: i64,
+    // Command the server is launched through, e.g. "nice -n 10" (priority name on Windows)
+    pub launch_wrapper: Option<String>,
+    // Free-form notes for the user's own reference
+    pub notes: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -138,6 +407,8 @@ pub struct CreateInstanceInput {
     pub name: String,
     pub path: String,
     pub java_path: Option<String>,
+    pub env_vars: Option<String>,
+    pub notes: Option<String>,
 }
 
 /// Create a new instance
@@ -145,21 +416,33 @@ pub async fn create_instance(pool: &DbPool, input: CreateInstanceInput) -> Resul
     let id = uuid::Uuid::new_v4().to_string();
     let now = Utc::now().to_rfc3339();
 
+    let mut tx = pool.begin().await?;
+
+    // New instances go to the end of the manual ordering
+    let (sort_order,): (i64,) = sqlx::query_as("SELECT COALESCE(MAX(sort_order), -1) + 1 FROM instances")
+        .fetch_one(&mut *tx)
+        .await?;
+
     sqlx::query(
         r#"
-        INSERT INTO instances (id, name, path, java_path, created_at, updated_at)
-        VALUES (?, ?, ?, ?, ?, ?)
+        INSERT INTO instances (id, name, path, java_path, env_vars, notes, created_at, updated_at, sort_order)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#,
     )
     .bind(&id)
     .bind(&input.name)
     .bind(&input.path)
     .bind(&input.java_path)
+    .bind(&input.env_vars)
+    .bind(&input.notes)
     .bind(&now)
     .bind(&now)
-    .execute(pool)
+    .bind(sort_order)
+    .execute(&mut *tx)
     .await?;
 
+    tx.commit().await?;
+
     Ok(Instance {
         id,
         name: input.name,
@@ -173,6 +456,15 @@ pub async fn create_instance(pool: &DbPool, input: CreateInstanceInput) -> Resul
         auth_persistence: Some("memory".to_string()),
         auth_profile_name: None,
         installed_version: None,
+        autostart: false,
+        env_vars: input.env_vars,
+        stop_command: None,
+        stop_command_timeout_secs: None,
+        deleted_at: None,
+        stop_timeout_secs: 10,
+        sort_order,
+        launch_wrapper: None,
+        notes: input.notes,
     })
 }
 
@@ -181,9 +473,57 @@ pub async fn get_all_instances(pool: &DbPool) -> Result<Vec<Instance>, sqlx::Err
     let instances = sqlx::query_as::<_, Instance>(
         r#"
         SELECT id, name, path, java_path, jvm_args, server_args, created_at, updated_at,
-               auth_status, auth_persistence, auth_profile_name, installed_version
+               auth_status, auth_persistence, auth_profile_name, installed_version,
+               autostart, env_vars, stop_command, stop_command_timeout_secs, deleted_at,
+               stop_timeout_secs, sort_order, launch_wrapper, notes
+        FROM instances
+        WHERE deleted_at IS NULL
+        ORDER BY sort_order ASC, created_at DESC
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(instances)
+}
+
+/// Get instances whose name contains `query` (case-insensitive for ASCII), in the same order as get_all_instances
+pub async fn search_instances(pool: &DbPool, query: &str) -> Result<Vec<Instance>, sqlx::Error> {
+    // Match % and _ in the query literally
+    let pattern = format!(
+        "%{}%",
+        query.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+    );
+
+    let instances = sqlx::query_as::<_, Instance>(
+        r#"
+        SELECT id, name, path, java_path, jvm_args, server_args, created_at, updated_at,
+               auth_status, auth_persistence, auth_profile_name, installed_version,
+               autostart, env_vars, stop_command, stop_command_timeout_secs, deleted_at,
+               stop_timeout_secs, sort_order, launch_wrapper, notes
+        FROM instances
+        WHERE deleted_at IS NULL AND name LIKE ? ESCAPE '\'
+        ORDER BY sort_order ASC, created_at DESC
+        "#
+    )
+    .bind(pattern)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(instances)
+}
+
+/// Get instances flagged to start when the app launches
+pub async fn get_autostart_instances(pool: &DbPool) -> Result<Vec<Instance>, sqlx::Error> {
+    let instances = sqlx::query_as::<_, Instance>(
+        r#"
+        SELECT id, name, path, java_path, jvm_args, server_args, created_at, updated_at,
+               auth_status, auth_persistence, auth_profile_name, installed_version,
+               autostart, env_vars, stop_command, stop_command_timeout_secs, deleted_at,
+               stop_timeout_secs, sort_order, launch_wrapper, notes
         FROM instances
-        ORDER BY created_at DESC
+        WHERE autostart = 1 AND deleted_at IS NULL
+        ORDER BY created_at ASC
         "#
     )
     .fetch_all(pool)
@@ -197,7 +537,9 @@ pub async fn get_instance_by_id(pool: &DbPool, id: &str) -> Result<Option<Instan
     let instance = sqlx::query_as::<_, Instance>(
         r#"
         SELECT id, name, path, java_path, jvm_args, server_args, created_at, updated_at,
-               auth_status, auth_persistence, auth_profile_name, installed_version
+               auth_status, auth_persistence, auth_profile_name, installed_version,
+               autostart, env_vars, stop_command, stop_command_timeout_secs, deleted_at,
+               stop_timeout_secs, sort_order, launch_wrapper, notes
         FROM instances
         WHERE id = ?
         "#
@@ -214,7 +556,9 @@ pub async fn get_instance_by_path(pool: &DbPool, path: &str) -> Result<Option<In
     let instance = sqlx::query_as::<_, Instance>(
         r#"
         SELECT id, name, path, java_path, jvm_args, server_args, created_at, updated_at,
-               auth_status, auth_persistence, auth_profile_name, installed_version
+               auth_status, auth_persistence, auth_profile_name, installed_version,
+               autostart, env_vars, stop_command, stop_command_timeout_secs, deleted_at,
+               stop_timeout_secs, sort_order, launch_wrapper, notes
         FROM instances
         WHERE path = ?
         "#
@@ -226,17 +570,95 @@ pub async fn get_instance_by_path(pool: &DbPool, path: &str) -> Result<Option<In
     Ok(instance)
 }
 
-/// Delete instance by ID
+/// Get instances in the trash, most recently deleted first
+pub async fn get_deleted_instances(pool: &DbPool) -> Result<Vec<Instance>, sqlx::Error> {
+    let instances = sqlx::query_as::<_, Instance>(
+        r#"
+        SELECT id, name, path, java_path, jvm_args, server_args, created_at, updated_at,
+               auth_status, auth_persistence, auth_profile_name, installed_version,
+               autostart, env_vars, stop_command, stop_command_timeout_secs, deleted_at,
+               stop_timeout_secs, sort_order, launch_wrapper, notes
+        FROM instances
+        WHERE deleted_at IS NOT NULL
+        ORDER BY deleted_at DESC
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(instances)
+}
+
+/// Move an instance to the trash
+pub async fn soft_delete_instance(pool: &DbPool, id: &str) -> Result<bool, sqlx::Error> {
+    let now = Utc::now().to_rfc3339();
+
+    let result = sqlx::query("UPDATE instances SET deleted_at = ? WHERE id = ? AND deleted_at IS NULL")
+        .bind(&now)
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Restore an instance from the trash
+pub async fn restore_instance(pool: &DbPool, id: &str) -> Result<bool, sqlx::Error> {
+    let now = Utc::now().to_rfc3339();
+
+    let result = sqlx::query(
+        "UPDATE instances SET deleted_at = NULL, updated_at = ? WHERE id = ? AND deleted_at IS NOT NULL"
+    )
+    .bind(&now)
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Permanently delete instance by ID, with its history, settings and running-server
+/// record, in one transaction so a failure never leaves orphaned rows
 pub async fn delete_instance(pool: &DbPool, id: &str) -> Result<bool, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    for statement in [
+        "DELETE FROM command_history WHERE instance_id = ?",
+        "DELETE FROM instance_settings WHERE instance_id = ?",
+        "DELETE FROM running_servers WHERE instance_id = ?",
+    ] {
+        sqlx::query(statement).bind(id).execute(&mut *tx).await?;
+    }
+
     let result = sqlx::query("DELETE FROM instances WHERE id = ?")
         .bind(id)
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
 
+    tx.commit().await?;
     Ok(result.rows_affected() > 0)
 }
 
-/// Update instance
+/// Assign sort_order 0..n to the given instances in one transaction.
+/// Instances not listed keep their current position value.
+pub async fn reorder_instances(pool: &DbPool, ordered_ids: &[String]) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    for (index, id) in ordered_ids.iter().enumerate() {
+        sqlx::query("UPDATE instances SET sort_order = ? WHERE id = ?")
+            .bind(index as i64)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Update instance fields; None leaves a field unchanged.
+/// Returns the updated instance, or None if no instance has this ID.
+#[allow(clippy::too_many_arguments)]
 pub async fn update_instance(
     pool: &DbPool,
     id: &str,
@@ -244,7 +666,14 @@ pub async fn update_instance(
     java_path: Option<String>,
     jvm_args: Option<String>,
     server_args: Option<String>,
-) -> Result<bool, sqlx::Error> {
+    autostart: Option<bool>,
+    env_vars: Option<String>,
+    stop_command: Option<String>,
+    stop_command_timeout_secs: Option<u32>,
+    stop_timeout_secs: Option<u32>,
+    launch_wrapper: Option<String>,
+    notes: Option<String>,
+) -> Result<Option<Instance>, sqlx::Error> {
     let now = Utc::now().to_rfc3339();
 
     // Build dynamic update query
@@ -267,21 +696,55 @@ pub async fn update_instance(
         updates.push("server_args = ?");
         values.push(sa);
     }
+    if let Some(a) = autostart {
+        updates.push("autostart = ?");
+        values.push(if a { "1" } else { "0" }.to_string());
+    }
+    if let Some(ev) = env_vars {
+        updates.push("env_vars = ?");
+        values.push(ev);
+    }
+    if let Some(sc) = stop_command {
+        updates.push("stop_command = ?");
+        values.push(sc);
+    }
+    if let Some(t) = stop_command_timeout_secs {
+        updates.push("stop_command_timeout_secs = ?");
+        values.push(t.to_string());
+    }
+    if let Some(t) = stop_timeout_secs {
+        updates.push("stop_timeout_secs = ?");
+        values.push(t.to_string());
+    }
+    if let Some(w) = launch_wrapper {
+        updates.push("launch_wrapper = ?");
+        values.push(w);
+    }
+    if let Some(n) = notes {
+        updates.push("notes = ?");
+        values.push(n);
+    }
 
+    // RETURNING hands back the row as written, so callers don't need a second
+    // query that could see another update in between
     let query = format!(
-        "UPDATE instances SET {} WHERE id = ?",
+        r#"
+        UPDATE instances SET {} WHERE id = ?
+        RETURNING id, name, path, java_path, jvm_args, server_args, created_at, updated_at,
+                  auth_status, auth_persistence, auth_profile_name, installed_version,
+                  autostart, env_vars, stop_command, stop_command_timeout_secs, deleted_at,
+                  stop_timeout_secs, sort_order, launch_wrapper, notes
+        "#,
         updates.join(", ")
     );
 
-    let mut q = sqlx::query(&query);
+    let mut q = sqlx::query_as::<_, Instance>(&query);
     for v in values {
         q = q.bind(v);
     }
     q = q.bind(id);
 
-    let result = q.execute(pool).await?;
-
-    Ok(result.rows_affected() > 0)
+    q.fetch_optional(pool).await
 }
 
 /// Update instance auth status
@@ -326,6 +789,21 @@ pub async fn update_instance_auth(
     Ok(result.rows_affected() > 0)
 }
 
+/// Mark an instance as logged out: unauthenticated, with no profile or stored credentials
+pub async fn reset_instance_auth(pool: &DbPool, id: &str) -> Result<bool, sqlx::Error> {
+    let now = Utc::now().to_rfc3339();
+
+    let result = sqlx::query(
+        "UPDATE instances SET auth_status = 'unauthenticated', auth_persistence = NULL, auth_profile_name = NULL, updated_at = ? WHERE id = ?",
+    )
+    .bind(&now)
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
 // ============================================================================
 // Settings operations
 // ============================================================================
@@ -367,6 +845,99 @@ pub async fn set_onboarding_completed(pool: &DbPool) -> Result<(), sqlx::Error>
     set_setting(pool, "onboarding_completed", "true").await
 }
 
+// ============================================================================
+// Instance settings operations
+// ============================================================================
+
+/// Get a per-instance setting value
+pub async fn get_instance_setting(
+    pool: &DbPool,
+    instance_id: &str,
+    key: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    let row: Option<(String,)> =
+        sqlx::query_as("SELECT value FROM instance_settings WHERE instance_id = ? AND key = ?")
+            .bind(instance_id)
+            .bind(key)
+            .fetch_optional(pool)
+            .await?;
+
+    Ok(row.map(|r| r.0))
+}
+
+/// Set a per-instance setting value
+pub async fn set_instance_setting(
+    pool: &DbPool,
+    instance_id: &str,
+    key: &str,
+    value: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO instance_settings (instance_id, key, value) VALUES (?, ?, ?)
+        ON CONFLICT(instance_id, key) DO UPDATE SET value = excluded.value
+        "#,
+    )
+    .bind(instance_id)
+    .bind(key)
+    .bind(value)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+// ============================================================================
+// Running server operations
+// ============================================================================
+
+/// A server process the app started, remembered so it can be re-adopted after a restart
+#[derive(Debug, Clone, FromRow)]
+pub struct RunningServer {
+    pub instance_id: String,
+    pub pid: i64,
+    pub started_at: String,
+}
+
+/// Remember the PID of a server process the app started
+pub async fn record_running_server(
+    pool: &DbPool,
+    instance_id: &str,
+    pid: u32,
+    started_at: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO running_servers (instance_id, pid, started_at) VALUES (?, ?, ?)
+        ON CONFLICT(instance_id) DO UPDATE SET pid = excluded.pid, started_at = excluded.started_at
+        "#,
+    )
+    .bind(instance_id)
+    .bind(pid as i64)
+    .bind(started_at)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Forget the server process of an instance once it has exited
+pub async fn clear_running_server(pool: &DbPool, instance_id: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM running_servers WHERE instance_id = ?")
+        .bind(instance_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Get all server processes recorded as running
+pub async fn get_running_servers(pool: &DbPool) -> Result<Vec<RunningServer>, sqlx::Error> {
+    sqlx::query_as::<_, RunningServer>("SELECT instance_id, pid, started_at FROM running_servers")
+        .fetch_all(pool)
+        .await
+}
+
 // ============================================================================
 // Version tracking operations
 // ============================================================================
@@ -390,3 +961,77 @@ pub async fn update_instance_version(
 
     Ok(result.rows_affected() > 0)
 }
+
+// ============================================================================
+// Command history operations
+// ============================================================================
+
+/// Append a console command to an instance's history, skipping consecutive
+/// duplicates and trimming the history to the most recent `max_entries`
+pub async fn add_command_history(
+    pool: &DbPool,
+    instance_id: &str,
+    command: &str,
+    max_entries: u32,
+) -> Result<(), sqlx::Error> {
+    let last: Option<(String,)> = sqlx::query_as(
+        "SELECT command FROM command_history WHERE instance_id = ? ORDER BY id DESC LIMIT 1",
+    )
+    .bind(instance_id)
+    .fetch_optional(pool)
+    .await?;
+
+    if last.map(|r| r.0 == command).unwrap_or(false) {
+        return Ok(());
+    }
+
+    let now = Utc::now().to_rfc3339();
+
+    sqlx::query("INSERT INTO command_history (instance_id, command, sent_at) VALUES (?, ?, ?)")
+        .bind(instance_id)
+        .bind(command)
+        .bind(&now)
+        .execute(pool)
+        .await?;
+
+    sqlx::query(
+        r#"
+        DELETE FROM command_history
+        WHERE instance_id = ? AND id NOT IN (
+            SELECT id FROM command_history WHERE instance_id = ? ORDER BY id DESC LIMIT ?
+        )
+        "#,
+    )
+    .bind(instance_id)
+    .bind(instance_id)
+    .bind(max_entries as i64)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Get the most recent commands sent to an instance, oldest first
+pub async fn get_command_history(
+    pool: &DbPool,
+    instance_id: &str,
+    limit: u32,
+) -> Result<Vec<String>, sqlx::Error> {
+    let rows: Vec<(String,)> = sqlx::query_as(
+        r#"
+        SELECT command FROM (
+            SELECT id, command FROM command_history
+            WHERE instance_id = ?
+            ORDER BY id DESC
+            LIMIT ?
+        )
+        ORDER BY id ASC
+        "#,
+    )
+    .bind(instance_id)
+    .bind(limit as i64)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(|r| r.0).collect())
+}
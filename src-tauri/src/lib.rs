@@ -8,30 +8,46 @@ use commands::{
     check_downloader, check_downloader_update, check_java, check_server_files,
     check_instance_paths, complete_onboarding, copy_server_files, create_instance,
     create_server_instance, delete_server_instance, download_server_files, get_downloader_info,
-    get_downloader_version, get_server_instance, get_server_instances, get_system_paths,
+    get_downloader_version, get_patchlines, get_downloader_path, set_downloader_path, load_downloader_path, get_server_instance, get_server_instances, search_instances, get_system_paths,
     install_downloader_cli, is_onboarding_complete, update_server_instance, validate_server_files,
-    update_instance_auth_status,
+    get_onboarding_state, set_onboarding_step,
+    update_instance_auth_status, clear_instance_auth, duplicate_instance, import_instance,
+    export_instance_config, import_instance_config, get_default_args, set_default_args,
+    is_autostart_disabled, set_autostart_disabled, get_instance_setting, set_instance_setting,
+    list_deleted_instances, restore_instance, purge_instance, reorder_instances,
     // Server management
-    start_server, stop_server, get_server_status, get_all_server_statuses, send_server_command,
-    get_online_players, ServerState,
+    start_server, stop_server, get_server_status, get_all_server_statuses, get_server_uptime, get_crash_history, send_server_command,
+    get_command_history, broadcast_command, send_command_await, get_online_players,
+    get_recent_output, set_output_filter, set_output_batching, get_launch_config, get_console_buffer_lines, set_console_buffer_lines,
+    get_memory_guard_block, set_memory_guard_block,
+    start_auth, persist_auth, start_all_servers, stop_all_servers, autostart_instances, adopt_orphaned_servers, ServerState,
     // Logs
-    list_log_files, read_log_file, tail_log_file,
+    list_log_files, read_log_file, tail_log_file, start_log_follow, poll_log_follow, stop_log_follow,
+    compress_old_logs, apply_log_retention, LogFollowState,
     // Metrics
-    get_server_metrics, get_all_server_metrics, get_system_metrics, MetricsState,
+    get_server_metrics, get_all_server_metrics, get_system_metrics, get_instance_disk_usage,
+    start_metrics_broadcast, stop_metrics_broadcast, recommend_jvm_args, validate_jvm_args,
+    start_metrics_server, stop_metrics_server, MetricsState,
     // Network
-    get_firewall_info, add_firewall_rule, remove_firewall_rule,
+    get_firewall_info, get_instance_firewall_info, add_firewall_rule, remove_firewall_rule, get_public_ip, check_server_reachable,
+    // Notifications
+    get_webhook_settings, set_webhook_settings, test_webhook,
     // Version checking
     get_version_settings, set_version_settings, check_all_versions, check_instance_version,
-    update_instance_installed_version, dismiss_version_banner, get_dismissed_version,
-    start_version_check_background_task,
+    update_instance_installed_version, detect_installed_version, update_instance, dismiss_version_banner, get_dismissed_version,
+    start_version_check_background_task, get_last_version_check, VersionCheckNotifier,
+    // Database backup
+    backup_database, restore_database, get_database_status, DatabaseInitStatus,
+    // Diagnostics
+    diagnose_instance,
     // Config files
     read_json_file, write_json_file, write_json_file_raw,
     get_whitelist, save_whitelist,
     get_bans, save_bans,
     get_permissions, save_permissions,
-    get_server_config, save_server_config,
+    get_server_config, save_server_config, save_server_config_raw, get_default_world, set_default_world,
     // Worlds
-    list_worlds, get_world_config, save_world_config, delete_world, duplicate_world,
+    list_worlds, get_world_details, get_world_config, save_world_config, set_world_toggle, set_world_seed, list_gameplay_configs, delete_world, delete_worlds, duplicate_world, rename_world,
 };
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -67,14 +83,25 @@ pub fn run() {
             handle.manage(Arc::new(Mutex::new(MetricsState::new())));
             println!("[app] Metrics state initialized");
 
+            // Initialize log follow state (background log readers)
+            handle.manage(Arc::new(Mutex::new(LogFollowState::new())));
+
+            // Initialize version check notifier (wakes the background task on settings change)
+            handle.manage(VersionCheckNotifier::new());
+
             tauri::async_runtime::block_on(async move {
                 match database::init_db(&handle).await {
                     Ok(pool) => {
+                        load_downloader_path(&pool).await;
                         handle.manage(pool);
+                        handle.manage(DatabaseInitStatus { error: None });
                         println!("[app] Database initialized and managed");
                     }
                     Err(e) => {
                         eprintln!("[app] Failed to initialize database: {}", e);
+                        handle.manage(DatabaseInitStatus {
+                            error: Some(e.to_string()),
+                        });
                     }
                 }
             });
@@ -86,6 +113,14 @@ pub fn run() {
             });
             println!("[app] Background version check task started");
 
+            // Re-adopt orphaned servers, then start instances flagged for autostart
+            let autostart_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                // Pick up servers left running by a previous session first, so they aren't started twice
+                adopt_orphaned_servers(autostart_handle.clone()).await;
+                autostart_instances(autostart_handle).await;
+            });
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -101,47 +136,113 @@ pub fn run() {
             check_downloader,
             get_downloader_info,
             get_downloader_version,
+            get_patchlines,
+            get_downloader_path,
+            set_downloader_path,
             check_downloader_update,
             download_server_files,
             install_downloader_cli,
             // Instance management (database)
             create_server_instance,
             get_server_instances,
+            search_instances,
             get_server_instance,
             delete_server_instance,
             update_server_instance,
             check_instance_paths,
             update_instance_auth_status,
+            clear_instance_auth,
+            duplicate_instance,
+            export_instance_config,
+            import_instance_config,
+            get_default_args,
+            set_default_args,
+            import_instance,
+            is_autostart_disabled,
+            set_autostart_disabled,
+            get_instance_setting,
+            set_instance_setting,
+            list_deleted_instances,
+            restore_instance,
+            purge_instance,
+            reorder_instances,
             // Onboarding
             is_onboarding_complete,
             complete_onboarding,
+            get_onboarding_state,
+            set_onboarding_step,
             // Server management
             start_server,
             stop_server,
+            start_all_servers,
+            stop_all_servers,
             get_server_status,
             get_all_server_statuses,
+            get_server_uptime,
+            get_crash_history,
             send_server_command,
+            get_command_history,
+            broadcast_command,
+            send_command_await,
+            get_recent_output,
+            set_output_filter,
+            set_output_batching,
+            get_launch_config,
+            get_console_buffer_lines,
+            set_console_buffer_lines,
+            get_memory_guard_block,
+            set_memory_guard_block,
+            start_auth,
+            persist_auth,
             get_online_players,
             // Logs
             list_log_files,
             read_log_file,
             tail_log_file,
+            start_log_follow,
+            poll_log_follow,
+            stop_log_follow,
+            compress_old_logs,
+            apply_log_retention,
             // Metrics
             get_server_metrics,
             get_all_server_metrics,
             get_system_metrics,
+            get_instance_disk_usage,
+            start_metrics_broadcast,
+            stop_metrics_broadcast,
+            start_metrics_server,
+            stop_metrics_server,
+            recommend_jvm_args,
+            validate_jvm_args,
             // Network
             get_firewall_info,
+            get_instance_firewall_info,
             add_firewall_rule,
             remove_firewall_rule,
+            get_public_ip,
+            check_server_reachable,
+            // Notifications
+            get_webhook_settings,
+            set_webhook_settings,
+            test_webhook,
             // Version checking
             get_version_settings,
             set_version_settings,
             check_all_versions,
             check_instance_version,
             update_instance_installed_version,
+            detect_installed_version,
+            update_instance,
             dismiss_version_banner,
             get_dismissed_version,
+            get_last_version_check,
+            // Database backup
+            backup_database,
+            restore_database,
+            get_database_status,
+            // Diagnostics
+            diagnose_instance,
             // Config files
             read_json_file,
             write_json_file,
@@ -154,12 +255,21 @@ pub fn run() {
             save_permissions,
             get_server_config,
             save_server_config,
+            save_server_config_raw,
+            get_default_world,
+            set_default_world,
             // Worlds
             list_worlds,
+            get_world_details,
             get_world_config,
             save_world_config,
+            set_world_toggle,
+            set_world_seed,
+            list_gameplay_configs,
             delete_world,
-            duplicate_world
+            delete_worlds,
+            duplicate_world,
+            rename_world
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -1,20 +1,34 @@
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { Loader2 } from "lucide-react";
+import { Loader2, DatabaseZap } from "lucide-react";
 import { WindowTitlebar } from "@/components/layout/WindowTitlebar";
 import { Onboarding } from "@/components/Onboarding";
 import { Dashboard } from "@/components/Dashboard";
 import { ConsoleStoreProvider } from "@/lib/console-store";
 import { AppUpdateNotification } from "@/components/AppUpdateNotification";
+import type { DatabaseStatus } from "@/lib/types";
 
 function App() {
   const [loading, setLoading] = useState(true);
   const [onboardingComplete, setOnboardingComplete] = useState(false);
+  const [databaseError, setDatabaseError] = useState<string | null>(null);
 
   useEffect(() => {
     checkOnboarding();
+    checkDatabase();
   }, []);
 
+  async function checkDatabase() {
+    try {
+      const status = await invoke<DatabaseStatus>("get_database_status");
+      if (!status.available && status.error) {
+        setDatabaseError(status.error);
+      }
+    } catch (err) {
+      console.error("Failed to check database status:", err);
+    }
+  }
+
   async function checkOnboarding() {
     try {
       const complete = await invoke<boolean>("is_onboarding_complete");
@@ -42,6 +56,17 @@ function App() {
     <ConsoleStoreProvider>
       {onboardingComplete ? <Dashboard /> : <Onboarding />}
       <AppUpdateNotification />
+      {databaseError && (
+        <div className="fixed bottom-4 left-4 z-50 max-w-sm flex items-start gap-3 rounded-lg border border-red-500/30 bg-red-500/10 p-3 text-sm backdrop-blur">
+          <DatabaseZap className="w-4 h-4 mt-0.5 shrink-0 text-red-500" />
+          <div>
+            <p className="font-medium text-red-500">Database unavailable</p>
+            <p className="text-muted-foreground">
+              Settings and instances won't be saved. {databaseError}
+            </p>
+          </div>
+        </div>
+      )}
     </ConsoleStoreProvider>
   );
 }
@@ -13,7 +13,7 @@ import { CreateServerDialog } from "@/components/CreateServerDialog";
 import { ImportServerDialog } from "@/components/ImportServerDialog";
 import { UpdateBanner } from "@/components/UpdateBanner";
 import { useVersionCheck } from "@/hooks/useVersionCheck";
-import type { Instance, InstancesListResult, ServerStatusInfo } from "@/lib/types";
+import type { Instance, InstancePathsCheck, InstancesListResult, ServerStatusInfo } from "@/lib/types";
 
 export function Dashboard() {
   const [loading, setLoading] = useState(true);
@@ -73,8 +73,8 @@ export function Dashboard() {
         // Check which folders are missing
         if (result.instances.length > 0) {
           const paths = result.instances.map((i) => [i.id, i.path] as [string, string]);
-          const missing = await invoke<string[]>("check_instance_paths", { paths });
-          setMissingFolders(new Set(missing));
+          const check = await invoke<InstancePathsCheck>("check_instance_paths", { paths });
+          setMissingFolders(new Set(check.missing));
         }
       } else {
         setError(result.error || "Failed to load instances");
@@ -102,13 +102,13 @@ export function Onboarding() {
     checkSystem();
   }, []);
 
-  async function checkSystem() {
+  async function checkSystem(force = false) {
     setLoading(true);
     setError(null);
 
     try {
       const [javaInfo, pathsInfo, downloaderInfo] = await Promise.all([
-        invoke<JavaInfo>("check_java"),
+        invoke<JavaInfo>("check_java", { force }),
         invoke<SystemPaths>("get_system_paths"),
         invoke<DownloaderInfo>("get_downloader_info"),
       ]);
@@ -367,7 +367,7 @@ export function Onboarding() {
             </CardContent>
 
             <CardFooter className="flex gap-3">
-              <Button variant="outline" onClick={checkSystem} disabled={loading} className="flex-1">
+              <Button variant="outline" onClick={() => checkSystem(true)} disabled={loading} className="flex-1">
                 {loading ? (
                   <Loader2 className="w-4 h-4 animate-spin" />
                 ) : (
@@ -34,6 +34,7 @@ import type {
   ServerStatus,
   ServerStatusInfo,
   ServerOutput,
+  ServerExitEvent,
   StartResult,
   StopResult,
   AuthEvent,
@@ -181,6 +182,22 @@ export function ServerDetailView({ instance: initialInstance, allInstances, onBa
     const unlisteners: UnlistenFn[] = [];
 
     async function setupListeners() {
+      // Restore console history kept by the backend (e.g. after a window reload)
+      if (consoleStore.getMessages(instance.id).length === 0) {
+        try {
+          const history = await invoke<ServerOutput[]>("get_recent_output", {
+            instanceId: instance.id,
+          });
+          if (isMounted) {
+            for (const output of history) {
+              addMessageRef.current(output.line, output.stream as "stdout" | "stderr");
+            }
+          }
+        } catch (err) {
+          console.error("Failed to load console history:", err);
+        }
+      }
+
       const outputUnlisten = await listen<ServerOutput>("server-output", (event) => {
         if (isMounted && event.payload.instance_id === instance.id) {
           addMessageRef.current(
@@ -279,9 +296,12 @@ export function ServerDetailView({ instance: initialInstance, allInstances, onBa
       });
       if (isMounted) unlisteners.push(persistenceUnlisten);
 
-      const exitUnlisten = await listen<string>("server-exit", (event) => {
-        if (isMounted && event.payload === instance.id) {
-          addMessageRef.current("Server process exited", "system");
+      const exitUnlisten = await listen<ServerExitEvent>("server-exit", (event) => {
+        if (isMounted && event.payload.instance_id === instance.id) {
+          const { code, signal } = event.payload;
+          const detail =
+            code !== null ? ` with code ${code}` : signal !== null ? ` by signal ${signal}` : "";
+          addMessageRef.current(`Server process exited${detail}`, "system");
         }
       });
       if (isMounted) unlisteners.push(exitUnlisten);
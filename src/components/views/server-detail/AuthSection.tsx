@@ -12,7 +12,7 @@ import {
 } from "lucide-react";
 import { Button } from "@/components/ui/button";
 import { Badge } from "@/components/ui/badge";
-import type { Instance } from "@/lib/types";
+import type { AuthCommandResult, Instance } from "@/lib/types";
 
 interface AuthSectionProps {
   instance: Instance;
@@ -36,10 +36,12 @@ export function AuthSection({ instance, isRunning, onRefresh }: AuthSectionProps
     setIsStartingAuth(true);
 
     try {
-      await invoke("send_server_command", {
+      const result = await invoke<AuthCommandResult>("start_auth", {
         instanceId: instance.id,
-        command: "/auth login device",
       });
+      if (!result.success) {
+        console.error("Failed to start auth:", result.error);
+      }
     } catch (err) {
       console.error("Failed to start auth:", err);
     } finally {
@@ -52,17 +54,12 @@ export function AuthSection({ instance, isRunning, onRefresh }: AuthSectionProps
     setIsSavingPersistence(true);
 
     try {
-      await invoke("send_server_command", {
+      const result = await invoke<AuthCommandResult>("persist_auth", {
         instanceId: instance.id,
-        command: "/auth persistence Encrypted",
-      });
-
-      await invoke("update_instance_auth_status", {
-        instanceId: instance.id,
-        authStatus: null,
-        authPersistence: "encrypted",
-        authProfileName: null,
       });
+      if (!result.success) {
+        console.error("Failed to save persistence:", result.error);
+      }
 
       onRefresh?.();
     } catch (err) {
@@ -17,9 +17,11 @@ interface FirewallInfo {
   os: string;
   firewall_type: string | null;
   firewall_enabled: boolean;
+  profiles: Record<string, boolean> | null; // Domain/Private/Public, Windows only
   rule_exists: boolean;
   rule_name: string;
   port: number;
+  protocol: string; // "udp", "tcp" or "both"
   command_to_add: string;
   command_to_remove: string;
   error: string | null;
@@ -29,6 +31,7 @@ interface FirewallResult {
   success: boolean;
   message: string;
   error: string | null;
+  action: "created" | "updated" | "unchanged" | null;
 }
 
 interface NetworkSectionProps {
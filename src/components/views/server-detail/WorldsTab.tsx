@@ -83,6 +83,7 @@ export function WorldsTab({ instance, serverStatus }: WorldsTabProps) {
       const result = await invoke<JsonWriteResult>("duplicate_world", {
         worldPath: worldToDuplicate.path,
         newName: duplicateName.trim(),
+        instanceId: instance.id,
       });
 
       if (result.success) {
@@ -107,6 +108,7 @@ export function WorldsTab({ instance, serverStatus }: WorldsTabProps) {
     try {
       const result = await invoke<JsonWriteResult>("delete_world", {
         worldPath: worldToDelete.path,
+        instanceId: instance.id,
       });
 
       if (result.success) {
@@ -10,12 +10,12 @@ export function useSystemCheck() {
     error: null,
   });
 
-  const checkSystem = useCallback(async () => {
+  const checkSystem = useCallback(async (force = false) => {
     setStatus((prev) => ({ ...prev, loading: true, error: null }));
 
     try {
       const [java, paths] = await Promise.all([
-        invoke<JavaInfo>("check_java"),
+        invoke<JavaInfo>("check_java", { force }),
         invoke<SystemPaths>("get_system_paths"),
       ]);
 
@@ -39,5 +39,8 @@ export function useSystemCheck() {
     checkSystem();
   }, [checkSystem]);
 
-  return { ...status, refresh: checkSystem };
+  // Refreshing skips the cached Java result so a fresh install is picked up
+  const refresh = useCallback(() => checkSystem(true), [checkSystem]);
+
+  return { ...status, refresh };
 }
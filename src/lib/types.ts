@@ -38,14 +38,22 @@ export interface CopyResult {
   files_copied: number;
   destination: string;
   error: string | null;
+  mismatches: CopyMismatch[];
+}
+
+export interface CopyMismatch {
+  path: string;              // Relative to the destination folder
+  reason: string;
+  source_size: number | null;
+  dest_size: number | null;
 }
 
 export interface CopyProgress {
-  current: number;
-  total: number;
-  current_file: string;
-  done: boolean;
-  error: string | null;
+  status: string;            // "copying" | "verifying" | "completed"
+  percentage: number | null; // Byte-based
+  message: string;
+  files_copied: number;
+  total_files: number;
 }
 
 export interface DownloadProgress {
@@ -62,10 +70,29 @@ export interface DownloaderInfo {
   error: string | null;
 }
 
+// Machine-readable error category sent next to error messages
+export type ErrorCode =
+  | "already_running"
+  | "server_files_missing"
+  | "java_unavailable"
+  | "invalid_jvm_args"
+  | "port_in_use"
+  | "insufficient_memory"
+  | "cli_missing"
+  | "download_failed"
+  | "extract_failed"
+  | "invalid_config"
+  | "not_found"
+  | "permission_denied"
+  | "default_world"
+  | "io";
+
 export interface DownloadResult {
   success: boolean;
   output_path: string | null;
+  preserved_files?: string[];
   error: string | null;
+  error_code?: ErrorCode | null;
 }
 
 export interface InstallCliResult {
@@ -97,6 +124,51 @@ export interface Instance {
   auth_profile_name: string | null;  // e.g. "Natxo"
   // Version tracking
   installed_version: string | null;  // e.g. "0.1.0"
+  // Start automatically when the app launches
+  autostart: boolean;
+  // Extra environment variables for the server process (JSON object)
+  env_vars: string | null;
+  // Console command sent on stop and seconds to wait before signalling
+  stop_command: string | null;             // null = "stop"
+  stop_command_timeout_secs: number | null; // null = 30
+  // Set when the instance is in the trash
+  deleted_at: string | null;
+  // Seconds between SIGTERM and a forced kill
+  stop_timeout_secs: number;
+  // Position in the manual ordering (lowest first)
+  sort_order: number;
+  // Command the server is launched through, e.g. "nice -n 10" (priority name on Windows)
+  launch_wrapper: string | null;
+  // Free-form notes for the user's own reference
+  notes: string | null;
+}
+
+// Arguments applied to newly created instances
+export interface DefaultArgs {
+  jvm_args: string | null;
+  server_args: string | null;
+}
+
+// Portable instance settings and config files (no path, auth, Java path, env vars or password)
+export interface InstanceBundle {
+  version: number;
+  exported_at: string;
+  name: string;
+  jvm_args: string | null;
+  server_args: string | null;
+  autostart: boolean;
+  stop_command: string | null;
+  stop_command_timeout_secs: number | null;
+  stop_timeout_secs: number;
+  launch_wrapper: string | null;
+  notes: string | null;
+  files: Record<string, unknown>;   // Server/ file name -> parsed JSON
+}
+
+export interface InstanceBundleResult {
+  success: boolean;
+  bundle: InstanceBundle | null;
+  error: string | null;
 }
 
 export interface InstanceResult {
@@ -116,6 +188,26 @@ export interface DeleteResult {
   error: string | null;
 }
 
+export interface OnboardingStep {
+  id: "java" | "server_files" | "instance";
+  done: boolean;
+}
+
+export interface OnboardingState {
+  completed: boolean;
+  java_ready: boolean;
+  cli_installed: boolean;
+  has_instances: boolean;
+  steps: OnboardingStep[];
+  next_step: OnboardingStep["id"] | null;  // First step that isn't done yet
+}
+
+export interface ClearAuthResult {
+  success: boolean;
+  removed_files: string[];
+  error: string | null;
+}
+
 // Server management types
 export type ServerStatus = "stopped" | "starting" | "running" | "stopping";
 
@@ -126,22 +218,113 @@ export interface ServerStatusInfo {
   started_at: string | null;
 }
 
+export interface ServerUptime {
+  instance_id: string;
+  started_at: string;
+  uptime_seconds: number;
+}
+
 export interface ServerOutput {
   instance_id: string;
   line: string;
   stream: "stdout" | "stderr";
   timestamp: string;
+  spans: OutputSpan[] | null;  // Styled segments, only when the line has ANSI codes
+}
+
+export interface OutputSpan {
+  text: string;
+  color: string | null;  // Color name like "red"/"bright_blue", or "#rrggbb"
+  bold: boolean;
+}
+
+export interface ServerExitEvent {
+  instance_id: string;
+  code: number | null;
+  signal: number | null;         // Unix only
+  last_lines: string[];          // Most recent output lines, oldest first
+}
+
+export interface ServerPortChangedEvent {
+  instance_path: string;
+  old_port: number;
+  new_port: number;
+}
+
+export interface ExitRecord {
+  exited_at: string;
+  code: number | null;
+  signal: number | null;
+  abnormal: boolean;             // Anything other than exit code 0, unless requested by stop_server
+}
+
+export interface CrashLoopEvent {
+  instance_id: string;
+  crashes: number;               // Abnormal exits within the window
+  window_secs: number;
+}
+
+export interface ServerStartTimeoutEvent {
+  instance_id: string;
+  timeout_secs: number;
+  killed: boolean;               // Process was killed because of the timeout
+}
+
+export interface ServerMemoryWarningEvent {
+  instance_id: string;
+  requested_mb: number;          // -Xmx of the server being started
+  running_mb: number;            // Sum of -Xmx of servers already running
+  limit_mb: number;              // Physical memory minus a reserve for the OS
+  blocked: boolean;              // Start was refused (memory_guard_block setting)
+}
+
+export interface CommandResponse {
+  success: boolean;
+  lines: string[];   // Output lines seen after the command, ANSI codes stripped
+  matched: boolean;  // True if a line matched before the timeout
+  error: string | null;
 }
 
 export interface StartResult {
   success: boolean;
   pid: number | null;
   error: string | null;
+  error_code: ErrorCode | null;
+  requires_auth: boolean; // Stored auth status is unknown/unauthenticated
 }
 
 export interface StopResult {
   success: boolean;
   error: string | null;
+  timeout_secs: number | null;  // Effective wait between SIGTERM and a forced kill
+}
+
+export interface BulkOperationResult {
+  instance_id: string;
+  success: boolean;
+  error: string | null;
+}
+
+export interface BulkOperationProgress {
+  operation: "start" | "stop";
+  result: BulkOperationResult;
+  completed: number;
+  total: number;
+}
+
+export interface LaunchConfig {
+  instance_path: string;
+  working_dir: string;
+  java_path: string;          // Resolved executable ("java" when none was configured)
+  jvm_args: string | null;
+  server_args: string | null;
+  env_vars: Record<string, string>;
+  launch_wrapper: string | null;
+}
+
+export interface AuthCommandResult {
+  success: boolean;
+  error: string | null;
 }
 
 export interface AuthEvent {
@@ -161,6 +344,11 @@ export interface AuthSuccessEvent {
   auth_mode: string;  // e.g. "OAUTH_DEVICE"
 }
 
+export interface AuthFailedEvent {
+  instance_id: string;
+  message: string;
+}
+
 // Auth state for UI
 export type AuthStatus = "none" | "needs_auth" | "awaiting_code" | "authenticated";
 
@@ -192,10 +380,15 @@ export interface OnlinePlayersResponse {
 export interface ServerMetrics {
   instance_id: string;
   pid: number | null;
-  cpu_usage: number | null;
+  cpu_usage: number | null;             // Summed across cores, can exceed 100
+  cpu_usage_normalized: number | null;  // Share of the whole machine, 0-100
   memory_mb: number | null;
   memory_percent: number | null;
+  disk_read_bytes_per_sec: number | null;   // Null until a second sample exists or if the OS doesn't report it
+  disk_write_bytes_per_sec: number | null;
   uptime_seconds: number | null;
+  tps: number | null; // Last TPS reading parsed from the console
+  ready: boolean;  // Server has logged that it finished booting
   status: string;
 }
 
@@ -236,6 +429,29 @@ export interface LogFilesResult {
   error: string | null;
 }
 
+export interface LogRetentionResult {
+  success: boolean;
+  deleted_files: string[];
+  bytes_freed: number;
+  error: string | null;
+}
+
+export interface CompressLogsResult {
+  success: boolean;
+  compressed_files: string[];
+  bytes_saved: number;
+  error: string | null;
+}
+
+export interface LogFollowResult {
+  success: boolean;
+  lines: LogLine[];
+  dropped_lines: number;  // Discarded because the buffer filled up since the last poll
+  rotated: boolean;       // File shrank (rotated or truncated) since the last poll
+  file_size: number;
+  error: string | null;
+}
+
 // Version checking types
 export interface VersionSettings {
   check_on_startup: boolean;
@@ -243,6 +459,11 @@ export interface VersionSettings {
   check_on_server_start: boolean;
 }
 
+export interface LastVersionCheck {
+  available_version: string | null;
+  checked_at: string | null;  // RFC 3339
+}
+
 export interface VersionCheckResult {
   instance_id: string;
   instance_name: string;
@@ -257,6 +478,79 @@ export interface VersionUpdateEvent {
   available_version: string;
 }
 
+// Database backup types
+export interface DatabaseStatus {
+  available: boolean;
+  path: string;
+  error: string | null; // Set when the database failed to open at startup
+}
+
+export interface DatabaseBackupResult {
+  success: boolean;
+  path: string | null;
+  size_bytes: number | null;
+  error: string | null;
+}
+
+export interface DatabaseRestoreResult {
+  success: boolean;
+  restart_required: boolean;
+  error: string | null;
+}
+
+// Diagnostics types
+export type CheckStatus = "pass" | "warn" | "fail";
+
+export interface DiagnosticCheck {
+  id: string;          // server_jar, assets, java, config, port, disk, auth
+  label: string;
+  status: CheckStatus;
+  message: string;
+}
+
+export interface DiagnosticsReport {
+  success: boolean;
+  instance_id: string;
+  checks: DiagnosticCheck[];
+  overall: CheckStatus | null;  // Worst status across all checks
+  error: string | null;
+}
+
+export interface PathCheck {
+  path: string;       // Absolute path
+  exists: boolean;
+}
+
+export interface InstancePaths {
+  root: PathCheck;
+  server_dir: PathCheck;
+  server_jar: PathCheck;
+  assets: PathCheck;
+  universe: PathCheck;
+  logs: PathCheck;
+  missing: string[];  // e.g. ["Assets.zip"]
+}
+
+export interface InstancePathsCheck {
+  missing: string[];  // Ids of instances whose directory doesn't exist
+  paths: Record<string, InstancePaths>;
+}
+
+// Notification types
+export interface WebhookSettings {
+  webhook_url: string | null;
+  notify_server_started: boolean;
+  notify_server_stopped: boolean;
+  notify_server_crashed: boolean;
+  notify_player_joined: boolean;
+  notify_update_available: boolean;
+}
+
+export interface WebhookTestResult {
+  success: boolean;
+  error: string | null;
+}
+
 // ============================================================================
 // Config Files Types
 // ============================================================================
@@ -272,6 +566,7 @@ export interface JsonReadResult {
 export interface JsonWriteResult {
   success: boolean;
   error: string | null;
+  error_code: ErrorCode | null;
 }
 
 // Whitelist
@@ -356,6 +651,7 @@ export interface WorldInfo {
   world_gen_name: string | null;
   is_ticking: boolean | null;
   is_pvp_enabled: boolean | null;
+  parse_error: string | null;  // config.json didn't match the expected format
 }
 
 export interface WorldsListResult {
@@ -364,6 +660,12 @@ export interface WorldsListResult {
   error: string | null;
 }
 
+export interface BatchDeleteResult {
+  success: boolean;  // True only if every world was deleted
+  results: Record<string, JsonWriteResult>;
+  error: string | null;
+}
+
 export interface WorldUUID {
   $binary: string;
   $type: string;
@@ -408,3 +710,23 @@ export interface WorldConfigResult {
   raw: string | null;
   error: string | null;
 }
+
+export interface WorldSeedResult {
+  success: boolean;
+  seed: number | null; // The numeric seed that was stored
+  error: string | null;
+}
+
+export interface GameplayConfigsResult {
+  success: boolean;
+  configs: string[]; // Sorted preset names usable as GameplayConfig
+  complete: boolean; // False when Assets.zip couldn't be scanned
+  error: string | null;
+}
+
+export interface SaveWorldConfigResult {
+  success: boolean;
+  error: string | null;
+  error_code: ErrorCode | null;
+  warning: string | null; // Set when saved but e.g. GameplayConfig is unknown
+}